@@ -14,6 +14,10 @@ void main() {
 uniform sampler2D u_texture;
 uniform float u_saturation;
 uniform float u_blue;
+uniform float u_flash;
+uniform vec4 u_flash_color;
+uniform float u_tint;
+uniform vec4 u_tint_color;
 
 void main() {
     vec4 color = texture2D(u_texture, v_vt);
@@ -26,6 +30,12 @@ void main() {
     float greyscale = dot(color.rgb, vec3(0.2126, 0.7152, 0.0722));
     color.rgb = mix(vec3(greyscale), color.rgb, u_saturation);
 
+    // Tint toward a theme color
+    color.rgb = mix(color.rgb, u_tint_color.rgb * max(greyscale, 0.25), u_tint * 0.5);
+
+    // Flash
+    color.rgb = mix(color.rgb, u_flash_color.rgb, u_flash);
+
     gl_FragColor = color;
 }
 #endif
@@ -5,7 +5,7 @@ pub use self::options::*;
 use std::path::PathBuf;
 
 use ctl_core::{
-    model::{LightMode, ScoreGrade},
+    model::{HealthPreset, LightMode, ScoreGrade, TintColor},
     prelude::{Color, Modifier},
     types::FloatTime,
 };
@@ -291,6 +291,10 @@ impl Assets {
             }
             Modifier::LightMode(LightMode::Flashlight) => self.atlas.mod_flashlight(),
             Modifier::LightMode(LightMode::Spotlight) => self.atlas.mod_spotlight(),
+            Modifier::Health(HealthPreset::Lenient) => self.atlas.plus(),
+            Modifier::Health(HealthPreset::Normal) => self.atlas.circle(),
+            Modifier::Health(HealthPreset::Strict) => self.atlas.star(),
+            Modifier::Health(HealthPreset::SuddenDeath) => self.atlas.discard(),
         }
     }
 
@@ -203,6 +203,17 @@ pub enum ThemeColor {
     Highlight,
 }
 
+impl From<TintColor> for ThemeColor {
+    fn from(color: TintColor) -> Self {
+        match color {
+            TintColor::Dark => Self::Dark,
+            TintColor::Light => Self::Light,
+            TintColor::Danger => Self::Danger,
+            TintColor::Highlight => Self::Highlight,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct VolumeOptions {
@@ -23,8 +23,12 @@ mod inner {
     pub(super) use crate::{Result, error::*};
     pub(super) use ctl_core::{
         prelude::{DeserializeOwned, Id, MusicInfo, MusicUpdate, log, serde_json},
-        score::{ServerScore, SubmitScore},
-        types::{LevelInfo, LevelSetFull, LevelSetInfo, LevelSetsQuery, NewMusician},
+        score::{
+            FetchScoresQuery, PlayerRanking, RankingsQuery, ServerScore, SubmitScore, UserProfile,
+        },
+        types::{
+            LevelInfo, LevelSetFull, LevelSetInfo, LevelSetUpdate, LevelSetsQuery, NewMusician,
+        },
     };
     pub(super) use std::sync::atomic::AtomicBool;
 
@@ -101,10 +105,23 @@ mod inner {
             Ok(())
         }
 
-        pub async fn fetch_scores(&self, level: Id) -> Result<Vec<ServerScore>> {
+        pub async fn fetch_scores(
+            &self,
+            level: Id,
+            query: &FetchScoresQuery,
+        ) -> Result<Vec<ServerScore>> {
             let url = self.url.join(&format!("level/{level}/scores")).unwrap();
-            let req = self.client.get(url);
+            let req = self.client.get(url).query(query);
+
+            let response = self.send(req).await?;
+            let res = read_json(response).await?;
+            Ok(res)
+        }
 
+        /// Fetch a page of the global player ranking.
+        pub async fn get_rankings(&self, query: &RankingsQuery) -> Result<Vec<PlayerRanking>> {
+            let url = self.url.join("rankings").unwrap();
+            let req = self.client.get(url).query(query);
             let response = self.send(req).await?;
             let res = read_json(response).await?;
             Ok(res)
@@ -122,6 +139,10 @@ mod inner {
             Ok(())
         }
 
+        pub async fn get_user_profile(&self, user: Id) -> Result<UserProfile> {
+            self.get_json(&format!("user/{user}/profile")).await
+        }
+
         pub async fn get_level_info(&self, level: Id) -> Result<LevelInfo> {
             let url = self.url.join(&format!("level/{level}")).unwrap();
             let req = self.client.get(url);
@@ -158,6 +179,75 @@ mod inner {
             Ok(res)
         }
 
+        pub async fn update_group(&self, group: Id, update: &LevelSetUpdate) -> Result<()> {
+            let url = self.url.join(&format!("level_set/{group}")).unwrap();
+
+            let req = self.client.patch(url).json(update);
+
+            let response = self.send(req).await?;
+            get_body(response).await?;
+            Ok(())
+        }
+
+        /// Delete the group from the server, its scores are kept archived.
+        pub async fn delete_group(&self, group: Id) -> Result<()> {
+            let url = self.url.join(&format!("level_set/{group}")).unwrap();
+
+            let req = self.client.delete(url);
+
+            let response = self.send(req).await?;
+            get_body(response).await?;
+            Ok(())
+        }
+
+        /// Offer the ownership of the group to another user.
+        /// The group is transferred once they accept it with [`Self::accept_group_transfer`].
+        pub async fn transfer_group(&self, group: Id, new_owner: Id) -> Result<()> {
+            let url = self.url.join(&format!("level_set/{group}/owner")).unwrap();
+
+            let req = self.client.post(url).query(&[("id", new_owner)]);
+
+            let response = self.send(req).await?;
+            get_body(response).await?;
+            Ok(())
+        }
+
+        pub async fn accept_group_transfer(&self, group: Id) -> Result<()> {
+            let url = self
+                .url
+                .join(&format!("level_set/{group}/owner/accept"))
+                .unwrap();
+
+            let req = self.client.post(url);
+
+            let response = self.send(req).await?;
+            get_body(response).await?;
+            Ok(())
+        }
+
+        pub async fn recommended_add(&self, group: Id, ord: Option<i64>) -> Result<()> {
+            let url = self.url.join("level_sets/recommended").unwrap();
+
+            let mut req = self.client.post(url).query(&[("id", group)]);
+            if let Some(ord) = ord {
+                req = req.query(&[("ord", ord)]);
+            }
+
+            let response = self.send(req).await?;
+            get_body(response).await?;
+            Ok(())
+        }
+
+        pub async fn recommended_remove(&self, group: Id) -> Result<()> {
+            let url = self.url.join("level_sets/recommended").unwrap();
+
+            let req = self.client.delete(url).query(&[("id", group)]);
+
+            let response = self.send(req).await?;
+            get_body(response).await?;
+            Ok(())
+        }
+
         pub async fn get_group_info(&self, group: Id) -> Result<LevelSetInfo> {
             self.get_json(&format!("level_set/{group}")).await
         }
@@ -0,0 +1,207 @@
+//! Objective estimation of the level difficulty from its data.
+//!
+//! The analyzer walks through the light events of the level and measures
+//! how fast, dense and overlapping the lights are, how fast the music is,
+//! and how little time the player gets to react to them. The metrics are then combined into
+//! a single star rating.
+
+use crate::{
+    model::{Event, Level},
+    types::{FloatTime, Time, time_to_seconds},
+};
+
+use geng::prelude::*;
+use itertools::Itertools;
+
+/// Objective metrics describing the level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyMetrics {
+    /// Average speed of the lights in units per second.
+    pub light_speed: R32,
+    /// Speed of the fastest light in units per second.
+    pub peak_light_speed: R32,
+    /// Number of lights appearing per second.
+    pub light_density: R32,
+    /// Number of dangerous lights appearing per second.
+    pub danger_density: R32,
+    /// Average number of lights visible at the same time.
+    pub overlap: R32,
+    /// Average number of beats per second at the time the lights appear.
+    pub beat_density: R32,
+    /// Typical time in seconds between two consecutive lights appearing.
+    pub reaction_time: FloatTime,
+}
+
+/// The result of the difficulty analysis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LevelDifficulty {
+    pub metrics: DifficultyMetrics,
+    /// Combined rating of the difficulty, typically in range from 0 to 10.
+    pub stars: R32,
+}
+
+/// Reaction time considered as instantaneous, to avoid dividing by zero.
+const MIN_REACTION_TIME: f32 = 0.05;
+
+impl DifficultyMetrics {
+    /// Measure the difficulty metrics of the level.
+    pub fn measure(level: &Level) -> Self {
+        let mut expanded;
+        let level = if level.has_patterns() {
+            expanded = level.clone();
+            expanded.expand_patterns();
+            &expanded
+        } else {
+            level
+        };
+
+        let mut starts = Vec::new();
+        let mut lights = 0;
+        let mut dangers = 0;
+        let mut distance = R32::ZERO;
+        let mut light_time = Time::ZERO;
+        let mut peak_light_speed = R32::ZERO;
+        let mut beats_per_second = R32::ZERO;
+        for event in &level.events {
+            let Event::Light(light) = &event.event else {
+                continue;
+            };
+            lights += 1;
+            let toggles_danger = light
+                .movement
+                .waypoints
+                .iter()
+                .any(|waypoint| waypoint.danger == Some(true));
+            if light.danger || toggles_danger {
+                dangers += 1;
+            }
+
+            let duration = light.movement.duration();
+            let light_distance = light.movement.total_distance();
+            distance += light_distance;
+            light_time += duration;
+            if duration > Time::ZERO {
+                peak_light_speed = peak_light_speed.max(light_distance / time_to_seconds(duration));
+            }
+            starts.push(event.time);
+
+            let beat_time = level.timing.get_timing(event.time).beat_time;
+            if beat_time > FloatTime::ZERO {
+                beats_per_second += R32::ONE / beat_time;
+            }
+        }
+
+        let first = starts.iter().copied().min().unwrap_or(Time::ZERO);
+        let level_duration = time_to_seconds(level.last_time() - first).max(R32::ONE);
+
+        let light_speed = if light_time > Time::ZERO {
+            distance / time_to_seconds(light_time)
+        } else {
+            R32::ZERO
+        };
+
+        // Use the median gap between lights, ignoring the ones appearing together
+        starts.sort();
+        let mut gaps: Vec<Time> = starts
+            .iter()
+            .tuple_windows()
+            .map(|(a, b)| b - a)
+            .filter(|&gap| gap > Time::ZERO)
+            .collect();
+        gaps.sort();
+        let reaction_time = gaps
+            .get(gaps.len() / 2)
+            .map_or(level_duration, |&gap| time_to_seconds(gap));
+
+        Self {
+            light_speed,
+            peak_light_speed,
+            light_density: r32(lights as f32) / level_duration,
+            danger_density: r32(dangers as f32) / level_duration,
+            overlap: time_to_seconds(light_time) / level_duration,
+            beat_density: if lights > 0 {
+                beats_per_second / r32(lights as f32)
+            } else {
+                R32::ZERO
+            },
+            reaction_time,
+        }
+    }
+
+    /// Combine the metrics into a star rating.
+    pub fn stars(&self) -> R32 {
+        if self.light_density == R32::ZERO {
+            return R32::ZERO;
+        }
+
+        let density = 1.2 * self.light_density.as_f32().sqrt();
+        let speed = 0.15 * self.light_speed.as_f32() + 0.05 * self.peak_light_speed.as_f32();
+        let danger = 0.6 * self.danger_density.as_f32().sqrt();
+        let overlap = 0.3 * (self.overlap.as_f32() - 1.0).max(0.0);
+        let tempo = 0.4 * self.beat_density.as_f32().sqrt();
+        let reaction = 0.5 / self.reaction_time.as_f32().max(MIN_REACTION_TIME).sqrt();
+        r32(density + speed + danger + overlap + tempo + reaction)
+    }
+}
+
+impl LevelDifficulty {
+    /// Analyze the level and calculate its star rating.
+    pub fn analyze(level: &Level) -> Self {
+        let metrics = DifficultyMetrics::measure(level);
+        Self {
+            stars: metrics.stars(),
+            metrics,
+        }
+    }
+}
+
+#[cfg(test)]
+fn level_with_lights(count: usize, gap: Time) -> Level {
+    use crate::model::{LightEvent, Movement, Shape, TimedEvent};
+
+    let mut level = Level::new(r32(150.0));
+    level.events = (0..count)
+        .map(|i| TimedEvent {
+            time: i as Time * gap,
+            event: Event::Light(LightEvent {
+                danger: false,
+                group: None,
+                sustain: false,
+                shape: Shape::circle(r32(1.0)),
+                movement: Movement::default(),
+            }),
+            envelope: None,
+        })
+        .collect();
+    level
+}
+
+#[test]
+fn test_empty_level() {
+    let level = Level::new(r32(150.0));
+    assert_eq!(LevelDifficulty::analyze(&level).stars, R32::ZERO);
+}
+
+#[test]
+fn test_denser_is_harder() {
+    let easy = LevelDifficulty::analyze(&level_with_lights(20, 2000));
+    let hard = LevelDifficulty::analyze(&level_with_lights(80, 500));
+    assert!(hard.metrics.light_density > easy.metrics.light_density);
+    assert!(hard.metrics.reaction_time < easy.metrics.reaction_time);
+    assert!(hard.stars > easy.stars);
+}
+
+#[test]
+fn test_faster_bpm_is_harder() {
+    use crate::model::Timing;
+
+    let mut slow = level_with_lights(40, 1000);
+    slow.timing = Timing::new(r32(100.0));
+    let mut fast = level_with_lights(40, 1000);
+    fast.timing = Timing::new(r32(200.0));
+
+    let slow = LevelDifficulty::analyze(&slow);
+    let fast = LevelDifficulty::analyze(&fast);
+    assert!(fast.metrics.beat_density > slow.metrics.beat_density);
+    assert!(fast.stars > slow.stars);
+}
@@ -214,6 +214,8 @@ fn convert_level(beat_time: crate::FloatTime, value: Level) -> crate::Level {
                 event: match event.event {
                     Event::Light(light) => crate::Event::Light(crate::LightEvent {
                         danger: light.light.danger,
+                        group: None,
+                        sustain: false,
                         shape: match light.light.shape {
                             Shape::Circle { radius } => crate::Shape::Circle { radius },
                             Shape::Line { width } => crate::Shape::Line { width },
@@ -257,6 +259,8 @@ fn convert_level(beat_time: crate::FloatTime, value: Level) -> crate::Level {
                                     interpolation: crate::MoveInterpolation::default(),
                                     change_curve: None,
                                     transform: movement.initial.into(),
+                                    danger: None,
+                                    shape: None,
                                 });
                                 for i in 1..waypoints.len() {
                                     let time = waypoints.get(i + 1).map_or_else(
@@ -275,11 +279,14 @@ fn convert_level(beat_time: crate::FloatTime, value: Level) -> crate::Level {
                         ))
                     }
                 },
+                envelope: None,
             })
             .collect(),
         timing: crate::Timing {
             points: vec![crate::TimingPoint { time: 0, beat_time }],
         },
+        groups: Vec::new(),
+        health_sections: Vec::new(),
     }
 }
 
@@ -289,7 +296,9 @@ impl From<Transform> for crate::TransformLight {
             translation: value.translation,
             rotation: value.rotation,
             scale: value.scale,
+            stretch: vec2::splat(R32::ONE),
             hollow: r32(-1.0),
+            pulse: R32::ZERO,
         }
     }
 }
@@ -300,6 +309,8 @@ fn convert_frame(beat_time: crate::FloatTime, value: MoveFrame) -> crate::Waypoi
         interpolation: crate::MoveInterpolation::default(),
         change_curve: None, // Linear
         transform: value.transform.into(),
+        danger: None,
+        shape: None,
     }
 }
 
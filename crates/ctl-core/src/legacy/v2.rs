@@ -354,6 +354,8 @@ fn convert_level(value: Level) -> crate::Level {
                 event: match event.event {
                     Event::Light(light) => crate::Event::Light(crate::LightEvent {
                         danger: light.danger,
+                        group: None,
+                        sustain: false,
                         shape: match light.shape {
                             Shape::Circle { radius } => crate::Shape::Circle { radius },
                             Shape::Line { width } => crate::Shape::Line { width },
@@ -403,6 +405,8 @@ fn convert_level(value: Level) -> crate::Level {
                                         *curve != crate::TrajectoryInterpolation::default()
                                     }),
                                     transform: light.movement.initial.into(),
+                                    danger: None,
+                                    shape: None,
                                 });
                                 for i in 1..waypoints.len() {
                                     let time = waypoints.get(i + 1).map_or_else(
@@ -431,9 +435,12 @@ fn convert_level(value: Level) -> crate::Level {
                         }
                     }),
                 },
+                envelope: None,
             })
             .collect(),
         timing: value.timing.into(),
+        groups: Vec::new(),
+        health_sections: Vec::new(),
     }
 }
 
@@ -443,7 +450,9 @@ impl From<Transform> for crate::TransformLight {
             translation: value.translation,
             rotation: value.rotation,
             scale: value.scale,
+            stretch: vec2::splat(R32::ONE),
             hollow: r32(-1.0),
+            pulse: R32::ZERO,
         }
     }
 }
@@ -455,6 +464,8 @@ impl From<MoveFrame> for crate::Waypoint {
             interpolation: value.interpolation.into(),
             change_curve: value.change_curve.map(Into::into),
             transform: value.transform.into(),
+            danger: None,
+            shape: None,
         }
     }
 }
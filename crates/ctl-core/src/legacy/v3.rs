@@ -390,6 +390,8 @@ fn convert_level(value: Level) -> crate::Level {
                 event: match event.event {
                     Event::Light(light) => crate::Event::Light(crate::LightEvent {
                         danger: light.danger,
+                        group: None,
+                        sustain: false,
                         shape: match light.shape {
                             Shape::Circle { radius } => crate::Shape::Circle { radius },
                             Shape::Line { width } => crate::Shape::Line { width },
@@ -427,9 +429,12 @@ fn convert_level(value: Level) -> crate::Level {
                         }
                     }),
                 },
+                envelope: None,
             })
             .collect(),
         timing: value.timing.into(),
+        groups: Vec::new(),
+        health_sections: Vec::new(),
     }
 }
 
@@ -439,7 +444,9 @@ impl From<TransformLight> for crate::TransformLight {
             translation: value.translation,
             rotation: value.rotation,
             scale: value.scale,
+            stretch: vec2::splat(R32::ONE),
             hollow: value.hollow,
+            pulse: R32::ZERO,
         }
     }
 }
@@ -451,6 +458,8 @@ impl From<Waypoint> for crate::Waypoint {
             interpolation: value.interpolation.into(),
             change_curve: value.change_curve.map(Into::into),
             transform: value.transform.into(),
+            danger: None,
+            shape: None,
         }
     }
 }
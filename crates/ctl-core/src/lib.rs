@@ -1,8 +1,12 @@
 pub mod auth;
+pub mod difficulty;
 pub mod interpolation;
 pub mod legacy;
 pub mod model;
+pub mod rating;
+pub mod replay;
 pub mod score;
+pub mod simulation;
 pub mod types;
 pub mod util;
 
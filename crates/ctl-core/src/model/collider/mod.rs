@@ -41,7 +41,10 @@ impl Collider {
         Self {
             position: self.position + transform.translation,
             rotation: self.rotation + transform.rotation,
-            shape: self.shape.scaled(transform.scale),
+            shape: self
+                .shape
+                .scaled(transform.scale)
+                .stretched(transform.stretch),
         }
     }
 
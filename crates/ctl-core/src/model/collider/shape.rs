@@ -1,10 +1,125 @@
 use super::*;
 
+use itertools::Itertools;
+
+/// Maximum number of vertices in a [`Shape::Convex`] polygon.
+pub const MAX_CONVEX_POINTS: usize = 8;
+/// Number of segments used to approximate a full ring.
+const RING_SEGMENTS: usize = 32;
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Shape {
-    Circle { radius: Coord },
-    Line { width: Coord },
-    Rectangle { width: Coord, height: Coord },
+    Circle {
+        radius: Coord,
+    },
+    Line {
+        width: Coord,
+    },
+    Rectangle {
+        width: Coord,
+        height: Coord,
+    },
+    /// Regular polygon (triangle, square, hexagon, etc) with one vertex pointing up.
+    Polygon {
+        /// Radius of the circumscribed circle.
+        radius: Coord,
+        sides: u8,
+    },
+    /// A part of a ring, symmetric around the positive X axis.
+    Arc {
+        /// Radius of the middle of the ring.
+        radius: Coord,
+        /// Thickness of the ring.
+        width: Coord,
+        /// Angular span in degrees, 360 or more makes a complete ring.
+        angle: Coord,
+    },
+    /// Arbitrary convex polygon.
+    Convex {
+        points: ConvexPoints,
+    },
+    /// Circle stretched along the axes.
+    Ellipse {
+        width: Coord,
+        height: Coord,
+    },
+}
+
+/// Vertices of a convex polygon, centered around the origin and ordered counter-clockwise.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "Vec<vec2<Coord>>", into = "Vec<vec2<Coord>>")]
+pub struct ConvexPoints {
+    len: u8,
+    points: [vec2<Coord>; MAX_CONVEX_POINTS],
+}
+
+impl ConvexPoints {
+    /// Construct the convex hull of the points and center it around the origin.
+    /// Returns `None` if the hull is degenerate or has too many vertices.
+    pub fn new(points: &[vec2<Coord>]) -> Option<Self> {
+        let points: Vec<_> = points
+            .iter()
+            .map(|p| parry2d::math::Point::new(p.x.as_f32(), p.y.as_f32()))
+            .collect();
+        let hull = parry2d::shape::ConvexPolygon::from_convex_hull(&points)?;
+        let hull = hull.points();
+        if hull.len() < 3 || hull.len() > MAX_CONVEX_POINTS {
+            return None;
+        }
+
+        let center =
+            hull.iter().fold(vec2::ZERO, |acc, p| acc + vec2(p.x, p.y)) / hull.len() as f32;
+        let mut result = Self {
+            len: hull.len() as u8,
+            points: [vec2::ZERO; MAX_CONVEX_POINTS],
+        };
+        for (target, p) in result.points.iter_mut().zip(hull) {
+            *target = (vec2(p.x, p.y) - center).as_r32();
+        }
+        Some(result)
+    }
+
+    pub fn points(&self) -> &[vec2<Coord>] {
+        &self.points[..self.len as usize]
+    }
+
+    fn map(self, f: impl Fn(vec2<Coord>) -> vec2<Coord>) -> Self {
+        let mut result = self;
+        for point in &mut result.points[..self.len as usize] {
+            *point = f(*point);
+        }
+        result
+    }
+
+    /// Combine the points pairwise, if both polygons have the same number of vertices.
+    fn zip_with(
+        self,
+        other: Self,
+        f: impl Fn(vec2<Coord>, vec2<Coord>) -> vec2<Coord>,
+    ) -> Option<Self> {
+        if self.len != other.len {
+            return None;
+        }
+        let mut result = self;
+        for (point, &other) in result.points.iter_mut().zip(&other.points) {
+            *point = f(*point, other);
+        }
+        Some(result)
+    }
+}
+
+impl TryFrom<Vec<vec2<Coord>>> for ConvexPoints {
+    type Error = &'static str;
+
+    fn try_from(points: Vec<vec2<Coord>>) -> Result<Self, Self::Error> {
+        Self::new(&points).ok_or("expected a non-degenerate convex polygon with at most 8 vertices")
+    }
+}
+
+impl From<ConvexPoints> for Vec<vec2<Coord>> {
+    fn from(points: ConvexPoints) -> Self {
+        points.points().to_vec()
+    }
 }
 
 impl Shape {
@@ -23,6 +138,102 @@ impl Shape {
         }
     }
 
+    pub fn polygon(radius: Coord, sides: u8) -> Self {
+        Self::Polygon {
+            radius,
+            sides: sides.max(3),
+        }
+    }
+
+    pub fn ellipse(size: vec2<Coord>) -> Self {
+        Self::Ellipse {
+            width: size.x,
+            height: size.y,
+        }
+    }
+
+    pub fn ring(radius: Coord, width: Coord) -> Self {
+        Self::Arc {
+            radius,
+            width,
+            angle: r32(360.0),
+        }
+    }
+
+    /// Radius of the smallest circle around the origin that contains the shape.
+    /// Lines are infinitely long, so only their half width is taken into account.
+    pub fn bounding_radius(&self) -> Coord {
+        match *self {
+            Shape::Circle { radius } => radius,
+            Shape::Line { width } => width / r32(2.0),
+            Shape::Rectangle { width, height } => vec2(width, height).len() / r32(2.0),
+            Shape::Polygon { radius, .. } => radius,
+            Shape::Arc { radius, width, .. } => radius + width / r32(2.0),
+            Shape::Convex { points } => points
+                .points()
+                .iter()
+                .map(|point| point.len())
+                .max()
+                .unwrap_or(Coord::ZERO),
+            Shape::Ellipse { width, height } => width.max(height) / r32(2.0),
+        }
+    }
+
+    /// Returns the vertices of the polygonal shapes in counter-clockwise order.
+    pub fn polygon_points(&self) -> Option<Vec<vec2<Coord>>> {
+        match *self {
+            Shape::Rectangle { width, height } => Some(
+                Aabb2::ZERO
+                    .extend_symmetric(vec2(width, height) / r32(2.0))
+                    .corners()
+                    .to_vec(),
+            ),
+            Shape::Polygon { radius, sides } => {
+                let sides = sides.max(3);
+                Some(
+                    (0..sides)
+                        .map(|i| {
+                            let angle =
+                                Angle::from_degrees(r32(90.0 + 360.0 * i as f32 / sides as f32));
+                            angle.unit_vec() * radius
+                        })
+                        .collect(),
+                )
+            }
+            Shape::Convex { points } => Some(points.points().to_vec()),
+            Shape::Ellipse { width, height } => {
+                let radius = vec2(width, height) / r32(2.0);
+                Some(
+                    (0..RING_SEGMENTS)
+                        .map(|i| {
+                            let angle =
+                                Angle::from_degrees(r32(360.0 * i as f32 / RING_SEGMENTS as f32));
+                            angle.unit_vec() * radius
+                        })
+                        .collect(),
+                )
+            }
+            Shape::Circle { .. } | Shape::Line { .. } | Shape::Arc { .. } => None,
+        }
+    }
+
+    /// Returns the points along the middle line of the arc, from one end to the other.
+    pub fn arc_points(&self) -> Option<Vec<vec2<Coord>>> {
+        let Shape::Arc { radius, angle, .. } = *self else {
+            return None;
+        };
+        let angle = angle.clamp(R32::ZERO, r32(360.0));
+        let segments = ((angle.as_f32() / 360.0 * RING_SEGMENTS as f32).ceil() as usize).max(1);
+        Some(
+            (0..=segments)
+                .map(|i| {
+                    let t = i as f32 / segments as f32 - 0.5;
+                    Angle::from_degrees(angle * r32(t)).unit_vec() * radius
+                })
+                .collect(),
+        )
+    }
+
     pub fn to_parry(self) -> Box<dyn parry2d::shape::Shape> {
         match self {
             Shape::Circle { radius } => Box::new(parry2d::shape::Ball::new(radius.as_f32())),
@@ -31,20 +242,78 @@ impl Shape {
                 height: width,
             }
             .to_parry(),
-            Shape::Rectangle { width, height } => {
-                if width == R32::ZERO || height == R32::ZERO {
-                    return Box::new(parry2d::shape::Ball::new(0.0));
-                }
-                let aabb = Aabb2::ZERO.extend_symmetric(vec2(width, height).as_f32() / 2.0);
-                let points = aabb.corners().map(|p| {
-                    let vec2(x, y) = p;
-                    parry2d::math::Point::new(x, y)
-                });
+            Shape::Rectangle { width, height } if width == R32::ZERO || height == R32::ZERO => {
+                Box::new(parry2d::shape::Ball::new(0.0))
+            }
+            Shape::Rectangle { .. }
+            | Shape::Polygon { .. }
+            | Shape::Convex { .. }
+            | Shape::Ellipse { .. } => {
+                let points: Vec<_> = self
+                    .polygon_points()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|vec2(x, y)| parry2d::math::Point::new(x.as_f32(), y.as_f32()))
+                    .collect();
                 match parry2d::shape::ConvexPolygon::from_convex_hull(&points) {
                     Some(shape) => Box::new(shape),
                     None => Box::new(parry2d::shape::Ball::new(0.0)),
                 }
             }
+            Shape::Arc { width, .. } => {
+                // Approximate with convex pieces of the ring
+                let points = self.arc_points().unwrap_or_default();
+                let half_width = width.as_f32() / 2.0;
+                let parts: Vec<_> = points
+                    .iter()
+                    .tuple_windows()
+                    .filter_map(|(a, b)| {
+                        let (a, b) = (a.as_f32(), b.as_f32());
+                        let (na, nb) = (a.normalize_or_zero(), b.normalize_or_zero());
+                        let corners = [
+                            a - na * half_width,
+                            a + na * half_width,
+                            b + nb * half_width,
+                            b - nb * half_width,
+                        ]
+                        .map(|vec2(x, y)| parry2d::math::Point::new(x, y));
+                        let part = parry2d::shape::ConvexPolygon::from_convex_hull(&corners)?;
+                        Some((
+                            parry2d::math::Isometry::identity(),
+                            parry2d::shape::SharedShape::new(part),
+                        ))
+                    })
+                    .collect();
+                if parts.is_empty() {
+                    return Box::new(parry2d::shape::Ball::new(0.0));
+                }
+                Box::new(parry2d::shape::Compound::new(parts))
+            }
+        }
+    }
+
+    /// A small shape of the same kind used to visualize the waypoints of the light.
+    pub fn waypoint_marker(self, radius: Coord) -> Self {
+        match self {
+            Shape::Circle { .. } | Shape::Arc { .. } | Shape::Ellipse { .. } => {
+                Shape::circle(radius)
+            }
+            Shape::Line { .. } => Shape::line(radius / r32(2.0)),
+            Shape::Rectangle { .. } => Shape::rectangle(vec2::splat(radius)),
+            Shape::Polygon { sides, .. } => Shape::polygon(radius, sides),
+            Shape::Convex { points } => {
+                let size = points
+                    .points()
+                    .iter()
+                    .map(|p| p.len())
+                    .max()
+                    .unwrap_or(Coord::ONE);
+                if size == Coord::ZERO {
+                    Shape::circle(radius)
+                } else {
+                    self.scaled(radius / size)
+                }
+            }
         }
     }
 
@@ -60,6 +329,232 @@ impl Shape {
                 width: width * scale,
                 height: height * scale,
             },
+            Shape::Polygon { radius, sides } => Shape::Polygon {
+                radius: radius * scale,
+                sides,
+            },
+            Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => Shape::Arc {
+                radius: radius * scale,
+                width: width * scale,
+                angle,
+            },
+            Shape::Convex { points } => Shape::Convex {
+                points: points.map(|p| p * scale),
+            },
+            Shape::Ellipse { width, height } => Shape::Ellipse {
+                width: width * scale,
+                height: height * scale,
+            },
+        }
+    }
+
+    /// Scale the shape separately along its local axes.
+    /// Circles turn into ellipses and regular polygons into convex ones.
+    /// Shapes that cannot be stretched (arcs and polygons with too many sides)
+    /// are scaled uniformly by the average instead.
+    pub fn stretched(self, stretch: vec2<Coord>) -> Self {
+        let stretch = stretch.map(|x| x.max(Coord::ZERO));
+        if stretch.x == stretch.y {
+            return self.scaled(stretch.x);
+        }
+        let uniform = (stretch.x + stretch.y) / r32(2.0);
+        match self {
+            Shape::Circle { radius } => Shape::ellipse(vec2::splat(radius * r32(2.0)) * stretch),
+            Shape::Line { width } => Shape::Line {
+                width: width * stretch.y,
+            },
+            Shape::Rectangle { width, height } => Shape::rectangle(vec2(width, height) * stretch),
+            Shape::Polygon { .. } => {
+                let points = self.polygon_points().unwrap_or_default();
+                match ConvexPoints::new(&points) {
+                    Some(points) => Shape::Convex {
+                        points: points.map(|p| p * stretch),
+                    },
+                    None => self.scaled(uniform),
+                }
+            }
+            Shape::Arc { .. } => self.scaled(uniform),
+            Shape::Convex { points } => Shape::Convex {
+                points: points.map(|p| p * stretch),
+            },
+            Shape::Ellipse { width, height } => Shape::ellipse(vec2(width, height) * stretch),
+        }
+    }
+}
+
+/// Shapes of the same kind are interpolated by their parameters,
+/// while different kinds keep the first shape until the next waypoint.
+impl Interpolatable for Shape {
+    fn add(self, other: Self) -> Self {
+        self.combine(other, |a, b| a + b)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.combine(other, |a, b| a - b)
+    }
+
+    fn scale(self, factor: f32) -> Self {
+        let factor = r32(factor);
+        match self {
+            Shape::Circle { radius } => Shape::Circle {
+                radius: radius * factor,
+            },
+            Shape::Line { width } => Shape::Line {
+                width: width * factor,
+            },
+            Shape::Rectangle { width, height } => Shape::Rectangle {
+                width: width * factor,
+                height: height * factor,
+            },
+            Shape::Polygon { radius, sides } => Shape::Polygon {
+                radius: radius * factor,
+                sides,
+            },
+            Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => Shape::Arc {
+                radius: radius * factor,
+                width: width * factor,
+                angle: angle * factor,
+            },
+            Shape::Convex { points } => Shape::Convex {
+                points: points.map(|p| p * factor),
+            },
+            Shape::Ellipse { width, height } => Shape::Ellipse {
+                width: width * factor,
+                height: height * factor,
+            },
+        }
+    }
+
+    fn length_sqr(self) -> f32 {
+        match self {
+            Shape::Circle { radius } => radius.length_sqr(),
+            Shape::Line { width } => width.length_sqr(),
+            Shape::Rectangle { width, height } | Shape::Ellipse { width, height } => {
+                width.length_sqr() + height.length_sqr()
+            }
+            Shape::Polygon { radius, .. } => radius.length_sqr(),
+            Shape::Arc {
+                radius,
+                width,
+                angle,
+            } => radius.length_sqr() + width.length_sqr() + angle.length_sqr(),
+            Shape::Convex { points } => points.points().iter().map(|p| p.length_sqr()).sum(),
+        }
+    }
+}
+
+impl Shape {
+    /// Combine the parameters of two shapes of the same kind.
+    /// Returns `self` if the kinds are different.
+    fn combine(self, other: Self, f: impl Fn(Coord, Coord) -> Coord) -> Self {
+        match (self, other) {
+            (Shape::Circle { radius: a }, Shape::Circle { radius: b }) => {
+                Shape::Circle { radius: f(a, b) }
+            }
+            (Shape::Line { width: a }, Shape::Line { width: b }) => Shape::Line { width: f(a, b) },
+            (
+                Shape::Rectangle {
+                    width: aw,
+                    height: ah,
+                },
+                Shape::Rectangle {
+                    width: bw,
+                    height: bh,
+                },
+            ) => Shape::Rectangle {
+                width: f(aw, bw),
+                height: f(ah, bh),
+            },
+            (
+                Shape::Polygon {
+                    radius: a,
+                    sides: a_sides,
+                },
+                Shape::Polygon {
+                    radius: b,
+                    sides: b_sides,
+                },
+            ) if a_sides == b_sides => Shape::Polygon {
+                radius: f(a, b),
+                sides: a_sides,
+            },
+            (
+                Shape::Arc {
+                    radius: ar,
+                    width: aw,
+                    angle: aa,
+                },
+                Shape::Arc {
+                    radius: br,
+                    width: bw,
+                    angle: ba,
+                },
+            ) => Shape::Arc {
+                radius: f(ar, br),
+                width: f(aw, bw),
+                angle: f(aa, ba),
+            },
+            (Shape::Convex { points: a }, Shape::Convex { points: b }) => a
+                .zip_with(b, |a, b| vec2(f(a.x, b.x), f(a.y, b.y)))
+                .map_or(self, |points| Shape::Convex { points }),
+            (
+                Shape::Ellipse {
+                    width: aw,
+                    height: ah,
+                },
+                Shape::Ellipse {
+                    width: bw,
+                    height: bh,
+                },
+            ) => Shape::Ellipse {
+                width: f(aw, bw),
+                height: f(ah, bh),
+            },
+            _ => self,
         }
     }
 }
+
+#[test]
+fn test_convex_points_centered() {
+    let points = [
+        vec2(0.0, 0.0),
+        vec2(2.0, 0.0),
+        vec2(2.0, 2.0),
+        vec2(0.0, 2.0),
+        vec2(1.0, 1.0),
+    ]
+    .map(|p: vec2<f32>| p.as_r32());
+    let convex = ConvexPoints::new(&points).unwrap();
+    assert_eq!(convex.points().len(), 4);
+    let center = convex.points().iter().fold(vec2::ZERO, |acc, &p| acc + p);
+    assert_eq!(center, vec2::ZERO);
+}
+
+#[test]
+fn test_stretched_circle() {
+    let shape = Shape::circle(r32(1.0)).stretched(vec2(2.0, 0.5).as_r32());
+    assert_eq!(shape, Shape::ellipse(vec2(4.0, 1.0).as_r32()));
+    let shape = Shape::circle(r32(1.0)).stretched(vec2::splat(r32(2.0)));
+    assert_eq!(shape, Shape::circle(r32(2.0)));
+}
+
+#[test]
+fn test_shape_interpolation() {
+    let a = Shape::rectangle(vec2(1.0, 2.0).as_r32());
+    let b = Shape::rectangle(vec2(3.0, 2.0).as_r32());
+    let mid = a.add(b.sub(a).scale(0.5));
+    assert_eq!(mid, Shape::rectangle(vec2(2.0, 2.0).as_r32()));
+
+    // Different kinds do not morph
+    let c = Shape::circle(r32(1.0));
+    assert_eq!(a.add(c.sub(a).scale(0.5)), a);
+}
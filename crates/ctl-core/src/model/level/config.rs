@@ -1,11 +1,17 @@
 use super::*;
 
+/// Overlap depth at which the depth drain reaches its full rate.
+const DEPTH_DRAIN_FULL: f32 = 0.5;
+/// Relative drain rate when barely touching a dangerous light with the depth drain.
+const DEPTH_DRAIN_MIN: f32 = 0.2;
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct LevelConfig {
     pub player: PlayerConfig,
     pub health: HealthConfig,
     pub modifiers: LevelModifiers,
+    pub judgement: JudgementConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -24,6 +30,34 @@ pub struct HealthConfig {
     pub danger_decrease_rate: FloatTime,
     /// How much health restores per second while in light.
     pub restore_rate: FloatTime,
+    /// Drain health in danger proportionally to how deep the player is inside the light.
+    pub depth_drain: bool,
+    /// Any contact with a dangerous light is fatal.
+    pub sudden_death: bool,
+}
+
+/// Named presets of the [`HealthConfig`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HealthPreset {
+    Lenient,
+    Normal,
+    Strict,
+    SuddenDeath,
+}
+
+/// Timing windows for judging the waypoint hits.
+/// Hit offsets are signed: negative when early and positive when late.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct JudgementConfig {
+    /// Max absolute offset of a perfect hit.
+    pub perfect: Time,
+    /// Max absolute offset of a great hit.
+    pub great: Time,
+    /// How early a waypoint can still be hit.
+    pub early: Time,
+    /// How late a waypoint can still be hit.
+    pub late: Time,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -84,11 +118,18 @@ pub enum Modifier {
     Touch,
     TimeScale(FloatTime),
     LightMode(LightMode),
+    Health(HealthPreset),
 }
 
 impl Modifier {
     pub fn multiplier(&self) -> R32 {
         match self {
+            // NOTE: Health presets only affect the performance points,
+            // scores are ranked in separate categories instead
+            Modifier::Health(HealthPreset::Lenient) => r32(0.8),
+            Modifier::Health(HealthPreset::Normal) => r32(1.0),
+            Modifier::Health(HealthPreset::Strict) => r32(1.05),
+            Modifier::Health(HealthPreset::SuddenDeath) => r32(1.1),
             Modifier::NoFail => r32(0.8),
             Modifier::Sudden => r32(1.15),
             Modifier::Hidden => r32(1.1),
@@ -124,6 +165,12 @@ impl Modifier {
             Modifier::LightMode(LightMode::Spotlight) => {
                 "the lights are spot on!\nvision is limited"
             }
+            Modifier::Health(HealthPreset::Lenient) => {
+                "health drains slower\ngrazing danger is forgiven"
+            }
+            Modifier::Health(HealthPreset::Normal) => "the usual health",
+            Modifier::Health(HealthPreset::Strict) => "health drains faster\nand restores slower",
+            Modifier::Health(HealthPreset::SuddenDeath) => "touching danger is fatal",
         }
     }
 }
@@ -144,6 +191,7 @@ impl Display for Modifier {
             }
             Modifier::LightMode(LightMode::Flashlight) => write!(f, "Flashlight"),
             Modifier::LightMode(LightMode::Spotlight) => write!(f, "Spotlight"),
+            Modifier::Health(preset) => write!(f, "{preset}"),
         }
     }
 }
@@ -157,6 +205,7 @@ impl LevelModifiers {
             Modifier::Touch => Some(&mut self.touch),
             Modifier::TimeScale(_) => None,
             Modifier::LightMode(_) => None,
+            Modifier::Health(_) => None,
         }
     }
 }
@@ -176,6 +225,37 @@ impl Default for LevelModifiers {
     }
 }
 
+impl Default for JudgementConfig {
+    fn default() -> Self {
+        Self {
+            perfect: TIME_IN_FLOAT_TIME * 25 / 1000, // 25ms
+            great: TIME_IN_FLOAT_TIME * 50 / 1000,   // 50ms
+            early: BUFFER_TIME,
+            late: COYOTE_TIME,
+        }
+    }
+}
+
+impl JudgementConfig {
+    /// Whether a hit with the given offset is within the timing windows.
+    pub fn contains(&self, offset: Time) -> bool {
+        offset > -self.early && offset < self.late
+    }
+
+    /// Judge a hit with the given signed offset.
+    pub fn judge(&self, offset: Time) -> Judgement {
+        if offset.abs() <= self.perfect {
+            Judgement::Perfect
+        } else if offset.abs() <= self.great {
+            Judgement::Great
+        } else if self.contains(offset) {
+            Judgement::Ok
+        } else {
+            Judgement::Miss
+        }
+    }
+}
+
 impl Default for PlayerConfig {
     fn default() -> Self {
         Self { radius: r32(0.5) }
@@ -183,14 +263,16 @@ impl Default for PlayerConfig {
 }
 
 impl HealthConfig {
-    // pub fn preset_easy() -> Self {
-    //     Self {
-    //         max: r32(1.0),
-    //         dark_decrease_rate: r32(0.3),
-    //         danger_decrease_rate: r32(0.5),
-    //         restore_rate: r32(0.5),
-    //     }
-    // }
+    pub fn preset_lenient() -> Self {
+        Self {
+            max: r32(1.0),
+            dark_decrease_rate: r32(0.4),
+            danger_decrease_rate: r32(1.0),
+            restore_rate: r32(0.5),
+            depth_drain: true,
+            sudden_death: false,
+        }
+    }
 
     pub fn preset_normal() -> Self {
         Self {
@@ -198,17 +280,73 @@ impl HealthConfig {
             dark_decrease_rate: r32(0.7),
             danger_decrease_rate: r32(1.5),
             restore_rate: r32(0.4),
+            depth_drain: false,
+            sudden_death: false,
+        }
+    }
+
+    pub fn preset_strict() -> Self {
+        Self {
+            max: r32(1.0),
+            dark_decrease_rate: r32(1.0),
+            danger_decrease_rate: r32(2.0),
+            restore_rate: r32(0.25),
+            depth_drain: false,
+            sudden_death: false,
+        }
+    }
+
+    pub fn preset_sudden_death() -> Self {
+        Self {
+            sudden_death: true,
+            ..Self::preset_strict()
         }
     }
 
-    // pub fn preset_hard() -> Self {
-    //     Self {
-    //         max: r32(1.0),
-    //         dark_decrease_rate: r32(1.0),
-    //         danger_decrease_rate: r32(2.0),
-    //         restore_rate: r32(0.25),
-    //     }
-    // }
+    /// Find the preset matching this config exactly.
+    pub fn preset(&self) -> Option<HealthPreset> {
+        HealthPreset::ALL
+            .into_iter()
+            .find(|preset| preset.config() == *self)
+    }
+
+    /// Health drain per second in danger, given the `distance` to the center
+    /// of the dangerous light and the `depth` from its edge.
+    pub fn danger_drain(&self, distance: Coord, depth: Coord) -> FloatTime {
+        if self.sudden_death {
+            return self.max * r32(TIME_IN_FLOAT_TIME as f32);
+        }
+        let multiplier = if self.depth_drain {
+            (depth / r32(DEPTH_DRAIN_FULL)).clamp(r32(DEPTH_DRAIN_MIN), r32(1.0))
+        } else {
+            (r32(1.0) - distance + r32(0.5)).min(r32(1.0))
+        };
+        self.danger_decrease_rate * multiplier
+    }
+}
+
+impl HealthPreset {
+    pub const ALL: [Self; 4] = [Self::Lenient, Self::Normal, Self::Strict, Self::SuddenDeath];
+
+    pub fn config(self) -> HealthConfig {
+        match self {
+            Self::Lenient => HealthConfig::preset_lenient(),
+            Self::Normal => HealthConfig::preset_normal(),
+            Self::Strict => HealthConfig::preset_strict(),
+            Self::SuddenDeath => HealthConfig::preset_sudden_death(),
+        }
+    }
+}
+
+impl Display for HealthPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Lenient => write!(f, "Lenient"),
+            Self::Normal => write!(f, "Normal"),
+            Self::Strict => write!(f, "Strict"),
+            Self::SuddenDeath => write!(f, "Sudden Death"),
+        }
+    }
 }
 
 impl Default for HealthConfig {
@@ -216,3 +354,16 @@ impl Default for HealthConfig {
         Self::preset_normal()
     }
 }
+
+#[test]
+fn test_health_presets() {
+    for preset in HealthPreset::ALL {
+        let config = preset.config();
+        assert_eq!(config.preset(), Some(preset));
+
+        // Replays and level sets are sent as bincode, which has no way to skip fields
+        let bytes = bincode::serialize(&config).unwrap();
+        let decoded: HealthConfig = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(decoded, config);
+    }
+}
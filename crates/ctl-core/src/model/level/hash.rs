@@ -0,0 +1,147 @@
+//! Stable binary representation of the level used for [`Level::calculate_hash`].
+//!
+//! Fields added after the hashes were first published are only written
+//! when they differ from their defaults (each prefixed with a tag),
+//! so that old levels keep the hash they are known by on the server.
+
+use super::*;
+
+use ::serde::{Serializer, ser::SerializeTuple};
+
+/// Serializes the wrapped value in the stable layout.
+pub(super) struct Stable<'a, T>(pub &'a T);
+
+/// Serializes the items of a collection in the stable layout.
+struct StableSeq<I>(I);
+
+impl<'a, T: 'a, I> Serialize for StableSeq<I>
+where
+    I: IntoIterator<Item = &'a T> + Copy,
+    Stable<'a, T>: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.into_iter().map(Stable))
+    }
+}
+
+impl Serialize for Stable<'_, Level> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let level = self.0;
+        let mut tuple = serializer.serialize_tuple(4)?;
+        tuple.serialize_element(&StableSeq(&level.events))?;
+        tuple.serialize_element(&level.timing)?;
+        if !level.groups.is_empty() {
+            tuple.serialize_element(&(0u8, &level.groups))?;
+        }
+        if !level.health_sections.is_empty() {
+            tuple.serialize_element(&(1u8, &level.health_sections))?;
+        }
+        tuple.end()
+    }
+}
+
+impl Serialize for Stable<'_, TimedEvent> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let event = self.0;
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&event.time)?;
+        tuple.serialize_element(&Stable(&event.event))?;
+        if let Some(envelope) = &event.envelope {
+            tuple.serialize_element(&(0u8, envelope))?;
+        }
+        tuple.end()
+    }
+}
+
+impl Serialize for Stable<'_, Event> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Event::Light(light) => {
+                serializer.serialize_newtype_variant("Event", 0, "Light", &Stable(light))
+            }
+            Event::Effect(effect) => {
+                serializer.serialize_newtype_variant("Event", 1, "Effect", effect)
+            }
+            Event::Pattern(pattern) => {
+                serializer.serialize_newtype_variant("Event", 2, "Pattern", pattern)
+            }
+        }
+    }
+}
+
+impl Serialize for Stable<'_, LightEvent> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let light = self.0;
+        let mut tuple = serializer.serialize_tuple(5)?;
+        tuple.serialize_element(&light.danger)?;
+        tuple.serialize_element(&light.shape)?;
+        tuple.serialize_element(&Stable(&light.movement))?;
+        if let Some(group) = light.group {
+            tuple.serialize_element(&(0u8, group))?;
+        }
+        if light.sustain {
+            tuple.serialize_element(&(1u8, light.sustain))?;
+        }
+        tuple.end()
+    }
+}
+
+impl Serialize for Stable<'_, Movement> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let movement = self.0;
+        let mut tuple = serializer.serialize_tuple(3)?;
+        tuple.serialize_element(&Stable(&movement.initial))?;
+        tuple.serialize_element(&StableSeq(&movement.waypoints))?;
+        tuple.serialize_element(&Stable(&movement.last))?;
+        tuple.end()
+    }
+}
+
+impl Serialize for Stable<'_, WaypointInitial> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let initial = self.0;
+        let mut tuple = serializer.serialize_tuple(4)?;
+        tuple.serialize_element(&initial.lerp_time)?;
+        tuple.serialize_element(&initial.interpolation)?;
+        tuple.serialize_element(&initial.curve)?;
+        tuple.serialize_element(&Stable(&initial.transform))?;
+        tuple.end()
+    }
+}
+
+impl Serialize for Stable<'_, Waypoint> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let waypoint = self.0;
+        let mut tuple = serializer.serialize_tuple(6)?;
+        tuple.serialize_element(&waypoint.lerp_time)?;
+        tuple.serialize_element(&waypoint.interpolation)?;
+        tuple.serialize_element(&waypoint.change_curve)?;
+        tuple.serialize_element(&Stable(&waypoint.transform))?;
+        if let Some(danger) = waypoint.danger {
+            tuple.serialize_element(&(0u8, danger))?;
+        }
+        if let Some(shape) = &waypoint.shape {
+            tuple.serialize_element(&(1u8, shape))?;
+        }
+        tuple.end()
+    }
+}
+
+impl Serialize for Stable<'_, TransformLight> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let transform = self.0;
+        let default = TransformLight::default();
+        let mut tuple = serializer.serialize_tuple(6)?;
+        tuple.serialize_element(&transform.translation)?;
+        tuple.serialize_element(&transform.rotation)?;
+        tuple.serialize_element(&transform.scale)?;
+        tuple.serialize_element(&transform.hollow)?;
+        if transform.stretch != default.stretch {
+            tuple.serialize_element(&(0u8, transform.stretch))?;
+        }
+        if transform.pulse != default.pulse {
+            tuple.serialize_element(&(1u8, transform.pulse))?;
+        }
+        tuple.end()
+    }
+}
@@ -5,6 +5,10 @@ use super::*;
 pub struct Level {
     pub events: Vec<TimedEvent>,
     pub timing: Timing,
+    /// Groups of lights moving relative to a shared pivot.
+    pub groups: Vec<LightGroup>,
+    /// Sections of the level with a modified health drain.
+    pub health_sections: Vec<HealthSection>,
 }
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -27,12 +31,28 @@ pub struct TimedEvent {
     /// The time on which the event should happen.
     pub time: Time,
     pub event: Event,
+    /// Shapes the intensity of effect events over their duration.
+    /// If set to `None`, the effect eases in and out on its own.
+    #[serde(default)]
+    pub envelope: Option<EffectEnvelope>,
+}
+
+/// Attack and release of an effect's intensity.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EffectEnvelope {
+    /// Time to reach the full intensity from the start of the effect.
+    pub attack: Time,
+    /// Time to fade out completely by the end of the effect.
+    pub release: Time,
+    pub easing: MoveInterpolation,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Event {
     Light(LightEvent),
     Effect(EffectEvent),
+    /// A generator of lights, expanded into concrete [`Event::Light`]s when played.
+    Pattern(PatternEvent),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -63,6 +83,34 @@ pub enum EffectEvent {
     /// Time specifies the duration of the **effect**.
     /// R32 specifies the dimming value (0-1).
     Spotlight(Time, R32),
+    /// Zoom the camera in, easing in and back out.
+    /// Time specifies the duration of the **effect**.
+    /// R32 specifies the intensity (1 zooms in twice as close).
+    CameraZoom(Time, R32),
+    /// Rotate the camera, easing in and back out.
+    /// Time specifies the duration of the **effect**.
+    CameraRotate(Time, Angle<R32>),
+    /// Pan the camera, easing in and back out.
+    /// Time specifies the duration of the **effect**.
+    /// The vector specifies the offset of the camera center.
+    CameraPan(Time, vec2<Coord>),
+    /// Flash the screen with the light color, fading out over time.
+    /// Time specifies the duration of the **effect**.
+    /// R32 specifies the intensity of the flash (0-1).
+    Flash(Time, R32),
+    /// Tint the screen toward a theme color.
+    /// Time specifies the duration of the **effect**.
+    /// R32 specifies the intensity of the tint (0-1).
+    Tint(Time, R32, TintColor),
+}
+
+/// A color of the theme used by effects.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TintColor {
+    Dark,
+    Light,
+    Danger,
+    Highlight,
 }
 
 impl EffectEvent {
@@ -74,7 +122,12 @@ impl EffectEvent {
             | EffectEvent::Vignette(duration, _)
             | EffectEvent::ScreenCurvature(duration, _)
             | EffectEvent::NoiseOffset(duration, _)
-            | EffectEvent::Spotlight(duration, _) => *duration,
+            | EffectEvent::Spotlight(duration, _)
+            | EffectEvent::CameraZoom(duration, _)
+            | EffectEvent::CameraRotate(duration, _)
+            | EffectEvent::CameraPan(duration, _)
+            | EffectEvent::Flash(duration, _)
+            | EffectEvent::Tint(duration, ..) => *duration,
         }
     }
 
@@ -86,7 +139,12 @@ impl EffectEvent {
             | EffectEvent::Vignette(duration, _)
             | EffectEvent::ScreenCurvature(duration, _)
             | EffectEvent::NoiseOffset(duration, _)
-            | EffectEvent::Spotlight(duration, _) => duration,
+            | EffectEvent::Spotlight(duration, _)
+            | EffectEvent::CameraZoom(duration, _)
+            | EffectEvent::CameraRotate(duration, _)
+            | EffectEvent::CameraPan(duration, _)
+            | EffectEvent::Flash(duration, _)
+            | EffectEvent::Tint(duration, ..) => duration,
         }
     }
 
@@ -96,7 +154,10 @@ impl EffectEvent {
             | EffectEvent::Vignette(_, intensity)
             | EffectEvent::ScreenCurvature(_, intensity)
             | EffectEvent::NoiseOffset(_, intensity)
-            | EffectEvent::Spotlight(_, intensity) => Some(intensity),
+            | EffectEvent::Spotlight(_, intensity)
+            | EffectEvent::CameraZoom(_, intensity)
+            | EffectEvent::Flash(_, intensity)
+            | EffectEvent::Tint(_, intensity, _) => Some(intensity),
             _ => None,
         }
     }
@@ -104,13 +165,126 @@ impl EffectEvent {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct LightEvent {
-    /// Whether the light is dangerous.
+    /// Whether the light is dangerous when it appears.
+    /// Waypoints can switch it later (see [`Waypoint::danger`]).
     #[serde(default)]
     pub danger: bool,
     pub shape: Shape,
     /// Movement with timings in beats.
     #[serde(default)]
     pub movement: Movement,
+    /// Index of the group in [`Level::groups`] this light belongs to.
+    /// The movement of a grouped light is relative to the group's pivot.
+    #[serde(default)]
+    pub group: Option<usize>,
+    /// Sustain lights have to be held: the player is expected to stay inside
+    /// from the first to the last waypoint, see [`Movement::sustain_window`].
+    #[serde(default)]
+    pub sustain: bool,
+}
+
+/// Parameterized generator of repetitive light patterns (spirals, rings, mirrored halves).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PatternEvent {
+    /// The light that is repeated. Copy `i` is rotated by `i * angle_step`
+    /// and delayed by `i * time_step`.
+    pub light: LightEvent,
+    /// Number of copies, including the original light.
+    pub count: usize,
+    /// Rotation around the [`PatternEvent::anchor`] between consecutive copies.
+    pub angle_step: Angle<Coord>,
+    /// Delay between consecutive copies.
+    pub time_step: Time,
+    /// The point the copies are rotated around.
+    #[serde(default)]
+    pub anchor: vec2<Coord>,
+    /// Whether every copy is accompanied by its mirror across the vertical line through the anchor.
+    #[serde(default)]
+    pub mirror: bool,
+}
+
+impl PatternEvent {
+    pub fn new(light: LightEvent) -> Self {
+        Self {
+            light,
+            count: 1,
+            angle_step: Angle::ZERO,
+            time_step: Time::ZERO,
+            anchor: vec2::ZERO,
+            mirror: false,
+        }
+    }
+
+    /// Generate the concrete lights of the pattern, starting at the given time.
+    pub fn expand(&self, time: Time) -> impl Iterator<Item = TimedEvent> + '_ {
+        (0..self.count).flat_map(move |i| {
+            let mut light = self.light.clone();
+            light
+                .movement
+                .rotate_around(self.anchor, self.angle_step * r32(i as f32));
+            let mirrored = self.mirror.then(|| {
+                let mut light = light.clone();
+                light.movement.flip_horizontal(self.anchor);
+                light
+            });
+            let time = time + self.time_step * i as Time;
+            std::iter::once(light)
+                .chain(mirrored)
+                .map(move |light| TimedEvent {
+                    time,
+                    event: Event::Light(light),
+                    envelope: None,
+                })
+        })
+    }
+
+    /// Duration from the start of the first copy to the end of the last one.
+    pub fn duration(&self) -> Time {
+        self.time_step * self.count.saturating_sub(1) as Time + self.light.movement.duration()
+    }
+}
+
+/// A time range of the level where the health drains at a different rate.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HealthSection {
+    pub start: Time,
+    pub end: Time,
+    /// Multiplier applied to the health drain in darkness and in danger.
+    pub drain: R32,
+}
+
+/// A group of lights sharing a common parent transform.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LightGroup {
+    pub name: String,
+    /// The time at which the parent movement starts.
+    pub time: Time,
+    /// Movement of the pivot of the group.
+    pub movement: Movement,
+}
+
+impl LightGroup {
+    /// Create a static group with the pivot at the given position.
+    pub fn new(name: impl Into<String>, time: Time, pivot: vec2<Coord>) -> Self {
+        let transform = TransformLight {
+            translation: pivot,
+            ..TransformLight::identity()
+        };
+        Self {
+            name: name.into(),
+            time,
+            movement: Movement {
+                initial: WaypointInitial::new(Time::ZERO, transform),
+                waypoints: VecDeque::new(),
+                last: transform,
+            },
+        }
+    }
+
+    /// Get the parent transform of the group at the given time.
+    pub fn transform_at(&self, time: Time) -> TransformLight {
+        self.movement.get((time - self.time).max(Time::ZERO))
+    }
 }
 
 impl Level {
@@ -118,9 +292,21 @@ impl Level {
         Self {
             events: Vec::new(),
             timing: Timing::new(bpm),
+            groups: Vec::new(),
+            health_sections: Vec::new(),
         }
     }
 
+    /// Get the health drain multiplier at the given time.
+    /// Overlapping sections stack multiplicatively.
+    pub fn health_multiplier(&self, time: Time) -> R32 {
+        self.health_sections
+            .iter()
+            .filter(|section| (section.start..section.end).contains(&time))
+            .map(|section| section.drain)
+            .fold(R32::ONE, |acc, drain| acc * drain)
+    }
+
     /// Calculate the last time when anything happens.
     pub fn last_time(&self) -> Time {
         self.events
@@ -130,8 +316,33 @@ impl Level {
             .unwrap_or(Time::ZERO)
     }
 
+    /// Whether the level contains any pattern events that need to be expanded.
+    pub fn has_patterns(&self) -> bool {
+        self.events
+            .iter()
+            .any(|event| matches!(event.event, Event::Pattern(_)))
+    }
+
+    /// Replace every pattern event with the lights it generates.
+    /// The order of the events is kept, generated lights take the place of their pattern.
+    pub fn expand_patterns(&mut self) {
+        if !self.has_patterns() {
+            return;
+        }
+        self.events = std::mem::take(&mut self.events)
+            .into_iter()
+            .flat_map(|event| match &event.event {
+                Event::Pattern(pattern) => pattern.expand(event.time).collect(),
+                _ => vec![event],
+            })
+            .collect();
+    }
+
+    /// Hash of the level contents that stays the same for the levels
+    /// not using the features added after the hash was introduced.
     pub fn calculate_hash(&self) -> String {
-        let bytes = bincode::serialize(self).expect("level should be serializable");
+        let bytes =
+            bincode::serialize(&super::hash::Stable(self)).expect("level should be serializable");
         crate::util::calculate_hash(&bytes)
     }
 }
@@ -259,12 +470,47 @@ impl Timing {
     }
 }
 
+impl EffectEnvelope {
+    /// Intensity multiplier (0-1) at the given time since the start of the effect.
+    pub fn factor(&self, time: Time, duration: Time) -> R32 {
+        let ramp = |time: Time, length: Time| {
+            if length <= 0 {
+                return R32::ONE;
+            }
+            let t = r32(time as f32 / length as f32).clamp(R32::ZERO, R32::ONE);
+            self.easing.apply(t)
+        };
+        ramp(time, self.attack).min(ramp(duration - time, self.release))
+    }
+}
+
 impl TimedEvent {
     /// Returns the duration of the event.
     pub fn duration(&self) -> Time {
         match &self.event {
             Event::Light(event) => event.movement.duration(),
             Event::Effect(_) => Time::ZERO,
+            Event::Pattern(pattern) => pattern.duration(),
+        }
+    }
+}
+
+impl Event {
+    /// Get the light of the event, or the repeated light of a pattern.
+    pub fn as_light(&self) -> Option<&LightEvent> {
+        match self {
+            Event::Light(light) => Some(light),
+            Event::Pattern(pattern) => Some(&pattern.light),
+            Event::Effect(_) => None,
+        }
+    }
+
+    /// Get the light of the event, or the repeated light of a pattern.
+    pub fn as_light_mut(&mut self) -> Option<&mut LightEvent> {
+        match self {
+            Event::Light(light) => Some(light),
+            Event::Pattern(pattern) => Some(&mut pattern.light),
+            Event::Effect(_) => None,
         }
     }
 }
@@ -278,6 +524,7 @@ impl LightEvent {
             lifetime: Time::ZERO,
             danger: self.danger,
             hollow: r32(-1.0),
+            sustain: false,
             event_id,
             closest_waypoint: (Time::ZERO, WaypointId::Initial),
         }
@@ -342,3 +589,97 @@ fn test_beat_alignment() {
     assert!(timing.is_beat_aligned(667).is_some());
     assert!(timing.is_beat_aligned(669).is_none());
 }
+
+#[test]
+fn test_pattern_expansion() {
+    let mut light = LightEvent {
+        danger: false,
+        shape: Shape::circle(r32(1.0)),
+        movement: Movement::new(0, TransformLight::identity()),
+        group: None,
+        sustain: false,
+    };
+    light.movement.initial.transform.translation = vec2(r32(2.0), r32(0.0));
+    let pattern = PatternEvent {
+        light,
+        count: 4,
+        angle_step: Angle::from_degrees(r32(90.0)),
+        time_step: 100,
+        anchor: vec2::ZERO,
+        mirror: true,
+    };
+
+    let mut level = Level::new(r32(120.0));
+    level.events.push(TimedEvent {
+        time: 500,
+        event: Event::Pattern(pattern),
+        envelope: None,
+    });
+    level.expand_patterns();
+    assert!(!level.has_patterns());
+    assert_eq!(level.events.len(), 8);
+
+    let positions: Vec<_> = level
+        .events
+        .iter()
+        .map(|event| {
+            let Event::Light(light) = &event.event else {
+                panic!("pattern should expand into lights");
+            };
+            let pos = light.movement.initial.transform.translation;
+            (event.time, pos.x.as_f32().round(), pos.y.as_f32().round())
+        })
+        .collect();
+    assert_eq!(positions[0], (500, 2.0, 0.0));
+    assert_eq!(positions[1], (500, -2.0, 0.0));
+    assert_eq!(positions[2], (600, 0.0, 2.0));
+    assert_eq!(positions[3], (600, -0.0, 2.0));
+    assert_eq!(positions[6], (800, 0.0, -2.0));
+}
+
+#[test]
+fn test_effect_envelope() {
+    let envelope = EffectEnvelope {
+        attack: 100,
+        release: 200,
+        easing: MoveInterpolation::Linear,
+    };
+    let factor = |time| envelope.factor(time, 1000).as_f32();
+    assert_eq!(factor(0), 0.0);
+    assert_eq!(factor(50), 0.5);
+    assert_eq!(factor(500), 1.0);
+    assert_eq!(factor(900), 0.5);
+    assert_eq!(factor(1000), 0.0);
+}
+
+#[test]
+fn test_level_hash_stable() {
+    let mut level = Level {
+        events: vec![TimedEvent {
+            time: 1000,
+            event: Event::Light(LightEvent {
+                danger: false,
+                shape: Shape::circle(r32(1.0)),
+                movement: Movement::new(500, TransformLight::identity()),
+                group: None,
+                sustain: false,
+            }),
+            envelope: None,
+        }],
+        ..Level::new(r32(120.0))
+    };
+    // The hash of a level that does not use any of the new features must never change,
+    // otherwise the scores submitted for it are rejected
+    assert_eq!(
+        level.calculate_hash(),
+        "26bd57e98c6ee43a02dbceb18997bad39317610ab81f203c306c8e92ad0cc312"
+    );
+
+    if let Event::Light(light) = &mut level.events[0].event {
+        light.sustain = true;
+    }
+    assert_ne!(
+        level.calculate_hash(),
+        "26bd57e98c6ee43a02dbceb18997bad39317610ab81f203c306c8e92ad0cc312"
+    );
+}
@@ -8,6 +8,7 @@ pub struct LevelState {
     /// The time after which events are not rendered.
     ignore_after: Option<Time>,
     timing: Timing,
+    groups: Vec<LightGroup>,
     pub lights: Vec<Light>,
     pub telegraphs: Vec<LightTelegraph>,
     pub waypoints: Vec<LightWaypoint>,
@@ -27,6 +28,7 @@ impl Default for LevelState {
             time: Time::ZERO,
             ignore_after: None,
             timing: Timing::default(),
+            groups: Vec::new(),
             lights: Vec::new(),
             telegraphs: Vec::new(),
             waypoints: Vec::new(),
@@ -52,6 +54,7 @@ impl LevelState {
             time,
             ignore_after,
             timing: level.timing.clone(),
+            groups: level.groups.clone(),
             lights: Vec::new(),
             telegraphs: Vec::new(),
             waypoints: Vec::new(),
@@ -103,12 +106,23 @@ impl LevelState {
                 if self.time < event.time - precede_time {
                     return;
                 }
+                let group = light.group.and_then(|group| self.groups.get(group));
+                let parent = |relative_time: Time| {
+                    group.map_or_else(TransformLight::identity, |group| {
+                        group.transform_at(event.time + relative_time)
+                    })
+                };
                 let (light, telegraph, waypoints) =
-                    render_light(light, time, event_id, precede_time, &timing);
+                    render_light(light, time, event_id, precede_time, &timing, parent);
                 self.lights.extend(light);
                 self.telegraphs.extend(telegraph);
                 self.waypoints.extend(waypoints);
             }
+            Event::Pattern(pattern) => {
+                for light in pattern.expand(event.time) {
+                    self.render_event(&light, event_id, None);
+                }
+            }
             Event::Effect(effect) => match *effect {
                 EffectEvent::PaletteSwap(duration) => {
                     if self.time < event.time {
@@ -116,16 +130,19 @@ impl LevelState {
                     }
                     if let Some(vfx) = vfx {
                         let t = (time as f32 / duration as f32).clamp(0.0, 1.0);
+                        let eased = event
+                            .envelope
+                            .map_or(r32(t), |envelope| envelope.easing.apply(r32(t)));
                         vfx.palette_swap.target = if t == 1.0 {
                             // After this palette swap - just invert target
                             // since a later swap event could be processed before this one
                             r32(1.0) - vfx.palette_swap.target
                         } else if vfx.palette_swap.target > r32(0.5) {
                             // Fade to normal
-                            r32(1.0 - t)
+                            r32(1.0) - eased
                         } else {
                             // Fade to inverted
-                            r32(t)
+                            eased
                         };
                     }
                 }
@@ -134,8 +151,7 @@ impl LevelState {
                         return;
                     }
                     if let Some(vfx) = vfx {
-                        vfx.rgb_split
-                            .set(time_to_seconds(duration - time), R32::ONE);
+                        set_effect(&mut vfx.rgb_split, event, time, duration, R32::ONE);
                     }
                 }
                 EffectEvent::CameraShake(duration, intensity) => {
@@ -143,7 +159,10 @@ impl LevelState {
                         return;
                     }
                     if let Some(vfx) = vfx {
-                        vfx.camera_shake = vfx.camera_shake.max(intensity);
+                        let factor = event
+                            .envelope
+                            .map_or(R32::ONE, |envelope| envelope.factor(time, duration));
+                        vfx.camera_shake = vfx.camera_shake.max(intensity * factor);
                     }
                 }
                 EffectEvent::Vignette(duration, intensity) => {
@@ -151,8 +170,7 @@ impl LevelState {
                         return;
                     }
                     if let Some(vfx) = vfx {
-                        vfx.vignette
-                            .set(time_to_seconds(duration - time), intensity);
+                        set_effect(&mut vfx.vignette, event, time, duration, intensity);
                     }
                 }
                 EffectEvent::ScreenCurvature(duration, intensity) => {
@@ -160,8 +178,7 @@ impl LevelState {
                         return;
                     }
                     if let Some(vfx) = vfx {
-                        vfx.curvature
-                            .set(time_to_seconds(duration - time), intensity);
+                        set_effect(&mut vfx.curvature, event, time, duration, intensity);
                     }
                 }
                 EffectEvent::NoiseOffset(duration, intensity) => {
@@ -169,8 +186,7 @@ impl LevelState {
                         return;
                     }
                     if let Some(vfx) = vfx {
-                        vfx.noise_offset
-                            .set(time_to_seconds(duration - time), intensity);
+                        set_effect(&mut vfx.noise_offset, event, time, duration, intensity);
                     }
                 }
                 EffectEvent::Spotlight(duration, intensity) => {
@@ -178,8 +194,62 @@ impl LevelState {
                         return;
                     }
                     if let Some(vfx) = vfx {
-                        vfx.spotlight
-                            .set(time_to_seconds(duration - time), intensity);
+                        set_effect(&mut vfx.spotlight, event, time, duration, intensity);
+                    }
+                }
+                EffectEvent::CameraZoom(duration, intensity) => {
+                    if self.time < event.time || self.time > event.time + duration {
+                        return;
+                    }
+                    if let Some(vfx) = vfx {
+                        set_effect(&mut vfx.camera_zoom, event, time, duration, intensity);
+                    }
+                }
+                EffectEvent::CameraRotate(duration, angle) => {
+                    if self.time < event.time || self.time > event.time + duration {
+                        return;
+                    }
+                    if let Some(vfx) = vfx {
+                        set_effect(
+                            &mut vfx.camera_rotation,
+                            event,
+                            time,
+                            duration,
+                            angle.as_degrees(),
+                        );
+                    }
+                }
+                EffectEvent::CameraPan(duration, offset) => {
+                    if self.time < event.time || self.time > event.time + duration {
+                        return;
+                    }
+                    if let Some(vfx) = vfx {
+                        set_effect(&mut vfx.camera_pan_x, event, time, duration, offset.x);
+                        set_effect(&mut vfx.camera_pan_y, event, time, duration, offset.y);
+                    }
+                }
+                EffectEvent::Flash(duration, intensity) => {
+                    if self.time < event.time || self.time > event.time + duration {
+                        return;
+                    }
+                    if let Some(vfx) = vfx {
+                        if event.envelope.is_some() {
+                            set_effect(&mut vfx.flash, event, time, duration, intensity);
+                        } else {
+                            // Fade out linearly over the duration
+                            let t = (time as f32 / duration.max(1) as f32).clamp(0.0, 1.0);
+                            vfx.flash
+                                .set(time_to_seconds(duration - time), intensity * r32(1.0 - t));
+                        }
+                    }
+                }
+                EffectEvent::Tint(duration, intensity, color) => {
+                    if self.time < event.time || self.time > event.time + duration {
+                        return;
+                    }
+                    if let Some(vfx) = vfx {
+                        set_effect(&mut vfx.tint, event, time, duration, intensity);
+                        vfx.tint_color = color;
                     }
                 }
             },
@@ -189,26 +259,50 @@ impl LevelState {
     }
 }
 
+/// Set the value of the effect, shaped by the envelope of the event if it has one.
+fn set_effect(value: &mut VfxValue, event: &TimedEvent, time: Time, duration: Time, target: R32) {
+    let time_left = time_to_seconds(duration - time);
+    match &event.envelope {
+        Some(envelope) => value.set_exact(time_left, target * envelope.factor(time, duration)),
+        None => value.set(time_left, target),
+    }
+}
+
 pub fn render_light(
     event: &LightEvent,
     relative_time: Time,
     event_id: Option<usize>,
     precede_time: Time,
     timing: &TimingPoint,
+    parent: impl Fn(Time) -> TransformLight,
 ) -> (Option<Light>, Option<LightTelegraph>, Vec<LightWaypoint>) {
     let movement = &event.movement;
     let base_light = event.clone().instantiate(event_id);
     let base_tele = base_light.clone().into_telegraph();
     let duration = event.movement.duration();
+    let pulse_beats = |time: Time| time_to_seconds(time) / timing.beat_time;
+    let collider_at = |time: Time, transform: TransformLight| {
+        Collider {
+            shape: movement.get_shape(time, event.shape),
+            ..base_light.collider.clone()
+        }
+        .transformed(transform)
+    };
 
     // Light
     let light = (relative_time > Time::ZERO && relative_time < duration).then(|| {
-        let transform = event.movement.get(relative_time);
+        let transform = event
+            .movement
+            .get(relative_time)
+            .pulsed(pulse_beats(relative_time))
+            .relative_to(&parent(relative_time));
         let mut main_light = base_tele.light.clone();
-        main_light.collider = base_light.collider.transformed(transform);
+        main_light.collider = collider_at(relative_time, transform);
         let (id, _, closest_time) = event.movement.closest_waypoint(relative_time);
         main_light.closest_waypoint = (closest_time - relative_time, id);
         main_light.hollow = transform.hollow;
+        main_light.danger = movement.danger_at(event.danger, relative_time);
+        main_light.sustain = event.sustain && movement.sustain_window().contains(&relative_time);
         main_light
     });
 
@@ -217,9 +311,14 @@ pub fn render_light(
     let (telegraph, waypoints) = if relative_time > duration {
         (None, vec![])
     } else {
-        let transform = event.movement.get(relative_time);
+        let transform = event
+            .movement
+            .get(relative_time)
+            .pulsed(pulse_beats(relative_time))
+            .relative_to(&parent(relative_time));
         let mut main_tele = base_tele.clone();
-        main_tele.light.collider = base_light.collider.transformed(transform);
+        main_tele.light.collider = collider_at(relative_time, transform);
+        main_tele.light.danger = movement.danger_at(event.danger, relative_time);
 
         let mut last_pos = movement.initial.transform.translation;
         let waypoints = movement
@@ -245,17 +344,16 @@ pub fn render_light(
                         .contains(&relative_time)
                     && radius.as_f32() > width)
                     .then(|| {
-                        let shape = match base_light.collider.shape {
-                            Shape::Circle { .. } => Shape::circle(radius),
-                            Shape::Line { .. } => Shape::line(radius / r32(2.0)),
-                            Shape::Rectangle { .. } => Shape::rectangle(vec2::splat(radius)),
-                        };
+                        let shape = base_light.collider.shape.waypoint_marker(radius);
                         let collider = Collider {
                             shape,
-                            ..base_light.collider.transformed(transform)
+                            ..base_light
+                                .collider
+                                .transformed(transform.relative_to(&parent(time)))
                         };
                         let mut light = base_light.clone();
                         light.collider = collider;
+                        light.danger = movement.danger_at(event.danger, time);
                         LightWaypoint {
                             time: relative_time - time,
                             light,
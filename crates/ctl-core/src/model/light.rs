@@ -1,5 +1,7 @@
 use super::*;
 
+use itertools::Itertools;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Light {
     /// Currently active collider.
@@ -12,6 +14,8 @@ pub struct Light {
     pub danger: bool,
     /// Makes the light hollow.
     pub hollow: R32,
+    /// Whether the light is a sustain light inside of its hold window.
+    pub sustain: bool,
     /// Id of the original event in the level.
     pub event_id: Option<usize>,
     /// Time delta to the closest waypoint.
@@ -102,5 +106,107 @@ pub fn get_light_distance(position: vec2<Coord>, light: &Collider, hollow: R32)
                 max: radius,
             }
         }
+        Shape::Polygon { .. } | Shape::Convex { .. } => {
+            let delta_pos = delta_pos.rotate(-light.rotation);
+            let points = light.shape.polygon_points().unwrap_or_default();
+            let radius = polygon_boundary_distance(&points, delta_pos);
+            LightDistance {
+                raw: delta_pos.len(),
+                min: hollow * radius,
+                max: radius,
+            }
+        }
+        Shape::Ellipse { width, height } => {
+            let delta_pos = delta_pos.rotate(-light.rotation);
+            let (a, b) = (width / r32(2.0), height / r32(2.0));
+            let direction = if delta_pos == vec2::ZERO {
+                vec2::UNIT_X
+            } else {
+                delta_pos.as_f32().normalize().as_r32()
+            };
+            // Distance to the boundary in polar coordinates
+            let denom = vec2(b * direction.x, a * direction.y).len();
+            let radius = if denom == Coord::ZERO {
+                Coord::ZERO
+            } else {
+                a * b / denom
+            };
+            LightDistance {
+                raw: delta_pos.len(),
+                min: hollow * radius,
+                max: radius,
+            }
+        }
+        Shape::Arc { width, .. } => {
+            let delta_pos = delta_pos.rotate(-light.rotation);
+            let points = light.shape.arc_points().unwrap_or_default();
+            // Distance to the middle line of the arc
+            let raw = points
+                .iter()
+                .tuple_windows()
+                .map(|(&a, &b)| distance_to_segment(delta_pos, a, b))
+                .min()
+                .unwrap_or(delta_pos.len());
+            let radius = width / r32(2.0);
+            LightDistance {
+                raw,
+                min: hollow * radius,
+                max: radius,
+            }
+        }
     }
 }
+
+/// Distance from the center to the boundary of the polygon in the direction of the point.
+/// The polygon is assumed to contain the origin.
+fn polygon_boundary_distance(points: &[vec2<Coord>], direction: vec2<Coord>) -> Coord {
+    let direction = if direction == vec2::ZERO {
+        vec2::UNIT_X
+    } else {
+        direction.as_f32().normalize().as_r32()
+    };
+    points
+        .iter()
+        .circular_tuple_windows()
+        .filter_map(|(&a, &b)| {
+            // Solve `direction * t = a + (b - a) * s`
+            let edge = b - a;
+            let denom = vec2::skew(direction, edge);
+            if denom == Coord::ZERO {
+                return None;
+            }
+            let t = vec2::skew(a, edge) / denom;
+            let s = vec2::skew(a, direction) / denom;
+            (t >= Coord::ZERO && (Coord::ZERO..=Coord::ONE).contains(&s)).then_some(t)
+        })
+        .max()
+        .unwrap_or(Coord::ZERO)
+}
+
+fn distance_to_segment(point: vec2<Coord>, a: vec2<Coord>, b: vec2<Coord>) -> Coord {
+    let edge = b - a;
+    let len_sqr = vec2::dot(edge, edge);
+    let t = if len_sqr == Coord::ZERO {
+        Coord::ZERO
+    } else {
+        (vec2::dot(point - a, edge) / len_sqr).clamp(Coord::ZERO, Coord::ONE)
+    };
+    (point - (a + edge * t)).len()
+}
+
+#[test]
+fn test_polygon_light_distance() {
+    let collider = Collider::new(vec2::ZERO, Shape::polygon(r32(1.0), 6));
+    let inside = get_light_distance(vec2(0.5, 0.0).as_r32(), &collider, r32(-1.0));
+    assert!(inside.is_inside());
+    let outside = get_light_distance(vec2(0.0, 1.1).as_r32(), &collider, r32(-1.0));
+    assert!(!outside.is_inside());
+
+    let ring = Collider::new(vec2::ZERO, Shape::ring(r32(2.0), r32(0.5)));
+    assert!(get_light_distance(vec2(2.1, 0.0).as_r32(), &ring, r32(-1.0)).is_inside());
+    assert!(!get_light_distance(vec2::ZERO, &ring, r32(-1.0)).is_inside());
+
+    let ellipse = Collider::new(vec2::ZERO, Shape::ellipse(vec2(4.0, 1.0).as_r32()));
+    assert!(get_light_distance(vec2(1.9, 0.0).as_r32(), &ellipse, r32(-1.0)).is_inside());
+    assert!(!get_light_distance(vec2(0.0, 0.6).as_r32(), &ellipse, r32(-1.0)).is_inside());
+}
@@ -37,6 +37,14 @@ pub struct Waypoint {
     /// If set to `None`, the curve will continue as the previous type.
     pub change_curve: Option<TrajectoryInterpolation>,
     pub transform: TransformLight,
+    /// Switches the light between safe and dangerous starting from this frame.
+    /// If set to `None`, the light keeps its previous state.
+    #[serde(default)]
+    pub danger: Option<bool>,
+    /// Morphs the light into this shape by the time it reaches this frame.
+    /// If set to `None`, the light keeps the shape of the previous frame.
+    #[serde(default)]
+    pub shape: Option<Shape>,
 }
 
 /// Controls the speed of the light when moving between keyframes.
@@ -47,16 +55,111 @@ pub enum MoveInterpolation {
     Smoothstep,
     EaseIn,
     EaseOut,
+    Cubic(EaseDirection),
+    Quart(EaseDirection),
+    Expo(EaseDirection),
+    /// Overshoots slightly past the target.
+    Back(EaseDirection),
+    /// Oscillates around the target like a spring.
+    Elastic(EaseDirection),
+    Bounce(EaseDirection),
+    /// Custom cubic bezier curve going through `(0, 0)` and `(1, 1)`
+    /// with the given control points, same as CSS `cubic-bezier`.
+    Bezier {
+        x1: R32,
+        y1: R32,
+        x2: R32,
+        y2: R32,
+    },
+}
+
+/// Which end of the interpolation the easing is applied to.
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EaseDirection {
+    #[default]
+    In,
+    Out,
+    InOut,
 }
 
 impl MoveInterpolation {
+    /// The standard `ease` curve as a starting point for custom bezier curves.
+    pub fn default_bezier() -> Self {
+        Self::Bezier {
+            x1: r32(0.25),
+            y1: r32(0.1),
+            x2: r32(0.25),
+            y2: r32(1.0),
+        }
+    }
+
+    /// The interpolation that produces the same motion when played backwards.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Linear => Self::Linear,
+            Self::Smoothstep => Self::Smoothstep,
+            Self::EaseIn => Self::EaseOut,
+            Self::EaseOut => Self::EaseIn,
+            Self::Cubic(dir) => Self::Cubic(dir.reversed()),
+            Self::Quart(dir) => Self::Quart(dir.reversed()),
+            Self::Expo(dir) => Self::Expo(dir.reversed()),
+            Self::Back(dir) => Self::Back(dir.reversed()),
+            Self::Elastic(dir) => Self::Elastic(dir.reversed()),
+            Self::Bounce(dir) => Self::Bounce(dir.reversed()),
+            Self::Bezier { x1, y1, x2, y2 } => Self::Bezier {
+                x1: R32::ONE - x2,
+                y1: R32::ONE - y2,
+                x2: R32::ONE - x1,
+                y2: R32::ONE - y1,
+            },
+        }
+    }
+
     /// Applies the interpolation function to a value between 0 and 1.
     pub fn apply(&self, t: FloatTime) -> FloatTime {
-        match self {
+        match *self {
             Self::Linear => t,
             Self::Smoothstep => smoothstep(t),
             Self::EaseIn => ease_in(t),
             Self::EaseOut => ease_out(t),
+            Self::Cubic(dir) => r32(dir.apply(t.as_f32(), |t| t * t * t)),
+            Self::Quart(dir) => r32(dir.apply(t.as_f32(), |t| t * t * t * t)),
+            Self::Expo(dir) => r32(dir.apply(t.as_f32(), ease_in_expo)),
+            Self::Back(dir) => r32(dir.apply(t.as_f32(), ease_in_back)),
+            Self::Elastic(dir) => r32(dir.apply(t.as_f32(), ease_in_elastic)),
+            Self::Bounce(dir) => r32(dir.apply(t.as_f32(), |t| 1.0 - bounce_out(1.0 - t))),
+            Self::Bezier { x1, y1, x2, y2 } => r32(cubic_bezier(
+                vec2(x1.as_f32(), y1.as_f32()),
+                vec2(x2.as_f32(), y2.as_f32()),
+                t.as_f32(),
+            )),
+        }
+    }
+}
+
+impl EaseDirection {
+    /// The direction that produces the same easing when played backwards.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::In => Self::Out,
+            Self::Out => Self::In,
+            Self::InOut => Self::InOut,
+        }
+    }
+
+    /// Applies the ease-in function in the given direction.
+    fn apply(self, t: f32, ease_in: impl Fn(f32) -> f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Self::In => ease_in(t),
+            Self::Out => 1.0 - ease_in(1.0 - t),
+            Self::InOut => {
+                if t < 0.5 {
+                    ease_in(t * 2.0) / 2.0
+                } else {
+                    1.0 - ease_in(2.0 - t * 2.0) / 2.0
+                }
+            }
         }
     }
 }
@@ -118,10 +221,16 @@ pub struct TransformLight {
     pub translation: vec2<Coord>,
     pub rotation: Angle<Coord>,
     pub scale: Coord,
+    /// Separate scaling along the local axes of the light, applied on top of `scale`.
+    pub stretch: vec2<Coord>,
     /// Turns the light hollow (circle lights become rings),
     /// with the value indicating the level of hollowness (inner cut ratio).
     /// Expected range: `-1..=1`.
     pub hollow: R32,
+    /// Makes the light pulse to the beat,
+    /// with the value indicating the amplitude relative to the scale.
+    /// Zero disables pulsing.
+    pub pulse: R32,
 }
 
 impl Interpolatable for TransformLight {
@@ -130,7 +239,9 @@ impl Interpolatable for TransformLight {
             translation: self.translation + other.translation,
             rotation: self.rotation + other.rotation,
             scale: self.scale + other.scale,
+            stretch: self.stretch + other.stretch,
             hollow: self.hollow + other.hollow,
+            pulse: self.pulse + other.pulse,
         }
     }
 
@@ -139,7 +250,9 @@ impl Interpolatable for TransformLight {
             translation: self.translation - other.translation,
             rotation: self.rotation - other.rotation,
             scale: self.scale - other.scale,
+            stretch: self.stretch - other.stretch,
             hollow: self.hollow - other.hollow,
+            pulse: self.pulse - other.pulse,
         }
     }
 
@@ -149,7 +262,9 @@ impl Interpolatable for TransformLight {
             translation: self.translation * factor,
             rotation: self.rotation * factor,
             scale: self.scale * factor,
+            stretch: self.stretch * factor,
             hollow: self.hollow * factor,
+            pulse: self.pulse * factor,
         }
     }
 
@@ -157,7 +272,9 @@ impl Interpolatable for TransformLight {
         self.translation.length_sqr()
             + self.rotation.length_sqr()
             + self.scale.length_sqr()
+            + self.stretch.length_sqr()
             + self.hollow.length_sqr()
+            + self.pulse.length_sqr()
     }
 }
 
@@ -179,6 +296,8 @@ impl Waypoint {
             interpolation: MoveInterpolation::default(),
             change_curve: None,
             transform,
+            danger: None,
+            shape: None,
         }
     }
 
@@ -188,6 +307,8 @@ impl Waypoint {
             interpolation: MoveInterpolation::default(),
             change_curve: None,
             transform: TransformLight::scale(scale),
+            danger: None,
+            shape: None,
         }
     }
 }
@@ -198,7 +319,9 @@ impl TransformLight {
             translation: vec2::ZERO,
             rotation: Angle::ZERO,
             scale: Coord::ONE,
+            stretch: vec2::splat(Coord::ONE),
             hollow: r32(-1.0),
+            pulse: R32::ZERO,
         }
     }
 
@@ -214,7 +337,33 @@ impl TransformLight {
             translation: self.translation + (target.translation - self.translation) * t,
             rotation: self.rotation + self.rotation.angle_to(target.rotation) * t,
             scale: self.scale + (target.scale - self.scale) * t,
+            stretch: self.stretch + (target.stretch - self.stretch) * t,
             hollow: self.hollow + (target.hollow - self.hollow) * t,
+            pulse: self.pulse + (target.pulse - self.pulse) * t,
+        }
+    }
+
+    /// Apply the parent transform on top of this one.
+    /// The stretch of the parent only affects the position, not the shape.
+    pub fn relative_to(self, parent: &Self) -> Self {
+        Self {
+            translation: parent.translation
+                + (self.translation * parent.scale * parent.stretch).rotate(parent.rotation),
+            rotation: parent.rotation + self.rotation,
+            scale: parent.scale * self.scale,
+            ..self
+        }
+    }
+
+    /// Apply the pulse to the scale, given the number of beats since the light appeared.
+    pub fn pulsed(self, beats: FloatTime) -> Self {
+        if self.pulse == R32::ZERO {
+            return self;
+        }
+        let wave = (beats.as_f32() * f32::PI * 2.0).sin();
+        Self {
+            scale: (self.scale * (R32::ONE + self.pulse * r32(wave))).max(R32::ZERO),
+            ..self
         }
     }
 }
@@ -341,20 +490,31 @@ impl Movement {
     /// Get the transform at the given time using the already baked interpolation curve.
     pub fn get_baked(
         &self,
-        mut time: Time,
+        time: Time,
         curve_interpolation: &Interpolation<TransformLight>,
     ) -> TransformLight {
-        // Find the target frame
-        let mut from = self.initial.transform;
+        let Some((i, t)) = self.locate(time) else {
+            // Past all waypoints just return the last transform
+            return self.last;
+        };
+        curve_interpolation.get(i, t).unwrap_or_else(|| {
+            self.timed_transforms()
+                .nth(i)
+                .map_or(self.last, |(_, transform, _)| transform)
+        })
+    }
+
+    /// Find the interval between the frames at the given time
+    /// and the progress along it with the frame's move interpolation applied.
+    /// Returns `None` past all waypoints.
+    fn locate(&self, mut time: Time) -> Option<(usize, FloatTime)> {
         let mut lerp_time = self.initial.lerp_time;
         let mut interpolation = self.initial.interpolation;
-        for (i, (frame, next_lerp_time, next_interpolation)) in itertools::chain![
-            self.waypoints.iter().map(|waypoint| (
-                waypoint.transform,
-                waypoint.lerp_time,
-                waypoint.interpolation
-            )),
-            [(self.last, Time::ZERO, MoveInterpolation::default())]
+        for (i, (next_lerp_time, next_interpolation)) in itertools::chain![
+            self.waypoints
+                .iter()
+                .map(|waypoint| (waypoint.lerp_time, waypoint.interpolation)),
+            [(Time::ZERO, MoveInterpolation::default())]
         ]
         .enumerate()
         {
@@ -365,17 +525,75 @@ impl Movement {
                 } else {
                     FloatTime::ONE
                 };
-                let time = interpolation.apply(time);
-                return curve_interpolation.get(i, time).unwrap_or(from);
+                return Some((i, interpolation.apply(time)));
             }
             time -= lerp_time;
-            from = frame;
             lerp_time = next_lerp_time;
             interpolation = next_interpolation;
         }
+        None
+    }
+
+    /// Get the shape of the light at the given time, starting from the `base` shape.
+    pub fn get_shape(&self, time: Time, base: Shape) -> Shape {
+        if self
+            .waypoints
+            .iter()
+            .all(|waypoint| waypoint.shape.is_none())
+        {
+            return base;
+        }
+        let shapes = self.bake_shapes(base);
+        let last = || shapes.get_path(1).last().unwrap_or(base);
+        match self.locate(time) {
+            Some((i, t)) => shapes.get(i, t).unwrap_or_else(last),
+            None => last(),
+        }
+    }
+
+    /// Bakes the linear interpolation between the shapes of the frames.
+    pub fn bake_shapes(&self, base: Shape) -> Interpolation<Shape> {
+        let mut shape = base;
+        let frames: Vec<_> = self
+            .waypoints
+            .iter()
+            .map(|frame| {
+                if let Some(frame_shape) = frame.shape {
+                    shape = frame_shape;
+                }
+                (shape, None)
+            })
+            .collect();
+        bake_movement(
+            base,
+            TrajectoryInterpolation::Linear,
+            frames.into_iter().chain([(shape, None)]),
+        )
+    }
+
+    /// Whether the light is dangerous at the given time,
+    /// taking into account the toggles on the waypoints.
+    pub fn danger_at(&self, initial: bool, time: Time) -> bool {
+        let mut danger = initial;
+        let mut frame_time = self.initial.lerp_time;
+        for waypoint in &self.waypoints {
+            if frame_time > time {
+                break;
+            }
+            if let Some(toggle) = waypoint.danger {
+                danger = toggle;
+            }
+            frame_time += waypoint.lerp_time;
+        }
+        danger
+    }
 
-        // Past all waypoints just return the last transform
-        self.last
+    /// The time range between the first and the last waypoint,
+    /// i.e. the duration of the movement without the fade in and fade out.
+    pub fn sustain_window(&self) -> std::ops::Range<Time> {
+        let start = self.get_fade_in();
+        let end = (self.duration() - self.get_fade_out()).max(start);
+        start..end
     }
 
     /// Returns the total duration of the movement.
@@ -470,6 +688,77 @@ impl Movement {
             transform.rotation = -transform.rotation;
         })
     }
+
+    /// Mirror across the line going through the `anchor` in the direction of the `axis`.
+    pub fn mirror(&mut self, anchor: vec2<Coord>, axis: Angle<Coord>) {
+        self.modify_transforms(|transform: &mut TransformLight| {
+            let local = (transform.translation - anchor).rotate(-axis);
+            transform.translation = anchor + vec2(local.x, -local.y).rotate(axis);
+            transform.rotation = axis + axis - transform.rotation;
+        })
+    }
+
+    /// Scale the positions relative to the `anchor`, keeping the size of the light.
+    pub fn scale_around(&mut self, anchor: vec2<Coord>, factor: Coord) {
+        self.modify_transforms(|transform: &mut TransformLight| {
+            transform.translation = anchor + (transform.translation - anchor) * factor;
+        })
+    }
+
+    /// Scale the duration of every segment by the `factor`.
+    pub fn stretch_time(&mut self, factor: FloatTime) {
+        let stretch = |time: &mut Time| *time = seconds_to_time(time_to_seconds(*time) * factor);
+        stretch(&mut self.initial.lerp_time);
+        for frame in &mut self.waypoints {
+            stretch(&mut frame.lerp_time);
+        }
+    }
+
+    /// Reverse the movement in time, so that the light follows the same path backwards.
+    /// Danger and shape changes stay attached to their waypoints.
+    pub fn reverse_time(&mut self) {
+        let n = self.waypoints.len();
+
+        // Segment `i` goes from keyframe `i` to `i + 1`, where keyframe `0` is the initial one
+        let segments: Vec<(Time, MoveInterpolation)> = itertools::chain![
+            [(self.initial.lerp_time, self.initial.interpolation)],
+            self.waypoints
+                .iter()
+                .map(|frame| (frame.lerp_time, frame.interpolation))
+        ]
+        .collect();
+        let curves: Vec<Option<TrajectoryInterpolation>> = itertools::chain![
+            [Some(self.initial.curve)],
+            self.waypoints.iter().map(|frame| frame.change_curve)
+        ]
+        .collect();
+
+        // A curve spanning keyframes `start..=end` spans `n + 1 - end..=n + 1 - start` when reversed
+        let mut reversed_curves = vec![None; n + 1];
+        for (start, curve) in curves.iter().enumerate() {
+            let Some(curve) = *curve else {
+                continue;
+            };
+            let end = (start + 1..=n)
+                .find(|&i| curves[i].is_some())
+                .unwrap_or(n + 1);
+            reversed_curves[n + 1 - end] = Some(curve);
+        }
+
+        std::mem::swap(&mut self.initial.transform, &mut self.last);
+        self.waypoints.make_contiguous().reverse();
+
+        let (lerp_time, interpolation) = segments[n];
+        self.initial.lerp_time = lerp_time;
+        self.initial.interpolation = interpolation.reversed();
+        self.initial.curve = reversed_curves[0].unwrap_or_default();
+        for (i, frame) in self.waypoints.iter_mut().enumerate() {
+            let (lerp_time, interpolation) = segments[n - 1 - i];
+            frame.lerp_time = lerp_time;
+            frame.interpolation = interpolation.reversed();
+            frame.change_curve = reversed_curves[i + 1];
+        }
+    }
 }
 
 pub fn bake_movement<T: 'static + Interpolatable>(
@@ -522,3 +811,194 @@ fn ease_out<T: Float>(t: T) -> T {
     let t = T::ONE - t;
     T::ONE - t * t * t
 }
+
+fn ease_in_expo(t: f32) -> f32 {
+    if t <= 0.0 {
+        0.0
+    } else {
+        2.0_f32.powf(10.0 * t - 10.0)
+    }
+}
+
+fn ease_in_back(t: f32) -> f32 {
+    const OVERSHOOT: f32 = 1.70158;
+    (OVERSHOOT + 1.0) * t * t * t - OVERSHOOT * t * t
+}
+
+fn ease_in_elastic(t: f32) -> f32 {
+    if t <= 0.0 || t >= 1.0 {
+        return t;
+    }
+    let period = 2.0 * std::f32::consts::PI / 3.0;
+    -(2.0_f32.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * period).sin()
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+/// Evaluates the cubic bezier easing curve at the given `x` coordinate.
+fn cubic_bezier(p1: vec2<f32>, p2: vec2<f32>, x: f32) -> f32 {
+    let x = x.clamp(0.0, 1.0);
+    let (x1, x2) = (p1.x.clamp(0.0, 1.0), p2.x.clamp(0.0, 1.0));
+    let curve = |a: f32, b: f32, s: f32| {
+        let r = 1.0 - s;
+        3.0 * r * r * s * a + 3.0 * r * s * s * b + s * s * s
+    };
+
+    // x(s) is monotonic for control points in 0..=1, so a bisection always converges
+    let (mut low, mut high) = (0.0, 1.0);
+    let mut s = x;
+    for _ in 0..32 {
+        let value = curve(x1, x2, s);
+        if (value - x).abs() < 1e-5 {
+            break;
+        }
+        if value < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+    curve(p1.y, p2.y, s)
+}
+
+#[test]
+fn test_danger_toggles() {
+    let mut movement = Movement::new(1000, TransformLight::identity());
+    movement.waypoints.push_back(Waypoint {
+        danger: Some(true),
+        ..Waypoint::new(1000, TransformLight::identity())
+    });
+    movement.waypoints.push_back(Waypoint {
+        danger: Some(false),
+        ..Waypoint::new(1000, TransformLight::identity())
+    });
+
+    // Waypoints start at 1000, 2000, and 3000
+    assert!(!movement.danger_at(false, 1500));
+    assert!(movement.danger_at(false, 2000));
+    assert!(movement.danger_at(false, 2999));
+    assert!(!movement.danger_at(false, 3500));
+}
+
+#[test]
+fn test_shape_morph() {
+    let mut movement = Movement::new(1000, TransformLight::identity());
+    movement.waypoints.push_back(Waypoint {
+        shape: Some(Shape::circle(r32(3.0))),
+        ..Waypoint::new(1000, TransformLight::identity())
+    });
+    movement.waypoints.iter_mut().for_each(|frame| {
+        frame.interpolation = MoveInterpolation::Linear;
+    });
+
+    // Waypoints start at 1000 and 2000
+    let base = Shape::circle(r32(1.0));
+    assert_eq!(movement.get_shape(1000, base), base);
+    assert_eq!(movement.get_shape(1500, base), Shape::circle(r32(2.0)));
+    assert_eq!(movement.get_shape(2500, base), Shape::circle(r32(3.0)));
+}
+
+#[test]
+fn test_relative_transform() {
+    let parent = TransformLight {
+        translation: vec2(1.0, 0.0).as_r32(),
+        rotation: Angle::from_degrees(r32(90.0)),
+        scale: r32(2.0),
+        ..TransformLight::identity()
+    };
+    let child = TransformLight {
+        translation: vec2(1.0, 0.0).as_r32(),
+        ..TransformLight::identity()
+    };
+    let result = child.relative_to(&parent);
+    assert!((result.translation - vec2(1.0, 2.0).as_r32()).len() < r32(1e-5));
+    assert_eq!(result.scale, r32(2.0));
+    assert_eq!(result.rotation, parent.rotation);
+}
+
+#[test]
+fn test_easing_endpoints() {
+    let easings = [EaseDirection::In, EaseDirection::Out, EaseDirection::InOut]
+        .into_iter()
+        .flat_map(|dir| {
+            [
+                MoveInterpolation::Cubic(dir),
+                MoveInterpolation::Quart(dir),
+                MoveInterpolation::Expo(dir),
+                MoveInterpolation::Back(dir),
+                MoveInterpolation::Elastic(dir),
+                MoveInterpolation::Bounce(dir),
+            ]
+        })
+        .chain([MoveInterpolation::Bezier {
+            x1: r32(0.25),
+            y1: r32(0.1),
+            x2: r32(0.25),
+            y2: r32(1.0),
+        }]);
+    for easing in easings {
+        let start = easing.apply(r32(0.0)).as_f32();
+        let end = easing.apply(r32(1.0)).as_f32();
+        assert!(start.abs() < 1e-3, "{easing:?} starts at {start}");
+        assert!((end - 1.0).abs() < 1e-3, "{easing:?} ends at {end}");
+    }
+
+    let linear = MoveInterpolation::Bezier {
+        x1: r32(0.0),
+        y1: r32(0.0),
+        x2: r32(1.0),
+        y2: r32(1.0),
+    };
+    let half = linear.apply(r32(0.3)).as_f32();
+    assert!((half - 0.3).abs() < 1e-3, "linear bezier gave {half}");
+}
+
+#[test]
+fn test_reverse_time() {
+    let mut movement = Movement::new(500, TransformLight::identity());
+    movement.waypoints.push_back(Waypoint {
+        interpolation: MoveInterpolation::EaseIn,
+        change_curve: Some(TrajectoryInterpolation::Bezier),
+        ..Waypoint::new(250, TransformLight::scale(r32(2.0)))
+    });
+    movement
+        .waypoints
+        .push_back(Waypoint::new(750, TransformLight::scale(r32(3.0))));
+
+    let original = movement.clone();
+    movement.reverse_time();
+    assert_eq!(movement.duration(), original.duration());
+    assert_eq!(movement.get_fade_in(), original.get_fade_out());
+    assert_eq!(
+        movement.waypoints[0].interpolation,
+        MoveInterpolation::EaseOut
+    );
+    assert_eq!(
+        movement.initial.curve,
+        TrajectoryInterpolation::Bezier,
+        "the bezier curve ends the original movement, so it starts the reversed one"
+    );
+    assert_eq!(
+        movement.waypoints[1].change_curve,
+        Some(TrajectoryInterpolation::Linear)
+    );
+
+    movement.reverse_time();
+    assert_eq!(movement, original);
+}
@@ -13,9 +13,14 @@ pub struct Player {
     /// Whether currently perfectly inside of any light.
     /// Controlled by the collider.
     pub is_perfect: bool,
-    /// Lights which are at their waypoint and the player is perfectly inside.
+    /// Lights which are at their waypoint and the player is perfectly inside,
+    /// with the signed offset of the hit (negative when early).
     /// Controlled by the collider.
-    pub perfect_waypoints: Vec<usize>,
+    pub perfect_waypoints: Vec<(usize, Time)>,
+    /// Sustain lights inside of their hold window (by event id)
+    /// and whether the player is holding each of them.
+    /// Controlled by the collider.
+    pub sustain_lights: Vec<(usize, bool)>,
 
     /// Event id of the closest friendly light.
     pub closest_light: Option<usize>,
@@ -23,6 +28,8 @@ pub struct Player {
     pub light_distance: Option<R32>,
     /// Distance to the closest dangerous light.
     pub danger_distance: Option<R32>,
+    /// How deep the player is inside of the dangerous lights, measured from the edge.
+    pub danger_depth: Option<R32>,
 
     pub tail: Vec<PlayerTail>,
 }
@@ -53,10 +60,12 @@ impl Player {
 
             is_perfect: false,
             perfect_waypoints: Vec::new(),
+            sustain_lights: Vec::new(),
 
             closest_light: None,
             light_distance: None,
             danger_distance: None,
+            danger_depth: None,
 
             tail: Vec::new(),
         }
@@ -97,13 +106,15 @@ impl Player {
     pub fn reset_distance(&mut self) {
         self.is_perfect = false;
         self.perfect_waypoints.clear();
+        self.sustain_lights.clear();
         self.closest_light = None;
         self.light_distance = None;
         self.danger_distance = None;
+        self.danger_depth = None;
     }
 
     pub fn update_distance_simple(&mut self, light: &Collider) {
-        self.update_distance(light, None, false, r32(-1.0), false)
+        self.update_distance(light, None, false, r32(-1.0), None);
     }
 
     /// Update player's light distance, perfect measurement, and waypoint detection.
@@ -112,31 +123,40 @@ impl Player {
         &mut self,
         light: &Light,
         last_rhythm: &HashMap<(usize, WaypointId), Time>,
+        judgement: &JudgementConfig,
     ) {
         let (time, waypoint) = light.closest_waypoint;
-        let at_waypoint = matches!(waypoint, WaypointId::Frame(_))
-            && time > -COYOTE_TIME
-            && time < BUFFER_TIME
+        let offset = -time;
+        let at_waypoint = (matches!(waypoint, WaypointId::Frame(_))
+            && judgement.contains(offset)
             && light
                 .event_id
-                .is_some_and(|event| !last_rhythm.contains_key(&(event, waypoint)));
-        self.update_distance(
+                .is_some_and(|event| !last_rhythm.contains_key(&(event, waypoint))))
+        .then_some(offset);
+        let inside = self.update_distance(
             &light.collider,
             light.event_id,
             light.danger,
             light.hollow,
             at_waypoint,
-        )
+        );
+        if light.sustain
+            && !light.danger
+            && let Some(event) = light.event_id
+        {
+            self.sustain_lights.push((event, inside));
+        }
     }
 
+    /// Returns whether the player is inside the light.
     fn update_distance(
         &mut self,
         light: &Collider,
         light_id: Option<usize>,
         danger: bool,
         hollow: R32,
-        at_waypoint: bool,
-    ) {
+        at_waypoint: Option<Time>,
+    ) -> bool {
         let leeway = if danger {
             // NOTE: Danger lights do not give leeway (that would be the opposite of leeway)
             Coord::ZERO
@@ -152,7 +172,7 @@ impl Player {
 
         if !(min_distance..=max_distance).contains(&raw_distance) {
             // Outside of the light or inside of the hollow light
-            return;
+            return false;
         }
 
         // Account for hollow lights
@@ -164,23 +184,20 @@ impl Player {
         };
         if danger {
             update(&mut self.danger_distance);
+            let depth = (raw_distance - min_distance).min(max_distance - raw_distance);
+            self.danger_depth = Some(self.danger_depth.map_or(depth, |old| old.max(depth)));
         } else {
             if self.light_distance.is_none_or(|old| distance < old) {
                 self.light_distance = Some(distance);
                 self.closest_light = light_id;
             }
 
-            let radius = match self.collider.shape {
-                Shape::Circle { radius } => radius,
-                Shape::Line { .. } => unimplemented!(),
-                Shape::Rectangle { .. } => unimplemented!(),
-            };
-            if distance < radius {
+            // NOTE: the player is always a circle
+            if distance < self.collider.shape.bounding_radius() {
                 self.is_perfect = true;
-                if at_waypoint {
-                    self.perfect_waypoints.extend(light_id);
-                }
+                self.perfect_waypoints.extend(light_id.zip(at_waypoint));
             }
         }
+        true
     }
 }
@@ -3,6 +3,11 @@ use super::*;
 pub const DISCRETE_PERFECT: i32 = 1000;
 pub const DISCRETE_OK: i32 = 100;
 pub const DYNAMIC_SCALE: f32 = 1000.0;
+pub const SUSTAIN_SCALE: f32 = 1000.0;
+/// Width of a single bin of the hit error histogram.
+pub const HIT_ERROR_BIN: Time = TIME_IN_FLOAT_TIME / 100; // 10ms
+/// Number of bins in the hit error histogram, centered around zero.
+pub const HIT_ERROR_BINS: usize = 21;
 /// The maximum distance where precision matters, beyond that distance
 /// everything is disregarded as too far from the light.
 pub const MAX_PREC_DISTANCE: f32 = 1.5;
@@ -19,6 +24,21 @@ pub enum ScoreGrade {
     SSS,
 }
 
+/// Judgement of a single waypoint hit, see [`JudgementConfig`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Judgement {
+    Perfect,
+    Great,
+    Ok,
+    Miss,
+}
+
+impl Judgement {
+    pub fn is_hit(self) -> bool {
+        !matches!(self, Self::Miss)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Score {
@@ -41,6 +61,10 @@ pub struct CalculatedScore {
 pub struct ScoreMetrics {
     pub discrete: DiscreteMetrics,
     pub dynamic: DynamicMetrics,
+    #[serde(default)]
+    pub sustain: SustainMetrics,
+    #[serde(default)]
+    pub timing: TimingMetrics,
 }
 
 /// Raw discrete metrics.
@@ -71,6 +95,36 @@ pub struct DynamicMetrics {
     pub frames_red: usize,
 }
 
+/// Raw metrics of the waypoint hit timings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimingMetrics {
+    pub perfect: usize,
+    pub great: usize,
+    pub ok: usize,
+    pub miss: usize,
+    /// Sum of the signed offsets of all hits, negative when early.
+    pub offset_sum: Time,
+    /// Histogram of the signed hit offsets in [`HIT_ERROR_BINS`] bins of [`HIT_ERROR_BIN`] width
+    /// centered around zero. Offsets outside of the range are put into the outermost bins.
+    pub histogram: Vec<usize>,
+}
+
+/// Raw metrics of holding the sustain lights.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SustainMetrics {
+    /// Total number of frames summed over every sustain light in its hold window.
+    pub frames: usize,
+    /// Number of those frames when the player was holding the light.
+    pub frames_held: usize,
+    /// Number of times the player has left a sustain light before its hold window ended.
+    pub breaks: usize,
+    /// Total score awarded for holding sustain lights.
+    pub score: i32,
+    /// Sustain lights held on the last frame, used to detect breaks.
+    #[serde(skip)]
+    holding: Vec<usize>,
+}
+
 impl Default for Score {
     fn default() -> Self {
         Self::new(R32::ONE)
@@ -116,8 +170,13 @@ impl Score {
     /// Update the score given current player state.
     /// Returns `true` if the player hits the perfect rhythm.
     #[must_use]
-    pub fn update(&mut self, player: &Player, delta_time: FloatTime) -> bool {
-        let rhythm = self.metrics.update(player, delta_time);
+    pub fn update(
+        &mut self,
+        player: &Player,
+        judgement: &JudgementConfig,
+        delta_time: FloatTime,
+    ) -> bool {
+        let rhythm = self.metrics.update(player, judgement, delta_time);
         self.calculated = CalculatedScore::from_metrics(&self.metrics, self.multiplier);
         rhythm
     }
@@ -150,7 +209,8 @@ impl CalculatedScore {
         let discrete = (metrics.discrete.score as f32 * accuracy.as_f32()).ceil() as i32;
 
         Self {
-            combined: ((discrete + metrics.dynamic.score) as f32 * multiplier.as_f32()) as i32,
+            combined: ((discrete + metrics.dynamic.score + metrics.sustain.score) as f32
+                * multiplier.as_f32()) as i32,
             accuracy,
             precision,
         }
@@ -168,15 +228,30 @@ impl ScoreMetrics {
         Self {
             discrete: DiscreteMetrics::new(),
             dynamic: DynamicMetrics::new(),
+            sustain: SustainMetrics::new(),
+            timing: TimingMetrics::new(),
         }
     }
 
     /// Update the metrics given the new player state.
-    pub fn update(&mut self, player: &Player, delta_time: FloatTime) -> bool {
+    pub fn update(
+        &mut self,
+        player: &Player,
+        judgement: &JudgementConfig,
+        delta_time: FloatTime,
+    ) -> bool {
         let rhythm = self.discrete.update(player);
         self.dynamic.update(player, delta_time);
+        self.sustain.update(player, delta_time);
+        self.timing.update(player, judgement);
         rhythm
     }
+
+    /// Register a waypoint the player has missed.
+    pub fn missed_rhythm(&mut self) {
+        self.discrete.missed_rhythm();
+        self.timing.miss += 1;
+    }
 }
 
 impl Default for DiscreteMetrics {
@@ -262,3 +337,131 @@ impl DynamicMetrics {
         }
     }
 }
+
+impl Default for SustainMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SustainMetrics {
+    pub fn new() -> Self {
+        Self {
+            frames: 0,
+            frames_held: 0,
+            breaks: 0,
+            score: 0,
+            holding: Vec::new(),
+        }
+    }
+
+    /// Whether the level had any sustain lights to hold.
+    pub fn is_empty(&self) -> bool {
+        self.frames == 0
+    }
+
+    /// The fraction of the hold windows the player has spent holding the lights (in range 0..=1).
+    pub fn held(&self) -> R32 {
+        if self.frames == 0 {
+            R32::ONE
+        } else {
+            r32(self.frames_held as f32 / self.frames as f32)
+        }
+    }
+
+    /// Update the metrics given the new player state.
+    pub fn update(&mut self, player: &Player, delta_time: FloatTime) {
+        let safe = player.danger_distance.is_none();
+        for &(light, inside) in &player.sustain_lights {
+            self.frames += 1;
+            if inside && safe {
+                self.frames_held += 1;
+                self.score += (delta_time.as_f32() * SUSTAIN_SCALE).ceil() as i32;
+            } else if self.holding.contains(&light) {
+                self.breaks += 1;
+            }
+        }
+        self.holding = player
+            .sustain_lights
+            .iter()
+            .filter(|&&(_, inside)| inside && safe)
+            .map(|&(light, _)| light)
+            .collect();
+    }
+}
+
+impl Default for TimingMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimingMetrics {
+    pub fn new() -> Self {
+        Self {
+            perfect: 0,
+            great: 0,
+            ok: 0,
+            miss: 0,
+            offset_sum: 0,
+            histogram: vec![0; HIT_ERROR_BINS],
+        }
+    }
+
+    /// Total number of the waypoints hit within the timing windows.
+    pub fn hits(&self) -> usize {
+        self.perfect + self.great + self.ok
+    }
+
+    /// Average signed offset of the hits, negative when early.
+    pub fn mean_offset(&self) -> Option<Time> {
+        let hits = self.hits();
+        (hits > 0).then(|| self.offset_sum / hits as Time)
+    }
+
+    /// Update the metrics given the new player state.
+    pub fn update(&mut self, player: &Player, judgement: &JudgementConfig) {
+        if player.danger_distance.is_some() {
+            return;
+        }
+        for &(_, offset) in &player.perfect_waypoints {
+            self.record(offset, judgement.judge(offset));
+        }
+    }
+
+    fn record(&mut self, offset: Time, judgement: Judgement) {
+        match judgement {
+            Judgement::Perfect => self.perfect += 1,
+            Judgement::Great => self.great += 1,
+            Judgement::Ok => self.ok += 1,
+            Judgement::Miss => {
+                self.miss += 1;
+                return;
+            }
+        }
+        self.offset_sum += offset;
+
+        let half = (HIT_ERROR_BINS / 2) as Time;
+        let bin = (offset + HIT_ERROR_BIN / 2).div_euclid(HIT_ERROR_BIN);
+        let bin = (bin.clamp(-half, half) + half) as usize;
+        if let Some(count) = self.histogram.get_mut(bin) {
+            *count += 1;
+        }
+    }
+}
+
+#[test]
+fn test_hit_error_histogram() {
+    let judgement = JudgementConfig::default();
+    let mut timing = TimingMetrics::new();
+    for offset in [-3, 4, -12, 38, 1000] {
+        timing.record(offset, judgement.judge(offset));
+    }
+    let center = HIT_ERROR_BINS / 2;
+    assert_eq!(timing.histogram[center], 2);
+    assert_eq!(timing.histogram[center - 1], 1);
+    assert_eq!(timing.histogram[center + 4], 1);
+    assert_eq!((timing.perfect, timing.great, timing.ok), (3, 1, 0));
+    assert_eq!(timing.miss, 1);
+    assert_eq!(timing.mean_offset(), Some(27 / 4));
+}
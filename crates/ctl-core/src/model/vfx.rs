@@ -21,6 +21,12 @@ impl VfxValue {
         self.value.target = target;
     }
 
+    /// Set the value immediately, skipping the smoothing.
+    pub fn set_exact(&mut self, time_left: FloatTime, value: R32) {
+        self.set(time_left, value);
+        self.value.current = value;
+    }
+
     pub fn update(&mut self, delta_time: FloatTime) {
         self.time_left = (self.time_left - delta_time).max(FloatTime::ZERO);
         if self.time_left.as_f32() <= 0.0 {
@@ -39,6 +45,15 @@ pub struct Vfx {
     pub curvature: VfxValue,
     pub noise_offset: VfxValue,
     pub spotlight: VfxValue,
+    /// Additional zoom of the camera, 0 is normal.
+    pub camera_zoom: VfxValue,
+    /// Rotation of the camera in degrees.
+    pub camera_rotation: VfxValue,
+    pub camera_pan_x: VfxValue,
+    pub camera_pan_y: VfxValue,
+    pub flash: VfxValue,
+    pub tint: VfxValue,
+    pub tint_color: TintColor,
 }
 
 impl Vfx {
@@ -51,6 +66,13 @@ impl Vfx {
             curvature: VfxValue::new(2.0, 1.0, 0.0),
             noise_offset: VfxValue::new(2.0, 1.0, 0.0),
             spotlight: VfxValue::new(2.0, 1.0, 0.0),
+            camera_zoom: VfxValue::new(2.0, 1.0, 0.0),
+            camera_rotation: VfxValue::new(2.0, 1.0, 0.0),
+            camera_pan_x: VfxValue::new(2.0, 1.0, 0.0),
+            camera_pan_y: VfxValue::new(2.0, 1.0, 0.0),
+            flash: VfxValue::new(10.0, 1.0, 1.0),
+            tint: VfxValue::new(2.0, 1.0, 0.0),
+            tint_color: TintColor::Highlight,
         }
     }
 
@@ -61,6 +83,12 @@ impl Vfx {
         self.curvature.time_left = FloatTime::ZERO;
         self.noise_offset.time_left = FloatTime::ZERO;
         self.spotlight.time_left = FloatTime::ZERO;
+        self.camera_zoom.time_left = FloatTime::ZERO;
+        self.camera_rotation.time_left = FloatTime::ZERO;
+        self.camera_pan_x.time_left = FloatTime::ZERO;
+        self.camera_pan_y.time_left = FloatTime::ZERO;
+        self.flash.time_left = FloatTime::ZERO;
+        self.tint.time_left = FloatTime::ZERO;
         self.camera_shake = R32::ZERO;
     }
 
@@ -71,6 +99,29 @@ impl Vfx {
         self.curvature.update(delta_time);
         self.noise_offset.update(delta_time);
         self.spotlight.update(delta_time);
+        self.camera_zoom.update(delta_time);
+        self.camera_rotation.update(delta_time);
+        self.camera_pan_x.update(delta_time);
+        self.camera_pan_y.update(delta_time);
+        self.flash.update(delta_time);
+        self.tint.update(delta_time);
+    }
+
+    /// Zoom multiplier of the camera, 1 is normal.
+    pub fn camera_zoom(&self) -> f32 {
+        1.0 + self.camera_zoom.value.current.as_f32().max(-0.5)
+    }
+
+    pub fn camera_rotation(&self) -> Angle<f32> {
+        Angle::from_degrees(self.camera_rotation.value.current.as_f32())
+    }
+
+    /// Offset of the camera center.
+    pub fn camera_pan(&self) -> vec2<f32> {
+        vec2(
+            self.camera_pan_x.value.current.as_f32(),
+            self.camera_pan_y.value.current.as_f32(),
+        )
     }
 }
 
@@ -0,0 +1,97 @@
+//! Performance points used for the global player ranking.
+//!
+//! Every level gets a difficulty value, and every finished play is awarded
+//! performance points based on the difficulty and the achieved score.
+//! The rating of a player is a weighted sum of their best performances,
+//! so that a few great plays count more than many mediocre ones.
+
+use crate::{
+    difficulty::LevelDifficulty,
+    model::{Level, ScoreGrade},
+    score::ScoreMeta,
+};
+
+use geng::prelude::*;
+
+/// Weight multiplier applied to each next best performance of a player.
+pub const RATING_WEIGHT_DECAY: f32 = 0.95;
+/// Performance points awarded for a perfect play on a level of difficulty 1.
+const PERFORMANCE_SCALE: f32 = 10.0;
+
+/// Estimate the difficulty of the level (see [`LevelDifficulty`]).
+pub fn level_difficulty(level: &Level) -> R32 {
+    LevelDifficulty::analyze(level).stars
+}
+
+/// Calculate the performance points awarded for the play.
+/// Failed and automated plays, and plays with a custom health config, are worth nothing.
+pub fn performance(difficulty: R32, meta: &ScoreMeta) -> R32 {
+    let score = &meta.score;
+    if meta.category.mods.clean_auto || score.calculate_grade(meta.completion) == ScoreGrade::F {
+        return R32::ZERO;
+    }
+
+    let accuracy = score.calculated.accuracy.as_f32().clamp(0.0, 1.0);
+    let precision = score.calculated.precision.as_f32().clamp(0.0, 1.0);
+    let value = PERFORMANCE_SCALE
+        * difficulty.as_f32().max(0.0).powi(2)
+        * accuracy.powi(4)
+        * (0.5 + 0.5 * precision)
+        * meta.category.multiplier().as_f32();
+    r32(value)
+}
+
+/// Calculate the rating of a player given the performance on every level they played.
+pub fn player_rating(performances: impl IntoIterator<Item = R32>) -> R32 {
+    let mut performances: Vec<R32> = performances.into_iter().collect();
+    performances.sort_by(|a, b| b.cmp(a));
+    let mut weight = 1.0;
+    let mut rating = 0.0;
+    for performance in performances {
+        rating += performance.as_f32() * weight;
+        weight *= RATING_WEIGHT_DECAY;
+    }
+    r32(rating)
+}
+
+#[test]
+fn test_player_rating_weights() {
+    let rating = player_rating([r32(10.0), r32(20.0)]);
+    assert_eq!(rating, r32(20.0 + 10.0 * RATING_WEIGHT_DECAY));
+    assert_eq!(player_rating([]), R32::ZERO);
+}
+
+#[test]
+fn test_failed_play_performance() {
+    let mut meta = ScoreMeta::default();
+    meta.completion = r32(0.5);
+    assert_eq!(performance(r32(5.0), &meta), R32::ZERO);
+
+    meta.completion = R32::ONE;
+    assert!(performance(r32(5.0), &meta) > R32::ZERO);
+}
+
+#[test]
+fn test_health_preset_performance() {
+    use crate::model::{HealthConfig, HealthPreset};
+
+    let performance_with = |health: HealthConfig| {
+        let mut meta = ScoreMeta::default();
+        meta.completion = R32::ONE;
+        meta.category.health = health;
+        performance(r32(5.0), &meta)
+    };
+    let lenient = performance_with(HealthPreset::Lenient.config());
+    let normal = performance_with(HealthPreset::Normal.config());
+    let strict = performance_with(HealthPreset::Strict.config());
+    let sudden_death = performance_with(HealthPreset::SuddenDeath.config());
+    assert!(lenient < normal);
+    assert!(normal < strict);
+    assert!(strict < sudden_death);
+
+    let custom = HealthConfig {
+        restore_rate: r32(100.0),
+        ..HealthConfig::default()
+    };
+    assert_eq!(performance_with(custom), R32::ZERO);
+}
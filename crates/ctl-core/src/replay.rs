@@ -0,0 +1,110 @@
+use super::*;
+
+use crate::simulation::Simulation;
+
+const REPLAY_VERSION: u32 = 1;
+/// Maximum music offset (in either direction) that the user can set in the options.
+pub const MAX_USER_MUSIC_OFFSET: Time = 100;
+
+/// Recorded player input of a single run of a level.
+/// Playing it back through the simulation reproduces the exact same score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    /// Hash of the level that the replay was recorded on.
+    pub level_hash: String,
+    /// Config used during the run, including modifiers and health.
+    pub config: LevelConfig,
+    /// Total music offset (user option and level set offset) used during the run.
+    pub music_offset: Time,
+    /// Time the level was started from.
+    pub start_time: Time,
+    /// Input of every simulation tick, from the level start until the player finished or lost.
+    pub ticks: Vec<ReplayTick>,
+}
+
+/// Input of a single simulation tick.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ReplayTick {
+    /// Duration of the tick in (real-time) seconds.
+    pub delta_time: FloatTime,
+    /// Position of the player's cursor in world coordinates.
+    pub position: vec2<Coord>,
+    /// Whether the game was paused during the tick.
+    /// Pauses of the run are reconstructed from these during playback.
+    pub paused: bool,
+}
+
+/// Sequential playback of the ticks recorded in a [`Replay`].
+#[derive(Debug, Clone)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    next_tick: usize,
+}
+
+impl Replay {
+    pub fn new(
+        level_hash: String,
+        config: LevelConfig,
+        music_offset: Time,
+        start_time: Time,
+    ) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            level_hash,
+            config,
+            music_offset,
+            start_time,
+            ticks: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, tick: ReplayTick) {
+        self.ticks.push(tick);
+    }
+
+    /// Play back the whole replay on the level without rendering or audio.
+    /// Returns the state of the simulation after the last tick.
+    pub fn simulate(self, level: Rc<Level>) -> Simulation {
+        let mut sim = Simulation::new(
+            level,
+            self.config.clone(),
+            self.music_offset,
+            self.start_time,
+            None,
+        );
+        sim.init(self.start_time);
+        sim.run(&mut ReplayPlayback::new(self));
+        sim
+    }
+}
+
+impl ReplayPlayback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next_tick: 0,
+        }
+    }
+
+    /// Returns the input of the next tick, or `None` if the replay has ended.
+    pub fn next_tick(&mut self) -> Option<ReplayTick> {
+        let tick = self.replay.ticks.get(self.next_tick).copied();
+        if tick.is_some() {
+            self.next_tick += 1;
+        }
+        tick
+    }
+
+    /// Returns the input of the most recently played back tick.
+    pub fn last_tick(&self) -> Option<ReplayTick> {
+        self.next_tick
+            .checked_sub(1)
+            .and_then(|i| self.replay.ticks.get(i))
+            .copied()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next_tick >= self.replay.ticks.len()
+    }
+}
@@ -1,17 +1,33 @@
 use crate::{
-    model::{HealthConfig, LevelModifiers, Score, ScoreGrade},
-    types::{FloatTime, Time, UserInfo},
+    model::{HealthConfig, HealthPreset, LevelModifiers, LightMode, Modifier, Score, ScoreGrade},
+    replay::Replay,
+    types::{FloatTime, Id, Time, UserInfo},
 };
 
 use geng::prelude::*;
 
-const SCORE_VERSION: u32 = 1;
+/// Bumped whenever the scoring changes, scores of different versions are not compared.
+/// Version 2 judges the waypoint hits (see [`crate::model::JudgementConfig`]).
+const SCORE_VERSION: u32 = 2;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubmitScore {
     pub level_hash: String,
     pub score: i32,
     pub meta: String,
+    /// Recorded input of the run, used by the server to verify the score.
+    #[serde(default)]
+    pub replay: Option<Replay>,
+}
+
+/// Query for the leaderboard of a level.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct FetchScoresQuery {
+    /// Only fetch the scores in the category with the given key (see [`ScoreCategory::key`]).
+    pub category: Option<String>,
+    /// Maximum number of scores to fetch.
+    pub limit: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +38,52 @@ pub struct ServerScore {
     pub meta: Option<String>,
 }
 
+/// Statistics of all the plays of a user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserProfile {
+    pub user: UserInfo,
+    /// Total number of submitted plays.
+    pub play_count: u64,
+    /// Number of levels with the best score achieving each grade.
+    pub grades: Vec<(ScoreGrade, u64)>,
+    /// The most recent plays, newest first.
+    pub recent: Vec<ProfileScore>,
+    /// The best score on every played level, highest first.
+    pub best: Vec<ProfileScore>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileScore {
+    pub level_id: Id,
+    pub level_set_id: Id,
+    pub level_name: String,
+    pub music_name: String,
+    pub score: i32,
+    pub submitted_at: ::time::OffsetDateTime,
+    pub meta: Option<String>,
+}
+
+/// Query for a page of the global player ranking.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RankingsQuery {
+    pub page: u32,
+    /// Maximum number of players per page.
+    pub limit: Option<u32>,
+}
+
+/// Position of a player in the global ranking.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerRanking {
+    /// Position in the ranking, starting from 1.
+    pub rank: u32,
+    pub user: UserInfo,
+    /// Weighted sum of the performance points (see [`crate::rating`]).
+    pub rating: R32,
+    /// Number of levels with a ranked play.
+    pub ranked_levels: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoreEntry {
     pub user: UserInfo,
@@ -81,6 +143,64 @@ impl ScoreCategory {
             health,
         }
     }
+
+    /// Iterate over the active modifiers, including the non-default health preset.
+    pub fn modifiers(&self) -> impl Iterator<Item = Modifier> {
+        let health = self
+            .health
+            .preset()
+            .filter(|&preset| preset != HealthPreset::Normal)
+            .map(Modifier::Health);
+        self.mods.iter().chain(health)
+    }
+
+    /// Performance multiplier of the modifiers and the health preset.
+    /// Custom health configs are not ranked, since they can make any level trivial.
+    pub fn multiplier(&self) -> R32 {
+        match self.health.preset() {
+            Some(preset) => self.mods.multiplier() * Modifier::Health(preset).multiplier(),
+            None => R32::ZERO,
+        }
+    }
+
+    /// A stable identifier of the category used to group the scores on the leaderboard.
+    /// Hashed from an explicit text representation of the fields instead of the
+    /// serialized layout, so that adding fields does not move the existing scores.
+    pub fn key(&self) -> String {
+        crate::util::calculate_hash(self.key_text().as_bytes())
+    }
+
+    /// New fields must only be appended when they differ from their defaults.
+    fn key_text(&self) -> String {
+        let mods = &self.mods;
+        let health = &self.health;
+        let light = match mods.light {
+            None => "none",
+            Some(LightMode::Flashlight) => "flashlight",
+            Some(LightMode::Spotlight) => "spotlight",
+        };
+        let mut text = format!(
+            "v{};clean_auto={};nofail={};sudden={};hidden={};touch={};time_scale={};light={light};health={},{},{},{}",
+            self.version,
+            u8::from(mods.clean_auto),
+            u8::from(mods.nofail),
+            u8::from(mods.sudden),
+            u8::from(mods.hidden),
+            u8::from(mods.touch),
+            mods.time_scale.as_f32(),
+            health.max.as_f32(),
+            health.dark_decrease_rate.as_f32(),
+            health.danger_decrease_rate.as_f32(),
+            health.restore_rate.as_f32(),
+        );
+        if health.depth_drain {
+            text += ";depth_drain";
+        }
+        if health.sudden_death {
+            text += ";sudden_death";
+        }
+        text
+    }
 }
 
 impl ScoreMeta {
@@ -106,3 +226,16 @@ impl ScoreMeta {
         self.score.calculate_grade(self.completion)
     }
 }
+
+#[test]
+fn test_category_key_stable() {
+    // Changing the key moves all the existing scores to a separate leaderboard
+    assert_eq!(
+        ScoreCategory::default().key(),
+        "904a776e3547d07909819941c80e5a655c8a08ecdc3b3c7b757941d7157e6547"
+    );
+
+    let mut category = ScoreCategory::default();
+    category.health.sudden_death = true;
+    assert_ne!(category.key(), ScoreCategory::default().key());
+}
@@ -0,0 +1,646 @@
+//! Headless gameplay of a level, independent of rendering and audio.
+//!
+//! Can be used to test levels, verify replays and run bots.
+
+use super::*;
+
+use crate::{
+    replay::{ReplayPlayback, ReplayTick},
+    score::PauseIndicator,
+};
+
+/// Fixed tick rate used by the game.
+pub const FIXED_FPS: f64 = 60.0; // TODO: upgrade to 120 i think
+
+/// Fixed tick duration used by the game.
+pub const TICK_DELTA_TIME: f32 = (1.0 / FIXED_FPS) as f32;
+
+#[derive(Debug, Clone)]
+pub enum State {
+    /// Wait for the player to hover the light and some additional time.
+    Starting {
+        /// Time until we can start the game.
+        start_timer: FloatTime,
+        /// Time to start playing music from.
+        music_start_time: Time,
+    },
+    Playing,
+    Lost {
+        /// The time of death.
+        death_time_ms: Time,
+    },
+    Finished,
+}
+
+impl State {
+    /// Returns `true` when the player finished or lost the level.
+    pub fn ended(&self) -> bool {
+        matches!(self, Self::Lost { .. } | Self::Finished)
+    }
+}
+
+/// Gameplay events produced by a simulation tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationEvent {
+    /// The level started playing, music should start from the given time.
+    Start {
+        music_start_time: Time,
+    },
+    /// The player passed a waypoint of a light.
+    Rhythm {
+        judgement: Judgement,
+        /// Signed offset of the hit (negative when early), `None` when missed.
+        offset: Option<Time>,
+    },
+    Finish,
+    Lose,
+}
+
+/// Source of player input for the simulation.
+pub trait InputSource {
+    /// Returns the input for the next tick, or `None` once the input has run out.
+    fn next_input(&mut self, sim: &Simulation) -> Option<ReplayTick>;
+}
+
+impl<F: FnMut(&Simulation) -> Option<ReplayTick>> InputSource for F {
+    fn next_input(&mut self, sim: &Simulation) -> Option<ReplayTick> {
+        self(sim)
+    }
+}
+
+impl InputSource for ReplayPlayback {
+    fn next_input(&mut self, _sim: &Simulation) -> Option<ReplayTick> {
+        self.next_tick()
+    }
+}
+
+/// Bot that follows the safe light closest to its waypoint.
+/// Never runs out of input.
+#[derive(Debug, Clone)]
+pub struct AutoInput {
+    pub delta_time: FloatTime,
+}
+
+impl Default for AutoInput {
+    fn default() -> Self {
+        Self {
+            delta_time: r32(TICK_DELTA_TIME),
+        }
+    }
+}
+
+impl InputSource for AutoInput {
+    fn next_input(&mut self, sim: &Simulation) -> Option<ReplayTick> {
+        let position = sim
+            .level_state
+            .lights
+            .iter()
+            .filter(|light| !light.danger)
+            .min_by_key(|light| light.closest_waypoint.0.abs())
+            .map_or(sim.player.collider.position, |light| {
+                light.collider.position
+            });
+        Some(ReplayTick {
+            delta_time: self.delta_time,
+            position,
+            paused: false,
+        })
+    }
+}
+
+/// Gameplay of a level: player health, score and the state of the lights.
+/// Does not depend on rendering or audio, so it can be used to verify scores.
+#[derive(Debug)]
+pub struct Simulation {
+    /// The level being played.
+    pub level: Rc<Level>,
+    pub config: LevelConfig,
+    /// Time the level was started from.
+    pub start_time: Time,
+    /// Time when the level is considered finished (used in practice).
+    pub end_time: Option<Time>,
+    /// Total music offset (user option and level set offset).
+    pub music_offset: Time,
+
+    pub player: Player,
+    /// Current state of the level.
+    pub level_state: LevelState,
+    pub state: State,
+    pub score: Score,
+    /// List collected rhythm (event_id, waypoint_id)
+    /// with times of their collection.
+    pub recent_rhythm: HashMap<(usize, WaypointId), Time>,
+    /// Times when the game was paused.
+    pub pauses: Vec<PauseIndicator>,
+
+    /// Time since the level was started playing.
+    pub play_time: FloatTime,
+    /// Current exact play time in milliseconds.
+    pub play_time_ms: Time,
+    /// Time since the level started playing, counting towards level completion.
+    pub completion_time: FloatTime,
+}
+
+impl Simulation {
+    pub fn new(
+        level: Rc<Level>,
+        config: LevelConfig,
+        music_offset: Time,
+        start_time: Time,
+        end_time: Option<Time>,
+    ) -> Self {
+        // Patterns are expanded so that every generated light has its own event id
+        let level = if level.has_patterns() {
+            let mut level = Level::clone(&level);
+            level.expand_patterns();
+            Rc::new(level)
+        } else {
+            level
+        };
+
+        Self {
+            player: Player::new(
+                Collider::new(
+                    vec2::ZERO,
+                    Shape::Circle {
+                        radius: config.player.radius,
+                    },
+                ),
+                config.health.max,
+            ),
+            level_state: LevelState::default(),
+            state: State::Starting {
+                start_timer: FloatTime::ZERO, // reset during init
+                music_start_time: Time::ZERO,
+            },
+            score: Score::new(config.modifiers.multiplier()),
+            recent_rhythm: HashMap::new(),
+            pauses: Vec::new(),
+
+            play_time: FloatTime::ZERO,
+            play_time_ms: Time::ZERO,
+            completion_time: FloatTime::ZERO,
+
+            level,
+            config,
+            start_time,
+            end_time,
+            music_offset,
+        }
+    }
+
+    /// Initialize the level by playing the events from the negative time.
+    pub fn init(&mut self, target_time: Time) {
+        // NOTE: double conversion in case of floating point errors
+        // since `play_time` is used to recalculate `play_time_ms` every frame
+        self.play_time = time_to_seconds(target_time);
+        self.play_time_ms = seconds_to_time(self.play_time) - self.music_offset;
+        self.completion_time = FloatTime::ZERO;
+
+        self.player.health.set_ratio(FloatTime::ONE);
+        self.state = State::Starting {
+            start_timer: r32(1.0),
+            music_start_time: target_time,
+        };
+    }
+
+    /// Record the pause if the game is paused while playing.
+    /// Returns `true` if the simulation should not advance this tick.
+    pub fn update_pause(&mut self, delta_time: FloatTime, is_paused: bool) -> bool {
+        if !is_paused || !matches!(self.state, State::Playing) {
+            return false;
+        }
+
+        // Update pause indicator
+        if self
+            .pauses
+            .last_mut()
+            .is_none_or(|ind| ind.time != self.play_time_ms)
+        {
+            self.pauses.push(PauseIndicator {
+                time: self.play_time_ms,
+                duration: FloatTime::ZERO,
+            });
+        };
+        let indicator = self.pauses.last_mut().unwrap();
+        indicator.duration += delta_time;
+        true
+    }
+
+    /// Feed the input into the simulation until the level ends or the input runs out.
+    pub fn run(&mut self, input: &mut impl InputSource) {
+        while !self.state.ended() {
+            let Some(tick) = input.next_input(self) else {
+                break;
+            };
+            self.step(tick);
+        }
+    }
+
+    /// Advance the gameplay by a single tick of (real-time) input.
+    pub fn step(&mut self, tick: ReplayTick) -> Vec<SimulationEvent> {
+        if self.update_pause(tick.delta_time, tick.paused) {
+            return Vec::new();
+        }
+        let delta_time = tick.delta_time * self.config.modifiers.time_scale;
+        self.update(tick.position, delta_time, tick.paused, None)
+    }
+
+    /// Advance the gameplay by a single tick.
+    /// `delta_time` should already be scaled by the time modifier.
+    pub fn update(
+        &mut self,
+        player_target: vec2<Coord>,
+        delta_time: FloatTime,
+        is_paused: bool,
+        vfx: Option<&mut Vfx>,
+    ) -> Vec<SimulationEvent> {
+        let mut events = Vec::new();
+
+        // Move
+        self.player.collider.position = player_target;
+
+        self.update_timers(delta_time);
+        self.render_level(vfx);
+        self.update_player(&mut events);
+        self.update_state(delta_time, is_paused, &mut events);
+
+        events
+    }
+
+    fn update_timers(&mut self, delta_time: FloatTime) {
+        if let State::Starting { .. } = self.state {
+        } else {
+            self.play_time += delta_time;
+            self.play_time_ms = seconds_to_time(self.play_time) - self.music_offset;
+        }
+        if let State::Playing = self.state {
+            self.completion_time += delta_time;
+        }
+    }
+
+    /// Update renderable level state based on the data and play_time.
+    fn render_level(&mut self, vfx: Option<&mut Vfx>) {
+        let ignore_time = match self.state {
+            State::Lost {
+                death_time_ms: death_beat_time,
+            } => Some(death_beat_time),
+            State::Finished => self.end_time,
+            _ => None,
+        };
+        self.level_state = LevelState::render(
+            &self.level,
+            self.play_time_ms.max(0),
+            ignore_time,
+            vfx,
+            true,
+        );
+    }
+
+    /// Update player's light state and check for missed rhythm.
+    fn update_player(&mut self, events: &mut Vec<SimulationEvent>) {
+        let late = self.config.judgement.late;
+        let get_light = |id: Option<usize>, pass: bool| {
+            id.and_then(|id| {
+                self.level_state
+                    .lights
+                    .iter()
+                    .find(|light| light.event_id == Some(id))
+                    .filter(|light| {
+                        // NOTE
+                        // Can only miss after the waypoint, not before, hence no buffer time
+                        // (allows for unpunished early exit)
+                        //
+                        // A miss occurs when the player was inside a light that was leaving its waypoint
+                        // and has missed the coyote time
+                        //
+                        // `pass` used to extend the coyote time for `last_light`,
+                        // because otherwise we cannot detect a miss
+                        // as both will get set to `None` at the same frame
+                        // (allows for unpunished late entrance)
+                        let (time, waypoint) = light.closest_waypoint;
+                        matches!(waypoint, WaypointId::Frame(_))
+                            && time < 0
+                            && (time > -late || pass && time > -late * 2)
+                    })
+                    .map(|light| (id, light.closest_waypoint.1))
+            })
+        };
+        let last_light = get_light(self.player.closest_light, true);
+
+        // Update light state
+        self.player.reset_distance();
+        self.recent_rhythm
+            .retain(|_, time| (self.level_state.time() - *time).abs() <= late * 2);
+        for light in self.level_state.lights.iter() {
+            self.player
+                .update_light_distance(light, &self.recent_rhythm, &self.config.judgement);
+        }
+
+        if let State::Playing = self.state {
+            // Check missed rhythm
+            let light = get_light(self.player.closest_light, false);
+
+            if last_light.is_some()
+                && last_light != light
+                && last_light.is_none_or(|last_light| !self.recent_rhythm.contains_key(&last_light))
+            {
+                // Light has changed and no perfect rhythm
+                self.score.metrics.missed_rhythm();
+                self.collect_rhythm(false);
+                events.push(SimulationEvent::Rhythm {
+                    judgement: Judgement::Miss,
+                    offset: None,
+                });
+            }
+        }
+    }
+
+    fn update_state(
+        &mut self,
+        delta_time: FloatTime,
+        is_paused: bool,
+        events: &mut Vec<SimulationEvent>,
+    ) {
+        match &mut self.state {
+            State::Starting {
+                start_timer,
+                music_start_time,
+            } => {
+                let music_start_time = *music_start_time;
+                *start_timer -= delta_time;
+                if !is_paused && *start_timer <= FloatTime::ZERO && self.player.is_perfect {
+                    self.state = State::Playing;
+                    events.push(SimulationEvent::Start { music_start_time });
+                }
+            }
+            State::Playing => {
+                if self.level_state.is_finished
+                    || self.end_time.is_some_and(|end| self.play_time_ms >= end)
+                {
+                    self.state = State::Finished;
+                    events.push(SimulationEvent::Finish);
+                } else if !self.config.modifiers.clean_auto {
+                    // Player health
+                    let section = self.level.health_multiplier(self.play_time_ms);
+                    if let Some(distance) = self.player.danger_distance {
+                        let depth = self.player.danger_depth.unwrap_or(R32::ZERO);
+                        self.player.health.change(
+                            -self.config.health.danger_drain(distance, depth)
+                                * section
+                                * delta_time,
+                        );
+                    } else if self.player.light_distance.is_some() {
+                        self.player
+                            .health
+                            .change(self.config.health.restore_rate * delta_time);
+                    } else {
+                        self.player
+                            .health
+                            .change(-self.config.health.dark_decrease_rate * section * delta_time);
+                    }
+
+                    let perfect_rhythm =
+                        self.score
+                            .update(&self.player, &self.config.judgement, delta_time);
+                    if perfect_rhythm {
+                        let offset = self
+                            .player
+                            .perfect_waypoints
+                            .iter()
+                            .map(|&(_, offset)| offset)
+                            .min_by_key(|offset| offset.abs());
+                        self.collect_rhythm(true);
+                        events.push(SimulationEvent::Rhythm {
+                            judgement: offset.map_or(Judgement::Miss, |offset| {
+                                self.config.judgement.judge(offset)
+                            }),
+                            offset,
+                        });
+                    }
+
+                    if !self.config.modifiers.nofail && self.player.health.is_min() {
+                        self.state = State::Lost {
+                            death_time_ms: self.play_time_ms,
+                        };
+                        events.push(SimulationEvent::Lose);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Mark the rhythm of the lights the player is at as collected.
+    fn collect_rhythm(&mut self, perfect: bool) {
+        let light_rhythm = |id: usize| {
+            self.level_state
+                .lights
+                .iter()
+                .find(|light| light.event_id == Some(id))
+                .map(|light| {
+                    (
+                        (id, light.closest_waypoint.1),
+                        self.level_state.time() + light.closest_waypoint.0,
+                    )
+                })
+        };
+        if perfect && !self.player.perfect_waypoints.is_empty() {
+            self.recent_rhythm.extend(
+                self.player
+                    .perfect_waypoints
+                    .iter()
+                    .flat_map(|&(id, _)| light_rhythm(id)),
+            );
+        } else if let Some((light, time)) = self.player.closest_light.and_then(light_rhythm) {
+            self.recent_rhythm.insert(light, time);
+        }
+    }
+
+    /// Calculates the current completion percentage (in range 0..=1).
+    pub fn current_completion(&self) -> R32 {
+        let t = if let State::Finished = self.state {
+            // Finished the level, avoid floating point imprecision
+            R32::ONE
+        } else {
+            let duration =
+                self.end_time.unwrap_or_else(|| self.level.last_time()) - self.start_time;
+            self.completion_time / time_to_seconds(duration)
+        };
+        t.clamp(R32::ZERO, R32::ONE)
+    }
+}
+
+/// A single safe light staying in the center for 5 seconds.
+/// Shared with the tests of the other crates through the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+pub fn test_level() -> Level {
+    let transform = TransformLight::default();
+    let mut level = Level::new(r32(120.0));
+    level.events.push(TimedEvent {
+        time: -500,
+        event: Event::Light(LightEvent {
+            danger: false,
+            group: None,
+            sustain: false,
+            shape: Shape::circle(r32(1.0)),
+            movement: Movement {
+                initial: WaypointInitial::new(
+                    500,
+                    TransformLight {
+                        scale: R32::ZERO,
+                        ..transform
+                    },
+                ),
+                waypoints: vec![
+                    Waypoint::new(5000, transform),
+                    Waypoint::new(500, transform),
+                ]
+                .into(),
+                last: TransformLight {
+                    scale: R32::ZERO,
+                    ..transform
+                },
+            },
+        }),
+        envelope: None,
+    });
+    level
+}
+
+#[cfg(test)]
+fn test_simulation() -> Simulation {
+    simulate_level(test_level())
+}
+
+/// Same as [`test_level`] but the light is a sustain light.
+#[cfg(test)]
+fn sustain_simulation() -> Simulation {
+    let mut level = test_level();
+    for event in &mut level.events {
+        if let Event::Light(light) = &mut event.event {
+            light.sustain = true;
+        }
+    }
+    simulate_level(level)
+}
+
+#[cfg(test)]
+fn simulate_level(level: Level) -> Simulation {
+    let mut sim = Simulation::new(
+        Rc::new(level),
+        LevelConfig::default(),
+        Time::ZERO,
+        Time::ZERO,
+        None,
+    );
+    sim.init(Time::ZERO);
+    sim
+}
+
+#[test]
+fn test_auto_finish() {
+    let mut sim = test_simulation();
+    sim.run(&mut AutoInput::default());
+    assert!(matches!(sim.state, State::Finished));
+    assert!(!sim.player.health.is_min());
+    assert_eq!(sim.current_completion(), R32::ONE);
+    assert_eq!(sim.score.metrics.timing.miss, 0);
+}
+
+#[test]
+fn test_no_start_outside_light() {
+    let mut sim = test_simulation();
+    let mut ticks = 0;
+    sim.run(&mut |_: &Simulation| {
+        ticks += 1;
+        (ticks < 600).then_some(ReplayTick {
+            delta_time: r32(TICK_DELTA_TIME),
+            position: vec2(r32(5.0), r32(5.0)),
+            paused: false,
+        })
+    });
+    assert!(matches!(sim.state, State::Starting { .. }));
+    assert_eq!(sim.score.calculated.combined, 0);
+}
+
+#[test]
+fn test_replay_matches() {
+    let level = Rc::new(test_level());
+    let mut sim = test_simulation();
+    let mut auto = AutoInput::default();
+    let mut replay = crate::replay::Replay::new(
+        level.calculate_hash(),
+        sim.config.clone(),
+        Time::ZERO,
+        Time::ZERO,
+    );
+    sim.run(&mut |sim: &Simulation| {
+        let tick = auto.next_input(sim)?;
+        replay.record(tick);
+        Some(tick)
+    });
+
+    let replayed = replay.simulate(level);
+    assert!(replayed.state.ended());
+    assert_eq!(
+        replayed.score.calculated.combined,
+        sim.score.calculated.combined
+    );
+}
+
+#[test]
+fn test_sustain_held() {
+    let mut sim = sustain_simulation();
+    sim.run(&mut AutoInput::default());
+    let sustain = &sim.score.metrics.sustain;
+    assert!(!sustain.is_empty());
+    assert_eq!(sustain.breaks, 0);
+    assert!(sustain.held().as_f32() > 0.99);
+}
+
+#[test]
+fn test_sustain_break() {
+    let mut sim = sustain_simulation();
+    let mut auto = AutoInput::default();
+    sim.run(&mut |sim: &Simulation| {
+        let mut tick = auto.next_input(sim)?;
+        if sim.score.metrics.sustain.frames_held > 60 {
+            tick.position = vec2(r32(5.0), r32(5.0));
+        }
+        Some(tick)
+    });
+    let sustain = &sim.score.metrics.sustain;
+    assert_eq!(sustain.breaks, 1);
+    assert!(sustain.frames_held < sustain.frames);
+}
+
+#[test]
+fn test_sudden_death() {
+    let mut level = test_level();
+    for event in &mut level.events {
+        if let Event::Light(light) = &mut event.event {
+            light.danger = true;
+        }
+    }
+    let mut sim = Simulation::new(
+        Rc::new(level),
+        LevelConfig {
+            health: HealthConfig::preset_sudden_death(),
+            ..default()
+        },
+        Time::ZERO,
+        Time::ZERO,
+        None,
+    );
+    sim.init(Time::ZERO);
+    sim.state = State::Playing;
+    sim.run(&mut |sim: &Simulation| {
+        (sim.play_time_ms < 2000).then_some(ReplayTick {
+            delta_time: r32(TICK_DELTA_TIME),
+            position: vec2::ZERO,
+            paused: false,
+        })
+    });
+    assert!(matches!(sim.state, State::Lost { .. }));
+}
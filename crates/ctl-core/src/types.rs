@@ -355,6 +355,12 @@ pub struct LevelUpdate {
     pub name: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LevelSetUpdate {
+    /// Whether the level set is shown in the list.
+    pub listed: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NewMusician {
     pub name: String,
@@ -362,9 +368,30 @@ pub struct NewMusician {
     pub user: Option<Id>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
 pub struct LevelSetsQuery {
     pub recommended: bool,
+    /// Text to search for in the music, musician and mapper names.
+    pub search: Option<String>,
+    /// Only list level sets uploaded by this user.
+    pub owner: Option<Id>,
+    pub sort: LevelSetsSort,
+    /// Index of the page, starting from 0.
+    pub page: u32,
+    /// Number of level sets per page, capped by the server.
+    pub limit: Option<u32>,
+}
+
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelSetsSort {
+    #[default]
+    Newest,
+    /// Most played first.
+    Popularity,
+    /// Featured first, then newest.
+    Featured,
 }
 
 pub fn non_zero(id: Id) -> Option<Id> {
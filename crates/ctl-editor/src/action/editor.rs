@@ -4,9 +4,17 @@ use super::*;
 pub enum EditorAction {
     Level(LevelAction),
     SetMusicOffset(Time),
+    /// Detect the timing from the music samples and propose it to the mapper.
+    DetectTiming,
+    /// Multiply the beat duration of the proposed timing, e.g. `2.0` to halve the BPM.
+    ScaleTimingProposal(FloatTime),
+    AcceptTimingProposal,
+    DiscardTimingProposal,
     SwitchTab(EditorTab),
     ToggleDynamicVisual,
     ToggleShowOnlySelected,
+    ToggleFrequencyBands,
+    ToggleOnsets,
     Save,
     ToggleUI,
     ToggleGrid,
@@ -48,11 +56,23 @@ impl Editor {
                 }
             }
             EditorAction::SetMusicOffset(offset) => self.set_music_offset(offset),
+            EditorAction::DetectTiming => self.detect_timing(),
+            EditorAction::ScaleTimingProposal(factor) => {
+                if let Some(proposal) = &mut self.timing_proposal {
+                    proposal.scale_beat(factor);
+                }
+            }
+            EditorAction::AcceptTimingProposal => self.accept_timing_proposal(),
+            EditorAction::DiscardTimingProposal => self.timing_proposal = None,
             EditorAction::SwitchTab(tab) => self.tab = tab,
             EditorAction::ToggleDynamicVisual => self.visualize_beat = !self.visualize_beat,
             EditorAction::ToggleShowOnlySelected => {
                 self.show_only_selected = !self.show_only_selected
             }
+            EditorAction::ToggleFrequencyBands => {
+                self.show_frequency_bands = !self.show_frequency_bands
+            }
+            EditorAction::ToggleOnsets => self.show_onsets = !self.show_onsets,
             EditorAction::Save => self.save(),
             EditorAction::ToggleUI => self.render_options.hide_ui = !self.render_options.hide_ui,
             EditorAction::ToggleGrid => {
@@ -8,6 +8,8 @@ pub enum LevelAction {
     List(Option<HistoryLabel>, Vec<LevelAction>), // TODO: smallvec
     Undo,
     Redo,
+    /// Undo or redo to the given number of applied changes in history.
+    JumpHistory(usize),
     Copy,
     CopySelection(Selection),
     SetSelection(Selection),
@@ -19,6 +21,7 @@ pub enum LevelAction {
     RotatePlacement(Angle<Coord>),
     ScrollTime(Change<Time>),
     SetBeatSnap(BeatTime),
+    ToggleOnsetSnap,
     TimelineZoom(Change<f32>),
     CameraPan(Change<vec2<f32>>),
     /// Selected a shape, but the specific action is up to interpretation.
@@ -36,6 +39,8 @@ pub enum LevelAction {
     // Timing
     TimingNew(Time, FloatTime),
     TimingUpdate(usize, FloatTime),
+    /// Replace all timing points.
+    SetTiming(Timing),
 
     // Vfx
     NewRgbSplit(BeatTime),
@@ -45,8 +50,17 @@ pub enum LevelAction {
     NewCurvature(BeatTime),
     NewNoiseOffset(BeatTime),
     NewSpotlight(BeatTime),
+    NewCameraZoom(BeatTime),
+    NewCameraRotate(BeatTime),
+    NewCameraPan(BeatTime),
+    NewFlash(BeatTime),
+    NewTint(BeatTime),
     ChangeEffectDuration(usize, Change<Time>),
     ChangeEffectIntensity(usize, Change<R32>),
+    ChangeCameraRotation(usize, Change<Angle<R32>>),
+    ChangeCameraPan(usize, Change<vec2<Coord>>),
+    SetTintColor(usize, TintColor),
+    SetEffectEnvelope(usize, Option<EffectEnvelope>),
 
     // Light actions
     NewLight(Shape),
@@ -59,10 +73,28 @@ pub enum LevelAction {
     FlipHorizontal(LightId, vec2<Coord>),
     FlipVertical(LightId, vec2<Coord>),
     ToggleDanger(LightId),
+    ToggleSustain(LightId),
     ChangeFadeOut(LightId, Change<Time>),
     ChangeFadeIn(LightId, Change<Time>),
     MoveLight(LightId, Change<Time>, Change<vec2<Coord>>),
     HoverLight(LightId),
+    GroupLights(Vec<LightId>),
+    UngroupLights(Vec<LightId>),
+    ChangeGroup(usize, LightGroup),
+    MakePattern(LightId),
+    /// Change the generator parameters of the pattern, keeping its light.
+    ChangePattern(LightId, PatternEvent),
+    ExpandPattern(LightId),
+
+    // Selection transforms
+    /// Mirror the lights across the line going through the anchor at the given angle.
+    MirrorLights(Vec<LightId>, vec2<Coord>, Angle<Coord>),
+    /// Rotate and scale the positions of the lights around the anchor.
+    RotateScaleLights(Vec<LightId>, vec2<Coord>, Angle<Coord>, Coord),
+    /// Stretch the lights in time relative to the pivot time, re-snapping them to the beat.
+    StretchLightsTime(Vec<LightId>, Time, FloatTime),
+    /// Play the lights backwards within the time span they occupy.
+    ReverseLightsTime(Vec<LightId>),
 
     // Waypoint actions
     NewWaypoint,
@@ -71,6 +103,8 @@ pub enum LevelAction {
     SelectWaypoint(SelectMode, LightId, Vec<WaypointId>, bool),
     RotateWaypointAround(LightId, WaypointId, vec2<Coord>, Change<Angle<Coord>>),
     ScaleWaypoint(LightId, WaypointId, Change<Coord>),
+    StretchWaypoint(LightId, WaypointId, Change<vec2<Coord>>),
+    SetWaypointShape(LightId, WaypointId, Option<Shape>),
     SetWaypointInterpolation(LightId, WaypointId, MoveInterpolation),
     SetWaypointCurve(LightId, WaypointId, Option<TrajectoryInterpolation>),
     MoveWaypoint(
@@ -78,6 +112,8 @@ pub enum LevelAction {
         Vec<(WaypointId, Change<Time>, Change<vec2<Coord>>)>,
     ),
     ChangeHollow(LightId, WaypointId, Change<R32>),
+    ChangePulse(LightId, WaypointId, Change<R32>),
+    SetWaypointDanger(LightId, WaypointId, Option<bool>),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -103,6 +139,7 @@ impl LevelAction {
             LevelAction::List(_, list) => list.iter().all(LevelAction::is_noop),
             LevelAction::Undo => false,
             LevelAction::Redo => false,
+            LevelAction::JumpHistory(_) => false,
             LevelAction::Copy => false,
             LevelAction::CopySelection(_) => false,
             LevelAction::SetSelection(_) => false,
@@ -114,6 +151,7 @@ impl LevelAction {
             LevelAction::RotatePlacement(delta) => *delta == Angle::ZERO,
             LevelAction::ScrollTime(delta) => delta.is_noop(&Time::ZERO),
             LevelAction::SetBeatSnap(_) => false,
+            LevelAction::ToggleOnsetSnap => false,
             LevelAction::TimelineZoom(zoom) => zoom.is_noop(&0.0),
             LevelAction::CameraPan(delta) => delta.is_noop(&vec2::ZERO),
             LevelAction::Shape(..) => false,
@@ -127,6 +165,7 @@ impl LevelAction {
 
             LevelAction::TimingNew(..) => false,
             LevelAction::TimingUpdate(..) => false,
+            LevelAction::SetTiming(_) => false,
 
             LevelAction::NewRgbSplit(_) => false,
             LevelAction::NewPaletteSwap(_) => false,
@@ -135,8 +174,17 @@ impl LevelAction {
             LevelAction::NewCurvature(_) => false,
             LevelAction::NewNoiseOffset(_) => false,
             LevelAction::NewSpotlight(_) => false,
+            LevelAction::NewCameraZoom(_) => false,
+            LevelAction::NewCameraRotate(_) => false,
+            LevelAction::NewCameraPan(_) => false,
+            LevelAction::NewFlash(_) => false,
+            LevelAction::NewTint(_) => false,
             LevelAction::ChangeEffectDuration(_, delta) => delta.is_noop(&0),
             LevelAction::ChangeEffectIntensity(_, delta) => delta.is_noop(&R32::ZERO),
+            LevelAction::ChangeCameraRotation(_, delta) => delta.is_noop(&Angle::ZERO),
+            LevelAction::ChangeCameraPan(_, delta) => delta.is_noop(&vec2::ZERO),
+            LevelAction::SetTintColor(..) => false,
+            LevelAction::SetEffectEnvelope(..) => false,
 
             LevelAction::NewLight(_) => false,
             LevelAction::ToggleDangerPlacement => false,
@@ -153,6 +201,21 @@ impl LevelAction {
             LevelAction::FlipHorizontal(_, _) => false,
             LevelAction::FlipVertical(_, _) => false,
             LevelAction::ToggleDanger(..) => false,
+            LevelAction::ToggleSustain(..) => false,
+            LevelAction::GroupLights(lights) => lights.is_empty(),
+            LevelAction::UngroupLights(lights) => lights.is_empty(),
+            LevelAction::ChangeGroup(..) => false,
+            LevelAction::MakePattern(..) => false,
+            LevelAction::ChangePattern(..) => false,
+            LevelAction::ExpandPattern(..) => false,
+            LevelAction::MirrorLights(lights, ..) => lights.is_empty(),
+            LevelAction::RotateScaleLights(lights, _, angle, scale) => {
+                lights.is_empty() || (*angle == Angle::ZERO && *scale == Coord::ONE)
+            }
+            LevelAction::StretchLightsTime(lights, _, factor) => {
+                lights.is_empty() || *factor == FloatTime::ONE
+            }
+            LevelAction::ReverseLightsTime(lights) => lights.is_empty(),
             LevelAction::ChangeFadeOut(_, delta) => delta.is_noop(&0),
             LevelAction::ChangeFadeIn(_, delta) => delta.is_noop(&0),
             LevelAction::MoveLight(_, time, position) => {
@@ -171,10 +234,14 @@ impl LevelAction {
             }
             LevelAction::RotateWaypointAround(_, _, _, delta) => delta.is_noop(&Angle::ZERO),
             LevelAction::ScaleWaypoint(_, _, delta) => delta.is_noop(&Coord::ZERO),
+            LevelAction::StretchWaypoint(_, _, delta) => delta.is_noop(&vec2::ZERO),
+            LevelAction::SetWaypointShape(..) => false,
             LevelAction::SetWaypointInterpolation(..) => false,
             LevelAction::SetWaypointCurve(..) => false,
             LevelAction::MoveWaypoint(_, ids) => ids.is_empty(),
             LevelAction::ChangeHollow(_, _, delta) => delta.is_noop(&R32::ZERO),
+            LevelAction::ChangePulse(_, _, delta) => delta.is_noop(&R32::ZERO),
+            LevelAction::SetWaypointDanger(..) => false,
         }
     }
 }
@@ -204,6 +271,10 @@ impl LevelEditor {
                 self.redo();
                 return;
             }
+            LevelAction::JumpHistory(position) => {
+                self.jump_history(position);
+                return;
+            }
             LevelAction::Copy => self.copy(),
             LevelAction::CopySelection(selection) => {
                 let beat_time = self
@@ -293,6 +364,7 @@ impl LevelEditor {
                 self.scroll_time(delta);
             }
             LevelAction::SetBeatSnap(beat_snap) => self.beat_snap = beat_snap,
+            LevelAction::ToggleOnsetSnap => self.snap_to_onsets = !self.snap_to_onsets,
             LevelAction::TimelineZoom(change) => {
                 let mut zoom = self.timeline_zoom.target.as_f32();
                 zoom = match change {
@@ -426,6 +498,9 @@ impl LevelEditor {
                     point.beat_time = beat_time;
                 }
             }
+            LevelAction::SetTiming(timing) => {
+                self.level.timing = timing;
+            }
 
             LevelAction::NewRgbSplit(duration) => {
                 self.execute(LevelAction::Deselect, drag);
@@ -436,8 +511,9 @@ impl LevelEditor {
                         .beat_time,
                 );
                 self.level.events.push(TimedEvent {
-                    time: self.current_time.target,
+                    time: self.placement_time(),
                     event: Event::Effect(EffectEvent::RgbSplit(duration)),
+                    envelope: None,
                 });
             }
             LevelAction::NewCameraShake(duration) => {
@@ -449,8 +525,9 @@ impl LevelEditor {
                         .beat_time,
                 );
                 self.level.events.push(TimedEvent {
-                    time: self.current_time.target,
+                    time: self.placement_time(),
                     event: Event::Effect(EffectEvent::CameraShake(duration, r32(0.25))),
+                    envelope: None,
                 });
             }
             LevelAction::NewPaletteSwap(duration) => {
@@ -462,8 +539,9 @@ impl LevelEditor {
                         .beat_time,
                 );
                 self.level.events.push(TimedEvent {
-                    time: self.current_time.target,
+                    time: self.placement_time(),
                     event: Event::Effect(EffectEvent::PaletteSwap(duration)),
+                    envelope: None,
                 });
             }
             LevelAction::NewVignette(duration) => {
@@ -475,8 +553,9 @@ impl LevelEditor {
                         .beat_time,
                 );
                 self.level.events.push(TimedEvent {
-                    time: self.current_time.target,
+                    time: self.placement_time(),
                     event: Event::Effect(EffectEvent::Vignette(duration, r32(0.5))),
+                    envelope: None,
                 });
             }
             LevelAction::NewCurvature(duration) => {
@@ -488,8 +567,9 @@ impl LevelEditor {
                         .beat_time,
                 );
                 self.level.events.push(TimedEvent {
-                    time: self.current_time.target,
+                    time: self.placement_time(),
                     event: Event::Effect(EffectEvent::ScreenCurvature(duration, r32(0.5))),
+                    envelope: None,
                 });
             }
             LevelAction::NewNoiseOffset(duration) => {
@@ -501,8 +581,9 @@ impl LevelEditor {
                         .beat_time,
                 );
                 self.level.events.push(TimedEvent {
-                    time: self.current_time.target,
+                    time: self.placement_time(),
                     event: Event::Effect(EffectEvent::NoiseOffset(duration, r32(1.0))),
+                    envelope: None,
                 });
             }
             LevelAction::NewSpotlight(duration) => {
@@ -514,8 +595,89 @@ impl LevelEditor {
                         .beat_time,
                 );
                 self.level.events.push(TimedEvent {
-                    time: self.current_time.target,
+                    time: self.placement_time(),
                     event: Event::Effect(EffectEvent::Spotlight(duration, r32(1.0))),
+                    envelope: None,
+                });
+            }
+            LevelAction::NewCameraZoom(duration) => {
+                self.execute(LevelAction::Deselect, drag);
+                let duration = duration.as_time(
+                    self.level
+                        .timing
+                        .get_timing(self.current_time.target)
+                        .beat_time,
+                );
+                self.level.events.push(TimedEvent {
+                    time: self.placement_time(),
+                    event: Event::Effect(EffectEvent::CameraZoom(duration, r32(0.25))),
+                    envelope: None,
+                });
+            }
+            LevelAction::NewCameraRotate(duration) => {
+                self.execute(LevelAction::Deselect, drag);
+                let duration = duration.as_time(
+                    self.level
+                        .timing
+                        .get_timing(self.current_time.target)
+                        .beat_time,
+                );
+                self.level.events.push(TimedEvent {
+                    time: self.placement_time(),
+                    event: Event::Effect(EffectEvent::CameraRotate(
+                        duration,
+                        Angle::from_degrees(r32(15.0)),
+                    )),
+                    envelope: None,
+                });
+            }
+            LevelAction::NewCameraPan(duration) => {
+                self.execute(LevelAction::Deselect, drag);
+                let duration = duration.as_time(
+                    self.level
+                        .timing
+                        .get_timing(self.current_time.target)
+                        .beat_time,
+                );
+                self.level.events.push(TimedEvent {
+                    time: self.placement_time(),
+                    event: Event::Effect(EffectEvent::CameraPan(
+                        duration,
+                        vec2(r32(1.0), r32(0.0)),
+                    )),
+                    envelope: None,
+                });
+            }
+            LevelAction::NewFlash(duration) => {
+                self.execute(LevelAction::Deselect, drag);
+                let duration = duration.as_time(
+                    self.level
+                        .timing
+                        .get_timing(self.current_time.target)
+                        .beat_time,
+                );
+                self.level.events.push(TimedEvent {
+                    time: self.placement_time(),
+                    event: Event::Effect(EffectEvent::Flash(duration, r32(0.5))),
+                    envelope: None,
+                });
+            }
+            LevelAction::NewTint(duration) => {
+                self.execute(LevelAction::Deselect, drag);
+                let duration = duration.as_time(
+                    self.level
+                        .timing
+                        .get_timing(self.current_time.target)
+                        .beat_time,
+                );
+                self.level.events.push(TimedEvent {
+                    time: self.placement_time(),
+                    event: Event::Effect(EffectEvent::Tint(
+                        duration,
+                        r32(0.5),
+                        TintColor::Highlight,
+                    )),
+                    envelope: None,
                 });
             }
             LevelAction::ChangeEffectDuration(index, change) => {
@@ -536,6 +698,38 @@ impl LevelEditor {
                     self.save_state(HistoryLabel::EffectIntensity(index));
                 }
             }
+            LevelAction::ChangeCameraRotation(index, change) => {
+                if let Some(event) = self.level.events.get_mut(index)
+                    && let Event::Effect(EffectEvent::CameraRotate(_, angle)) = &mut event.event
+                {
+                    change.apply(angle);
+                    self.save_state(HistoryLabel::EffectIntensity(index));
+                }
+            }
+            LevelAction::ChangeCameraPan(index, change) => {
+                if let Some(event) = self.level.events.get_mut(index)
+                    && let Event::Effect(EffectEvent::CameraPan(_, offset)) = &mut event.event
+                {
+                    change.apply(offset);
+                    self.save_state(HistoryLabel::EffectIntensity(index));
+                }
+            }
+            LevelAction::SetEffectEnvelope(index, envelope) => {
+                if let Some(event) = self.level.events.get_mut(index)
+                    && let Event::Effect(_) = event.event
+                {
+                    event.envelope = envelope;
+                    self.save_state(HistoryLabel::Envelope(index));
+                }
+            }
+            LevelAction::SetTintColor(index, tint_color) => {
+                if let Some(event) = self.level.events.get_mut(index)
+                    && let Event::Effect(EffectEvent::Tint(_, _, color)) = &mut event.event
+                {
+                    *color = tint_color;
+                    self.save_state(default());
+                }
+            }
 
             LevelAction::NewLight(shape) => {
                 self.execute(LevelAction::Deselect, drag);
@@ -554,7 +748,7 @@ impl LevelEditor {
             LevelAction::SelectLight(mode, ids) => self.select_light(mode, ids),
             LevelAction::ChangeShape(id, shape) => {
                 if let Some(event) = self.level.events.get_mut(id.event)
-                    && let Event::Light(light) = &mut event.event
+                    && let Some(light) = event.event.as_light_mut()
                 {
                     light.shape = shape;
                     self.save_state(HistoryLabel::Unknown);
@@ -570,9 +764,50 @@ impl LevelEditor {
                 self.modify_movement(light, |movement| movement.flip_vertical(anchor))
             }
             LevelAction::ToggleDanger(light) => self.toggle_danger(light),
+            LevelAction::ToggleSustain(light) => self.toggle_sustain(light),
+            LevelAction::GroupLights(lights) => self.group_lights(&lights),
+            LevelAction::UngroupLights(lights) => self.ungroup_lights(&lights),
+            LevelAction::ChangeGroup(index, group) => {
+                if let Some(target) = self.level.groups.get_mut(index) {
+                    *target = group;
+                    self.save_state(HistoryLabel::Group(index));
+                }
+            }
+            LevelAction::MakePattern(light_id) => self.make_pattern(light_id),
+            LevelAction::ChangePattern(light_id, pattern) => {
+                if let Some(event) = self.level.events.get_mut(light_id.event)
+                    && let Event::Pattern(target) = &mut event.event
+                {
+                    *target = PatternEvent {
+                        light: target.light.clone(),
+                        ..pattern
+                    };
+                    self.save_state(HistoryLabel::Pattern(light_id));
+                }
+            }
+            LevelAction::ExpandPattern(light_id) => self.expand_pattern(light_id),
+            LevelAction::MirrorLights(lights, anchor, axis) => {
+                for light in lights {
+                    self.modify_movement(light, |movement| movement.mirror(anchor, axis));
+                }
+                self.save_state(default());
+            }
+            LevelAction::RotateScaleLights(lights, anchor, angle, scale) => {
+                for light in lights {
+                    self.modify_movement(light, |movement| {
+                        movement.rotate_around(anchor, angle);
+                        movement.scale_around(anchor, scale);
+                    });
+                }
+                self.save_state(default());
+            }
+            LevelAction::StretchLightsTime(lights, pivot, factor) => {
+                self.stretch_lights_time(&lights, pivot, factor)
+            }
+            LevelAction::ReverseLightsTime(lights) => self.reverse_lights_time(&lights),
             LevelAction::ChangeFadeOut(id, change) => {
                 if let Some(event) = self.level.events.get_mut(id.event)
-                    && let Event::Light(light) = &mut event.event
+                    && let Some(light) = event.event.as_light_mut()
                 {
                     let movement = &mut light.movement;
                     let mut value = movement.get_fade_out();
@@ -583,7 +818,7 @@ impl LevelEditor {
             }
             LevelAction::ChangeFadeIn(id, change) => {
                 if let Some(event) = self.level.events.get_mut(id.event)
-                    && let Event::Light(light) = &mut event.event
+                    && let Some(light) = event.event.as_light_mut()
                 {
                     let movement = &mut light.movement;
                     let from = movement.get_fade_in();
@@ -609,7 +844,7 @@ impl LevelEditor {
             }
             LevelAction::ScaleWaypoint(light_id, waypoint_id, change) => {
                 if let Some(event) = self.level.events.get_mut(light_id.event)
-                    && let Event::Light(light) = &mut event.event
+                    && let Some(light) = event.event.as_light_mut()
                     && let Some(frame) = light.movement.get_frame_mut(waypoint_id)
                 {
                     change.apply(&mut frame.scale);
@@ -617,6 +852,19 @@ impl LevelEditor {
                     self.save_state(HistoryLabel::Scale(light_id, waypoint_id));
                 }
             }
+            LevelAction::StretchWaypoint(light_id, waypoint_id, change) => {
+                if let Some(event) = self.level.events.get_mut(light_id.event)
+                    && let Some(light) = event.event.as_light_mut()
+                    && let Some(frame) = light.movement.get_frame_mut(waypoint_id)
+                {
+                    change.apply(&mut frame.stretch);
+                    frame.stretch = frame.stretch.map(|x| x.clamp(r32(0.0), r32(MAX_SCALE)));
+                    self.save_state(HistoryLabel::Stretch(light_id, waypoint_id));
+                }
+            }
+            LevelAction::SetWaypointShape(light, waypoint, shape) => {
+                self.set_waypoint_shape(light, waypoint, shape)
+            }
             LevelAction::SetWaypointInterpolation(light, waypoint, interpolation) => {
                 self.set_waypoint_interpolation(light, waypoint, interpolation)
             }
@@ -629,6 +877,12 @@ impl LevelEditor {
             LevelAction::ChangeHollow(light, waypoint, change) => {
                 self.change_hollow(light, waypoint, change)
             }
+            LevelAction::ChangePulse(light, waypoint, change) => {
+                self.change_pulse(light, waypoint, change)
+            }
+            LevelAction::SetWaypointDanger(light, waypoint, danger) => {
+                self.set_waypoint_danger(light, waypoint, danger)
+            }
         }
 
         // In case some action forgot to save the state,
@@ -653,7 +907,7 @@ impl LevelEditor {
             return;
         };
 
-        let Event::Light(event) = &mut timed_event.event else {
+        let Some(event) = timed_event.event.as_light_mut() else {
             return;
         };
 
@@ -682,7 +936,7 @@ impl LevelEditor {
             return;
         };
 
-        let Event::Light(event) = &mut timed_event.event else {
+        let Some(event) = timed_event.event.as_light_mut() else {
             return;
         };
         let original_event = event.clone();
@@ -864,7 +1118,7 @@ impl LevelEditor {
             return;
         };
 
-        let Event::Light(event) = &mut timed_event.event else {
+        let Some(event) = timed_event.event.as_light_mut() else {
             return;
         };
 
@@ -893,7 +1147,7 @@ impl LevelEditor {
             return;
         };
 
-        let Event::Light(event) = &mut timed_event.event else {
+        let Some(event) = timed_event.event.as_light_mut() else {
             return;
         };
 
@@ -981,7 +1235,7 @@ impl LevelEditor {
             return;
         };
 
-        let Event::Light(light) = &mut event.event else {
+        let Some(light) = event.event.as_light_mut() else {
             return;
         };
 
@@ -1040,16 +1294,14 @@ impl LevelEditor {
             let waypoint_time = (ids.len() == 1)
                 .then(|| {
                     let waypoint_id = *ids.first().unwrap();
-                    self.level
-                        .events
-                        .get(light_id.event)
-                        .and_then(|event| match &event.event {
-                            Event::Light(light) => light
-                                .movement
-                                .get_time(waypoint_id)
-                                .map(|time| event.time + time),
-                            _ => None,
-                        })
+                    self.level.events.get(light_id.event).and_then(|event| {
+                        event
+                            .event
+                            .as_light()?
+                            .movement
+                            .get_time(waypoint_id)
+                            .map(|time| event.time + time)
+                    })
                 })
                 .flatten();
             if let Some(waypoint_time) = waypoint_time {
@@ -1070,7 +1322,7 @@ impl LevelEditor {
         let Some(event) = self.level.events.get_mut(light_id.event) else {
             return;
         };
-        let Event::Light(event) = &mut event.event else {
+        let Some(event) = event.event.as_light_mut() else {
             return;
         };
         let Some(frame) = event.movement.get_frame_mut(waypoint_id) else {
@@ -1105,15 +1357,164 @@ impl LevelEditor {
 
     fn toggle_danger(&mut self, light_id: LightId) {
         if let Some(event) = self.level.events.get_mut(light_id.event)
-            && let Event::Light(event) = &mut event.event
+            && let Some(event) = event.event.as_light_mut()
         {
             event.danger = !event.danger;
         }
     }
 
+    fn toggle_sustain(&mut self, light_id: LightId) {
+        if let Some(event) = self.level.events.get_mut(light_id.event)
+            && let Some(event) = event.event.as_light_mut()
+        {
+            event.sustain = !event.sustain;
+        }
+    }
+
+    fn group_lights(&mut self, light_ids: &[LightId]) {
+        // Detach from the previous groups first so that all positions are absolute
+        self.detach_lights(light_ids);
+        self.remove_empty_groups();
+
+        let lights: Vec<(Time, vec2<Coord>)> = light_ids
+            .iter()
+            .filter_map(|id| {
+                let event = self.level.events.get(id.event)?;
+                let Some(light) = event.event.as_light() else {
+                    return None;
+                };
+                Some((event.time, light.movement.initial.transform.translation))
+            })
+            .collect();
+        if lights.is_empty() {
+            return;
+        }
+
+        // Place the pivot in the middle of the lights
+        let time = lights
+            .iter()
+            .map(|(time, _)| *time)
+            .min()
+            .unwrap_or(Time::ZERO);
+        let pivot =
+            lights.iter().fold(vec2::ZERO, |acc, (_, pos)| acc + *pos) / r32(lights.len() as f32);
+
+        let group = self.level.groups.len();
+        self.level
+            .groups
+            .push(LightGroup::new(format!("Group {}", group + 1), time, pivot));
+        for id in light_ids {
+            if let Some(event) = self.level.events.get_mut(id.event)
+                && let Some(light) = event.event.as_light_mut()
+            {
+                light.group = Some(group);
+                light.movement.modify_transforms(|transform| {
+                    transform.translation -= pivot;
+                });
+            }
+        }
+        self.save_state(default());
+    }
+
+    fn ungroup_lights(&mut self, light_ids: &[LightId]) {
+        self.detach_lights(light_ids);
+        self.remove_empty_groups();
+        self.save_state(default());
+    }
+
+    fn make_pattern(&mut self, light_id: LightId) {
+        let Some(event) = self.level.events.get_mut(light_id.event) else {
+            return;
+        };
+        if let Event::Light(light) = &event.event {
+            event.event = Event::Pattern(PatternEvent::new(light.clone()));
+            self.save_state(default());
+        }
+    }
+
+    /// Replace the pattern with the concrete lights it generates.
+    fn expand_pattern(&mut self, light_id: LightId) {
+        let Some(event) = self.level.events.get(light_id.event) else {
+            return;
+        };
+        let Event::Pattern(pattern) = &event.event else {
+            return;
+        };
+        let mut lights = pattern.expand(event.time).collect::<Vec<_>>().into_iter();
+        let Some(first) = lights.next() else {
+            // Pattern with no lights
+            self.delete_light(light_id);
+            return;
+        };
+        self.level.events[light_id.event] = first;
+        self.level.events.extend(lights);
+        self.selection.clear();
+        self.save_state(default());
+    }
+
+    fn stretch_lights_time(&mut self, light_ids: &[LightId], pivot: Time, factor: FloatTime) {
+        let timing = &self.level.timing;
+        for id in light_ids {
+            if let Some(event) = self.level.events.get_mut(id.event)
+                && let Some(light) = event.event.as_light_mut()
+            {
+                let offset = seconds_to_time(time_to_seconds(event.time - pivot) * factor);
+                // NOTE: stretching introduces rounding errors, so snap back to the closest beat
+                event.time = timing.snap_to_best_alignment(pivot + offset).0;
+                light.movement.stretch_time(factor);
+                beat_align_waypoints(timing, event.time, light);
+            }
+        }
+        self.save_state(default());
+    }
+
+    fn reverse_lights_time(&mut self, light_ids: &[LightId]) {
+        let spans: Vec<(Time, Time)> = light_ids
+            .iter()
+            .filter_map(|id| {
+                let event = self.level.events.get(id.event)?;
+                let light = event.event.as_light()?;
+                Some((event.time, event.time + light.movement.duration()))
+            })
+            .collect();
+        let Some(start) = spans.iter().map(|&(start, _)| start).min() else {
+            return;
+        };
+        let end = spans.iter().map(|&(_, end)| end).max().unwrap_or(start);
+
+        for id in light_ids {
+            if let Some(event) = self.level.events.get_mut(id.event)
+                && let Some(light) = event.event.as_light_mut()
+            {
+                // Whatever ended last now starts first
+                let light_end = event.time + light.movement.duration();
+                event.time = start + end - light_end;
+                light.movement.reverse_time();
+            }
+        }
+        self.save_state(default());
+    }
+
+    /// Remove the lights from their groups keeping their rest positions.
+    fn detach_lights(&mut self, light_ids: &[LightId]) {
+        for id in light_ids {
+            if let Some(event) = self.level.events.get_mut(id.event)
+                && let Some(light) = event.event.as_light_mut()
+                && let Some(group) = light.group.take()
+                && let Some(group) = self.level.groups.get(group)
+            {
+                // NOTE: the light keeps its rest position, the motion of the group is lost
+                let pivot = group.movement.initial.transform;
+                light
+                    .movement
+                    .modify_transforms(|transform| *transform = transform.relative_to(&pivot));
+            }
+        }
+    }
+
     fn change_hollow(&mut self, light_id: LightId, waypoint_id: WaypointId, change: Change<R32>) {
         if let Some(event) = self.level.events.get_mut(light_id.event)
-            && let Event::Light(event) = &mut event.event
+            && let Some(event) = event.event.as_light_mut()
             && let Some(waypoint) = event.movement.get_frame_mut(waypoint_id)
         {
             let mut hollow = waypoint.hollow;
@@ -1123,6 +1524,53 @@ impl LevelEditor {
         }
     }
 
+    fn change_pulse(&mut self, light_id: LightId, waypoint_id: WaypointId, change: Change<R32>) {
+        if let Some(event) = self.level.events.get_mut(light_id.event)
+            && let Some(event) = event.event.as_light_mut()
+            && let Some(waypoint) = event.movement.get_frame_mut(waypoint_id)
+        {
+            let mut pulse = waypoint.pulse;
+            change.apply(&mut pulse);
+            waypoint.pulse = pulse.clamp(R32::ZERO, R32::ONE);
+            self.save_state(HistoryLabel::Pulse(light_id, waypoint_id));
+        }
+    }
+
+    fn set_waypoint_shape(
+        &mut self,
+        light_id: LightId,
+        waypoint_id: WaypointId,
+        shape: Option<Shape>,
+    ) {
+        // NOTE: the initial shape is the shape of the light itself
+        if let WaypointId::Frame(frame) = waypoint_id
+            && let Some(event) = self.level.events.get_mut(light_id.event)
+            && let Some(event) = event.event.as_light_mut()
+            && let Some(frame) = event.movement.waypoints.get_mut(frame)
+        {
+            frame.shape = shape;
+            self.save_state(default());
+        }
+    }
+
+    fn set_waypoint_danger(
+        &mut self,
+        light_id: LightId,
+        waypoint_id: WaypointId,
+        danger: Option<bool>,
+    ) {
+        // NOTE: the initial danger is controlled by the light itself,
+        // and the last waypoint is never reached, so only frames can toggle it
+        if let WaypointId::Frame(frame) = waypoint_id
+            && let Some(event) = self.level.events.get_mut(light_id.event)
+            && let Some(event) = event.event.as_light_mut()
+            && let Some(frame) = event.movement.waypoints.get_mut(frame)
+        {
+            frame.danger = danger;
+            self.save_state(default());
+        }
+    }
+
     fn cancel(&mut self) {
         match &mut self.state {
             EditingState::Idle => {
@@ -1155,7 +1603,7 @@ impl LevelEditor {
             return;
         };
 
-        let start_beat = self.current_time.target;
+        let start_beat = self.placement_time();
         let timing = self.level.timing.get_timing(start_beat);
         let rotation = self.place_rotation.normalized_2pi();
         self.place_rotation = rotation;
@@ -1165,7 +1613,9 @@ impl LevelEditor {
                 translation: position,
                 rotation,
                 scale: self.place_scale,
+                stretch: vec2::splat(Coord::ONE),
                 hollow: r32(-1.0),
+                pulse: R32::ZERO,
             },
         );
 
@@ -1173,6 +1623,8 @@ impl LevelEditor {
             shape,
             movement,
             danger,
+            group: None,
+            sustain: false,
         };
 
         let beat = start_beat - light.movement.get_fade_in(); // extra time for the fade in and telegraph
@@ -1180,6 +1632,7 @@ impl LevelEditor {
         let event = TimedEvent {
             time: beat,
             event: Event::Light(event),
+            envelope: None,
         };
 
         let event_i = self.level.events.len();
@@ -1197,11 +1650,15 @@ impl LevelEditor {
             return;
         };
 
+        // Waypoints of grouped lights are relative to the pivot of the group
+        let pivot = self.group_pivot(waypoints.light);
+        let position = position - pivot.translation;
+
         let Some(event) = self.level.events.get_mut(waypoints.light.event) else {
             return;
         };
 
-        let Event::Light(light) = &mut event.event else {
+        let Some(light) = event.event.as_light_mut() else {
             return;
         };
 
@@ -1215,7 +1672,7 @@ impl LevelEditor {
 
         // NOTE: target to make sure it is snapped to the beat
         // assume time interpolation doesn't take long, so not visually weird
-        let target_time = self.current_time.target;
+        let target_time = self.placement_time();
 
         let prev_frame = i.checked_sub(2).and_then(|i| {
             light
@@ -1230,7 +1687,9 @@ impl LevelEditor {
             translation: position,
             rotation: self.place_rotation,
             scale: self.place_scale,
+            stretch: prev_transform.stretch,
             hollow: prev_transform.hollow,
+            pulse: prev_transform.pulse,
         };
         let mut interpolation = prev_frame.map_or(light.movement.initial.interpolation, |frame| {
             frame.interpolation
@@ -1247,6 +1706,8 @@ impl LevelEditor {
                     interpolation,
                     change_curve,
                     transform,
+                    danger: None,
+                    shape: None,
                 });
                 light.movement.initial.transform = TransformLight {
                     translation: transform.translation,
@@ -1269,6 +1730,8 @@ impl LevelEditor {
                             interpolation,
                             change_curve,
                             transform,
+                            danger: None,
+                            shape: None,
                         },
                     );
 
@@ -1302,6 +1765,8 @@ impl LevelEditor {
                     interpolation,
                     change_curve,
                     transform,
+                    danger: None,
+                    shape: None,
                 });
                 light.movement.last = TransformLight {
                     translation: transform.translation,
@@ -0,0 +1,366 @@
+//! Offline beat tracking and spectral analysis over the decoded music samples.
+
+use super::*;
+
+use std::ops::Range;
+
+/// Number of samples in a single frame of the onset envelope.
+const HOP_SIZE: usize = 512;
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+/// Tempo that is preferred when the detection is ambiguous (e.g. half or double time).
+const PREFERRED_BPM: f32 = 120.0;
+/// Duration (in seconds) of the chunks analyzed separately to detect tempo changes.
+const SEGMENT_DURATION: f32 = 15.0;
+/// Relative tempo difference between the chunks that is considered a tempo change.
+const TEMPO_CHANGE_THRESHOLD: f32 = 0.02;
+/// Detected BPM closer than this to a whole number gets rounded.
+const ROUND_BPM_THRESHOLD: f32 = 0.05;
+/// Minimal number of beats that should line up with the onsets to trust the tempo.
+const MIN_MATCHED_BEATS: usize = 8;
+/// Cutoff frequency (in Hz) between the low and mid bands.
+const LOW_CUTOFF: f32 = 250.0;
+/// Cutoff frequency (in Hz) between the mid and high bands.
+const HIGH_CUTOFF: f32 = 4000.0;
+
+/// Frequency bands and onsets of the music, used to visualize it on the timeline.
+#[derive(Debug, Clone)]
+pub struct MusicAnalysis {
+    /// Duration of a single frame (in seconds).
+    pub frame_time: f32,
+    /// Energy of the low, mid and high frequencies in each frame, in range `0..=1`.
+    pub bands: Vec<[f32; 3]>,
+    /// Times of the detected onsets (drum hits, notes) relative to the start of the music.
+    pub onsets: Vec<Time>,
+}
+
+impl MusicAnalysis {
+    pub fn new(samples: &[f32], sample_rate: f32) -> Self {
+        let envelope = OnsetEnvelope::new(samples, sample_rate);
+        Self {
+            frame_time: envelope.frame_time,
+            bands: split_bands(samples, sample_rate),
+            onsets: envelope
+                .onsets()
+                .map(|time| seconds_to_time(r32(time)))
+                .collect(),
+        }
+    }
+}
+
+/// Split the samples into the low, mid and high frequency bands
+/// and calculate the energy of each band in every frame.
+fn split_bands(samples: &[f32], sample_rate: f32) -> Vec<[f32; 3]> {
+    // One-pole low-pass filter coefficient for the cutoff frequency
+    let alpha = |cutoff: f32| {
+        let rc = 1.0 / (std::f32::consts::TAU * cutoff);
+        let dt = 1.0 / sample_rate;
+        dt / (rc + dt)
+    };
+    let (low_alpha, high_alpha) = (alpha(LOW_CUTOFF), alpha(HIGH_CUTOFF));
+
+    let mut low = 0.0;
+    let mut below_high = 0.0;
+    let mut bands: Vec<[f32; 3]> = samples
+        .chunks(HOP_SIZE)
+        .map(|chunk| {
+            let mut energy = [0.0; 3];
+            for &sample in chunk {
+                low += low_alpha * (sample - low);
+                below_high += high_alpha * (sample - below_high);
+                let split = [low, below_high - low, sample - below_high];
+                for (energy, value) in energy.iter_mut().zip(split) {
+                    *energy += value * value;
+                }
+            }
+            energy.map(|energy| (energy / chunk.len() as f32).sqrt())
+        })
+        .collect();
+
+    // Normalize each band separately so the quieter highs are still visible
+    for band in 0..3 {
+        let max = bands.iter().map(|frame| frame[band]).fold(0.0, f32::max);
+        if max > 0.0 {
+            for frame in &mut bands {
+                frame[band] = (frame[band] / max).sqrt();
+            }
+        }
+    }
+    bands
+}
+
+/// Timing proposed by the beat detection, relative to the start of the music.
+#[derive(Debug, Clone)]
+pub struct TimingProposal {
+    /// The first point holds the offset of the first beat,
+    /// the rest are the tempo changes.
+    pub points: Vec<TimingPoint>,
+}
+
+impl TimingProposal {
+    /// Detect the timing of the music given its (mono) samples.
+    pub fn detect(samples: &[f32], sample_rate: f32) -> Self {
+        let envelope = OnsetEnvelope::new(samples, sample_rate);
+        Self {
+            points: envelope
+                .detect_tempo()
+                .into_iter()
+                .map(|(time, beat_time)| TimingPoint {
+                    time: seconds_to_time(r32(time)),
+                    beat_time: r32(beat_time),
+                })
+                .collect(),
+        }
+    }
+
+    /// Multiply the beat duration of all points, e.g. `2.0` to halve the BPM.
+    pub fn scale_beat(&mut self, factor: FloatTime) {
+        for point in &mut self.points {
+            point.beat_time *= factor;
+        }
+    }
+
+    /// Convert into the level timing given the music offset.
+    pub fn to_timing(&self, music_offset: Time) -> Timing {
+        Timing {
+            points: self
+                .points
+                .iter()
+                .map(|point| TimingPoint {
+                    time: point.time - music_offset,
+                    ..point.clone()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Onset strength of the music over time:
+/// how much the loudness increases in each frame.
+struct OnsetEnvelope {
+    /// Duration of a single frame in seconds.
+    frame_time: f32,
+    /// Onset strength in range `0..=1` in each frame.
+    strength: Vec<f32>,
+    /// Smoothed `strength` so that close peaks correlate even when off by a frame.
+    smoothed: Vec<f32>,
+}
+
+impl OnsetEnvelope {
+    fn new(samples: &[f32], sample_rate: f32) -> Self {
+        let loudness: Vec<f32> = samples
+            .chunks(HOP_SIZE)
+            .map(|chunk| {
+                let energy = chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32;
+                (1.0 + 100.0 * energy.sqrt()).ln()
+            })
+            .collect();
+        let mut strength: Vec<f32> = std::iter::once(0.0)
+            .chain(loudness.windows(2).map(|w| (w[1] - w[0]).max(0.0)))
+            .collect();
+        let max = strength.iter().copied().fold(0.0, f32::max);
+        if max > 0.0 {
+            for value in &mut strength {
+                *value /= max;
+            }
+        }
+
+        const KERNEL: [f32; 5] = [1.0, 2.0, 3.0, 2.0, 1.0];
+        let kernel_sum: f32 = KERNEL.iter().sum();
+        let smoothed = (0..strength.len())
+            .map(|i| {
+                KERNEL
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(j, w)| Some(w * strength.get((i + j).checked_sub(2)?)?))
+                    .sum::<f32>()
+                    / kernel_sum
+            })
+            .collect();
+
+        Self {
+            frame_time: HOP_SIZE as f32 / sample_rate,
+            strength,
+            smoothed,
+        }
+    }
+
+    /// Whether the frame is a local peak noticeably stronger than its surroundings.
+    fn is_peak(&self, i: usize) -> bool {
+        const WINDOW: usize = 8;
+        const DELTA: f32 = 0.05;
+        let s = &self.strength;
+        let local = &s[i.saturating_sub(WINDOW)..(i + WINDOW + 1).min(s.len())];
+        let mean = local.iter().sum::<f32>() / local.len() as f32;
+        let max = local.iter().copied().fold(0.0, f32::max);
+        s[i] >= max && s[i] > mean + DELTA && (i == 0 || s[i - 1] < s[i])
+    }
+
+    /// Times of the onsets in seconds.
+    fn onsets(&self) -> impl Iterator<Item = f32> + '_ {
+        (0..self.strength.len())
+            .filter(|&i| self.is_peak(i))
+            .map(|i| i as f32 * self.frame_time)
+    }
+
+    /// Range of beat periods (in frames) to look for.
+    fn lag_range(&self) -> Range<usize> {
+        let min = ((60.0 / MAX_BPM / self.frame_time).floor() as usize).max(2);
+        let max = (60.0 / MIN_BPM / self.frame_time).ceil() as usize;
+        min..max + 1
+    }
+
+    /// Estimate the beat period (in frames) from the autocorrelation of the envelope.
+    fn estimate_period(&self, frames: Range<usize>) -> Option<f32> {
+        let s = &self.smoothed[frames];
+        let lags = self.lag_range();
+        if s.len() <= lags.end * 4 {
+            return None;
+        }
+        let mean = s.iter().sum::<f32>() / s.len() as f32;
+        let centered: Vec<f32> = s.iter().map(|v| v - mean).collect();
+        let acf = |lag: usize| {
+            centered
+                .iter()
+                .zip(&centered[lag..])
+                .map(|(a, b)| a * b)
+                .sum::<f32>()
+                / (centered.len() - lag) as f32
+        };
+        let values: Vec<f32> = (lags.start - 1..lags.end + 1).map(acf).collect();
+        let value = |lag: usize| values[lag + 1 - lags.start];
+
+        let (lag, score) = lags
+            .map(|lag| {
+                let bpm = 60.0 / (lag as f32 * self.frame_time);
+                let weight = (-0.5 * (bpm / PREFERRED_BPM).log2().powi(2)).exp();
+                (lag, value(lag) * weight)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+        if score <= 0.0 {
+            return None;
+        }
+
+        // Parabolic interpolation between the neighbouring lags
+        let (a, b, c) = (value(lag - 1), value(lag), value(lag + 1));
+        let denom = a - 2.0 * b + c;
+        let shift = if denom.abs() > f32::EPSILON {
+            (0.5 * (a - c) / denom).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+        Some(lag as f32 + shift)
+    }
+
+    /// Sum of the envelope at the beats of the grid.
+    fn comb(&self, frames: &Range<usize>, phase: f32, period: f32) -> f32 {
+        let mut sum = 0.0;
+        let mut frame = frames.start as f32 + phase;
+        while (frame.round() as usize) < frames.end {
+            sum += self.smoothed[frame.round() as usize];
+            frame += period;
+        }
+        sum
+    }
+
+    /// Find the beat grid given the estimated period.
+    /// Returns the frame of the first beat and the refined period.
+    fn fit_beats(&self, frames: Range<usize>, period: f32) -> Option<(f32, f32)> {
+        let phase = (0..period.ceil() as usize)
+            .map(|step| step as f32)
+            .max_by(|&a, &b| {
+                self.comb(&frames, a, period)
+                    .total_cmp(&self.comb(&frames, b, period))
+            })?;
+
+        // Match the beats to the onset peaks and fit the grid with least squares
+        let radius = (period / 4.0).round() as usize;
+        let mut matched = Vec::new();
+        let mut beats = 0;
+        loop {
+            let center = (frames.start as f32 + phase + beats as f32 * period).round() as usize;
+            if center >= frames.end {
+                break;
+            }
+            let window = center.saturating_sub(radius)..(center + radius + 1).min(frames.end);
+            if let Some(peak) = window
+                .filter(|&i| self.is_peak(i))
+                .max_by(|&a, &b| self.strength[a].total_cmp(&self.strength[b]))
+            {
+                matched.push((beats as f32, peak as f32));
+            }
+            beats += 1;
+        }
+        if matched.len() < MIN_MATCHED_BEATS || matched.len() * 2 < beats {
+            // Not confident in the tempo
+            return None;
+        }
+
+        let n = matched.len() as f32;
+        let mean_beat = matched.iter().map(|(beat, _)| beat).sum::<f32>() / n;
+        let mean_frame = matched.iter().map(|(_, frame)| frame).sum::<f32>() / n;
+        let covariance: f32 = matched
+            .iter()
+            .map(|(beat, frame)| (beat - mean_beat) * (frame - mean_frame))
+            .sum();
+        let variance: f32 = matched
+            .iter()
+            .map(|(beat, _)| (beat - mean_beat).powi(2))
+            .sum();
+        let mut period = covariance / variance;
+
+        let bpm = 60.0 / (period * self.frame_time);
+        if (bpm - bpm.round()).abs() < ROUND_BPM_THRESHOLD {
+            period = 60.0 / bpm.round() / self.frame_time;
+        }
+        let start = mean_frame - period * mean_beat;
+        Some((start, period))
+    }
+
+    /// Detect the tempo and the tempo changes.
+    /// Returns the time of the first beat and the duration of a beat (both in seconds)
+    /// for every section with a constant tempo.
+    fn detect_tempo(&self) -> Vec<(f32, f32)> {
+        let segment = ((SEGMENT_DURATION / self.frame_time) as usize).max(1);
+        let len = self.strength.len();
+
+        // Estimate the tempo in each segment and merge the similar ones
+        let mut sections: Vec<(Range<usize>, f32)> = Vec::new();
+        for start in (0..len).step_by(segment) {
+            let frames = start..(start + segment).min(len);
+            let period = self.estimate_period(frames.clone());
+            match (sections.last_mut(), period) {
+                (Some((range, last)), period)
+                    if period.is_none_or(|period| {
+                        ((period - *last) / *last).abs() <= TEMPO_CHANGE_THRESHOLD
+                    }) =>
+                {
+                    range.end = frames.end;
+                }
+                (_, Some(period)) => sections.push((frames, period)),
+                (_, None) => {}
+            }
+        }
+
+        let mut points: Vec<(f32, f32)> = Vec::new();
+        for (frames, period) in sections {
+            let Some((start, period)) = self.fit_beats(frames, period) else {
+                continue;
+            };
+            let beat_time = period * self.frame_time;
+            if points
+                .last()
+                .is_some_and(|&(_, last)| ((last - beat_time) / beat_time).abs() < 0.001)
+            {
+                continue;
+            }
+            let mut time = start * self.frame_time;
+            if points.is_empty() {
+                // Offset of the very first beat
+                time = time.rem_euclid(beat_time);
+            }
+            points.push((time, beat_time));
+        }
+        points
+    }
+}
@@ -1,5 +1,11 @@
 use super::*;
 
+/// Prefix of the clipboard text that marks a serialized [`ClipboardItem`].
+const CLIPBOARD_HEADER: &str = "close-to-light-clipboard:";
+
+/// Clipboard of the editor.
+/// Copied items are also written to the system clipboard,
+/// so they can be pasted in other level sets or shared as text.
 pub struct Clipboard {
     data: Option<ClipboardItem>,
 }
@@ -36,10 +42,21 @@ impl Clipboard {
     }
 
     pub fn copy(&mut self, item: ClipboardItem) {
+        match item.to_text() {
+            Ok(text) => system::set_text(text),
+            Err(err) => log::error!("Failed to serialize the clipboard item: {err:?}"),
+        }
         self.data = Some(item);
     }
 
+    /// Get the item from the system clipboard if it holds one,
+    /// otherwise the last item copied in this session.
     pub fn paste(&mut self) -> Option<ClipboardItem> {
+        if let Some(text) = system::get_text()
+            && let Some(item) = ClipboardItem::from_text(&text)
+        {
+            self.data = Some(item);
+        }
         self.data.clone()
     }
 
@@ -47,3 +64,52 @@ impl Clipboard {
         &self.data
     }
 }
+
+impl ClipboardItem {
+    /// Serialize the item into the text to put in the system clipboard.
+    pub fn to_text(&self) -> Result<String, ron::Error> {
+        Ok(format!("{CLIPBOARD_HEADER}{}", ron::ser::to_string(self)?))
+    }
+
+    /// Deserialize the item from the system clipboard text.
+    /// Returns `None` if the text is not a valid item.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let data = text.trim().strip_prefix(CLIPBOARD_HEADER)?;
+        match ron::de::from_str(data) {
+            Ok(item) => Some(item),
+            Err(err) => {
+                log::warn!("Failed to parse the clipboard item: {err:?}");
+                None
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod system {
+    use super::*;
+
+    pub fn set_text(text: String) {
+        if let Err(err) = arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(text))
+        {
+            log::error!("Failed to write to the system clipboard: {err:?}");
+        }
+    }
+
+    pub fn get_text() -> Option<String> {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .inspect_err(|err| log::debug!("Failed to read the system clipboard: {err:?}"))
+            .ok()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod system {
+    // TODO: web clipboard api is async
+    pub fn set_text(_text: String) {}
+
+    pub fn get_text() -> Option<String> {
+        None
+    }
+}
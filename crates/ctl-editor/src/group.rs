@@ -42,6 +42,13 @@ pub struct Editor {
     pub visualize_beat: bool,
     /// Whether to only render the selected light.
     pub show_only_selected: bool,
+    /// Whether to show the frequency bands of the music instead of the waveform.
+    pub show_frequency_bands: bool,
+    /// Whether to show the detected onsets of the music on the timeline.
+    pub show_onsets: bool,
+    pub music_analysis: Option<Rc<MusicAnalysis>>,
+    /// Timing detected from the music, waiting to be accepted by the mapper.
+    pub timing_proposal: Option<TimingProposal>,
 
     pub group: PlayGroup,
     pub level_edit: Option<LevelEditor>,
@@ -114,7 +121,7 @@ pub struct DragWaypoint {
 
 impl Editor {
     pub fn new(context: Context, config: EditorConfig, group: PlayGroup) -> Self {
-        Self {
+        let mut editor = Self {
             context: context.clone(),
             real_time: FloatTime::ZERO,
             render_options: RenderOptions {
@@ -134,13 +141,19 @@ impl Editor {
             view_zoom: SecondOrderState::new(3.0, 1.0, 1.0, 1.0),
             visualize_beat: true,
             show_only_selected: false,
+            show_frequency_bands: false,
+            show_onsets: true,
+            music_analysis: None,
+            timing_proposal: None,
             snap_to_grid: PTValue::new(true),
             music_timer: FloatTime::ZERO,
 
             group,
             level_edit: None,
             config,
-        }
+        };
+        editor.analyze_music();
+        editor
     }
 
     pub fn set_music_offset(&mut self, offset: Time) {
@@ -158,12 +171,80 @@ impl Editor {
                 .update_group(self.group.group_index, new_group, None)
         {
             self.group.cached = group;
+            self.sync_onsets();
             log::info!("Saved the level successfully");
         } else {
             log::error!("Failed to update the level cache");
         }
     }
 
+    /// Analyze the frequency bands and the onsets of the music.
+    pub fn analyze_music(&mut self) {
+        self.music_analysis = self.group.music.as_ref().map(|music| {
+            Rc::new(MusicAnalysis::new(
+                &music.sound.get_channel_data(0),
+                music.sound.sample_rate(),
+            ))
+        });
+        self.sync_onsets();
+    }
+
+    /// Update the onsets the level editor snaps to.
+    fn sync_onsets(&mut self) {
+        let Some(level_editor) = &mut self.level_edit else {
+            return;
+        };
+        let music_offset = self.group.cached.local.data.music_offset;
+        level_editor.onsets = self
+            .music_analysis
+            .as_ref()
+            .map(|analysis| {
+                analysis
+                    .onsets
+                    .iter()
+                    .map(|&time| time - music_offset)
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    pub fn detect_timing(&mut self) {
+        let Some(music) = &self.group.music else {
+            log::error!("Cannot detect timing: no music selected");
+            return;
+        };
+        let proposal = TimingProposal::detect(
+            &music.sound.get_channel_data(0),
+            music.sound.sample_rate(),
+        );
+        if proposal.points.is_empty() {
+            log::warn!("Failed to detect the tempo of the music");
+            self.timing_proposal = None;
+            return;
+        }
+        log::info!(
+            "Detected timing: {:?}",
+            proposal
+                .points
+                .iter()
+                .map(|point| r32(60.0) / point.beat_time)
+                .collect::<Vec<_>>()
+        );
+        self.timing_proposal = Some(proposal);
+    }
+
+    pub fn accept_timing_proposal(&mut self) {
+        if self.level_edit.is_none() {
+            log::error!("Cannot apply the detected timing: no level is loaded");
+            return;
+        }
+        let Some(proposal) = self.timing_proposal.take() else {
+            return;
+        };
+        let timing = proposal.to_timing(self.group.cached.local.data.music_offset);
+        self.execute(LevelAction::SetTiming(timing).into());
+    }
+
     pub fn delete_level(&mut self, level_index: usize) {
         if self.cannot_edit_assets() {
             return;
@@ -313,6 +394,7 @@ impl Editor {
                 },
             );
             self.level_edit = Some(editor);
+            self.sync_onsets();
         }
     }
 
@@ -366,6 +448,13 @@ impl Editor {
             level_editor.model.level.level = level;
             self.group.cached = group;
             log::info!("Saved the level successfully");
+
+            // Keep the history consistent with the saved level
+            level_editor.save_state(default());
+            level_editor.history.save(
+                &self.group.cached.local.path,
+                level_editor.static_level.level_index,
+            );
         } else {
             log::error!("Failed to update the level cache");
         }
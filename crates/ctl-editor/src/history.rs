@@ -1,6 +1,9 @@
 use super::*;
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+/// Maximum number of changes kept in the undo stack.
+const MAX_UNDO: usize = 1000;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HistoryLabel {
     #[default]
     Unknown,
@@ -12,18 +15,24 @@ pub enum HistoryLabel {
 
     // Vfx
     EffectIntensity(usize),
+    Envelope(usize),
 
     // Lights
     FadeIn(LightId),
     FadeOut(LightId),
     MoveLight(LightId),
+    Pattern(LightId),
 
     // Waypoints
     MoveWaypoint(LightId, WaypointId),
     MoveWaypointTime(LightId, WaypointId),
     Rotate(LightId, WaypointId),
     Scale(LightId, WaypointId),
+    Stretch(LightId, WaypointId),
     Hollow(LightId, WaypointId),
+    Pulse(LightId, WaypointId),
+
+    Group(usize),
 
     Drag,
 }
@@ -36,16 +45,200 @@ impl HistoryLabel {
             _ => self == other,
         }
     }
+
+    /// Human-readable name of the change, if the label is specific enough.
+    pub fn name(&self) -> Option<&'static str> {
+        let name = match self {
+            Self::Unknown | Self::Merge => return None,
+            Self::MoveEvent(_) => "Move event",
+            Self::EventDuration(_) => "Event duration",
+            Self::EffectIntensity(_) => "Effect intensity",
+            Self::Envelope(_) => "Effect envelope",
+            Self::FadeIn(_) => "Fade in",
+            Self::FadeOut(_) => "Fade out",
+            Self::MoveLight(_) => "Move light",
+            Self::Pattern(_) => "Pattern",
+            Self::MoveWaypoint(..) => "Move waypoint",
+            Self::MoveWaypointTime(..) => "Waypoint time",
+            Self::Rotate(..) => "Rotate",
+            Self::Scale(..) => "Scale",
+            Self::Stretch(..) => "Stretch",
+            Self::Hollow(..) => "Hollow",
+            Self::Pulse(..) => "Pulse",
+            Self::Group(_) => "Group",
+            Self::Drag => "Drag",
+        };
+        Some(name)
+    }
+}
+
+/// A change of a list: `removed` items starting at `start` were replaced with `inserted`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ListDiff<T> {
+    pub start: usize,
+    pub removed: Vec<T>,
+    pub inserted: Vec<T>,
+}
+
+impl<T: Clone + PartialEq> ListDiff<T> {
+    /// Find the changed range between the lists.
+    /// Returns `None` if the lists are equal.
+    pub fn new(old: &[T], new: &[T]) -> Option<Self> {
+        let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+        let suffix = old[prefix..]
+            .iter()
+            .rev()
+            .zip(new[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let removed = &old[prefix..old.len() - suffix];
+        let inserted = &new[prefix..new.len() - suffix];
+        if removed.is_empty() && inserted.is_empty() {
+            return None;
+        }
+        Some(Self {
+            start: prefix,
+            removed: removed.to_vec(),
+            inserted: inserted.to_vec(),
+        })
+    }
+
+    /// Change the old list into the new one.
+    pub fn apply(&self, list: &mut Vec<T>) {
+        Self::splice(list, self.start, self.removed.len(), &self.inserted);
+    }
+
+    /// Change the new list back into the old one.
+    pub fn revert(&self, list: &mut Vec<T>) {
+        Self::splice(list, self.start, self.inserted.len(), &self.removed);
+    }
+
+    fn splice(list: &mut Vec<T>, start: usize, len: usize, items: &[T]) {
+        let start = start.min(list.len());
+        let end = (start + len).min(list.len());
+        list.splice(start..end, items.iter().cloned());
+    }
+
+    fn describe(&self, name: &str) -> String {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        match (self.removed.len(), self.inserted.len()) {
+            (0, n) => format!("Add {n} {name}{}", plural(n)),
+            (n, 0) => format!("Remove {n} {name}{}", plural(n)),
+            (_, n) => format!("Edit {name}{}", plural(n)),
+        }
+    }
+}
+
+/// Compact difference between two states of the level.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LevelDiff {
+    pub events: Option<ListDiff<TimedEvent>>,
+    pub timing: Option<ListDiff<TimingPoint>>,
+    pub groups: Option<ListDiff<LightGroup>>,
+    pub health_sections: Option<ListDiff<HealthSection>>,
+}
+
+impl LevelDiff {
+    pub fn new(old: &Level, new: &Level) -> Self {
+        Self {
+            events: ListDiff::new(&old.events, &new.events),
+            timing: ListDiff::new(&old.timing.points, &new.timing.points),
+            groups: ListDiff::new(&old.groups, &new.groups),
+            health_sections: ListDiff::new(&old.health_sections, &new.health_sections),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_none()
+            && self.timing.is_none()
+            && self.groups.is_none()
+            && self.health_sections.is_none()
+    }
+
+    /// Change the old level into the new one.
+    pub fn apply(&self, level: &mut Level) {
+        if let Some(diff) = &self.events {
+            diff.apply(&mut level.events);
+        }
+        if let Some(diff) = &self.timing {
+            diff.apply(&mut level.timing.points);
+        }
+        if let Some(diff) = &self.groups {
+            diff.apply(&mut level.groups);
+        }
+        if let Some(diff) = &self.health_sections {
+            diff.apply(&mut level.health_sections);
+        }
+    }
+
+    /// Change the new level back into the old one.
+    pub fn revert(&self, level: &mut Level) {
+        if let Some(diff) = &self.events {
+            diff.revert(&mut level.events);
+        }
+        if let Some(diff) = &self.timing {
+            diff.revert(&mut level.timing.points);
+        }
+        if let Some(diff) = &self.groups {
+            diff.revert(&mut level.groups);
+        }
+        if let Some(diff) = &self.health_sections {
+            diff.revert(&mut level.health_sections);
+        }
+    }
+
+    /// Short description of the change.
+    pub fn describe(&self) -> String {
+        [
+            self.events.as_ref().map(|diff| diff.describe("event")),
+            self.timing.as_ref().map(|diff| diff.describe("timing point")),
+            self.groups.as_ref().map(|diff| diff.describe("group")),
+            self.health_sections
+                .as_ref()
+                .map(|diff| diff.describe("health section")),
+        ]
+        .into_iter()
+        .flatten()
+        .join(", ")
+    }
+}
+
+/// A single change in the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub label: HistoryLabel,
+    /// Difference from the state before the change to the state after it.
+    pub diff: LevelDiff,
+}
+
+impl HistoryEntry {
+    pub fn name(&self) -> String {
+        self.label
+            .name()
+            .map_or_else(|| self.diff.describe(), Into::into)
+    }
+}
+
+/// History as it is saved alongside the level set.
+#[derive(Serialize, Deserialize)]
+struct SavedHistory {
+    /// Hash of the level at the end of the undo stack.
+    level_hash: String,
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
 }
 
 pub struct History {
-    /// State that will be saved in the undo stack.
+    /// State after the last saved change, used to calculate the diffs.
     /// (Not every operation gets saved)
     pub buffer_state: Level,
     pub buffer_label: HistoryLabel,
+    /// Whether the last entry of the undo stack holds the buffered changes,
+    /// so the merged changes should be added to it.
+    buffer_entry: bool,
 
-    pub undo_stack: Vec<Level>,
-    pub redo_stack: Vec<Level>,
+    pub undo_stack: Vec<HistoryEntry>,
+    pub redo_stack: Vec<HistoryEntry>,
 }
 
 impl History {
@@ -53,44 +246,131 @@ impl History {
         Self {
             buffer_state: level.clone(),
             buffer_label: HistoryLabel::default(),
+            buffer_entry: false,
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
         }
     }
 
+    /// Load the history saved alongside the level set.
+    /// Returns `None` if there is no saved history or it belongs to another version of the level.
+    pub fn load(group_path: &std::path::Path, level_index: usize, level: &Level) -> Option<Self> {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (group_path, level_index, level);
+            None
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let bytes = std::fs::read(history_path(group_path, level_index)).ok()?;
+            let saved: SavedHistory = match cbor4ii::serde::from_slice(&bytes) {
+                Ok(saved) => saved,
+                Err(err) => {
+                    log::error!("Failed to parse the editor history: {err:?}");
+                    return None;
+                }
+            };
+            if saved.level_hash != level.calculate_hash() {
+                log::debug!("Saved editor history does not match the level, ignoring it");
+                return None;
+            }
+            log::debug!("Loaded {} history entries", saved.undo_stack.len());
+            Some(Self {
+                undo_stack: saved.undo_stack,
+                redo_stack: saved.redo_stack,
+                ..Self::new(level)
+            })
+        }
+    }
+
+    /// Save the history alongside the level set.
+    /// Should be called right after saving the level.
+    pub fn save(&self, group_path: &std::path::Path, level_index: usize) {
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (group_path, level_index);
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let saved = SavedHistory {
+                level_hash: self.buffer_state.calculate_hash(),
+                undo_stack: self.undo_stack.clone(),
+                redo_stack: self.redo_stack.clone(),
+            };
+            let path = history_path(group_path, level_index);
+            let result = cbor4ii::serde::to_vec(Vec::new(), &saved)
+                .map_err(|err| format!("{err:?}"))
+                .and_then(|bytes| {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent).map_err(|err| format!("{err:?}"))?;
+                    }
+                    std::fs::write(&path, bytes).map_err(|err| format!("{err:?}"))
+                });
+            if let Err(err) = result {
+                log::error!("Failed to save the editor history: {err}");
+            }
+        }
+    }
+
+    /// All changes in chronological order, including the undone ones.
+    pub fn entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.undo_stack.iter().chain(self.redo_stack.iter().rev())
+    }
+
+    /// The number of applied changes in [`Self::entries`].
+    pub fn position(&self) -> usize {
+        self.undo_stack.len()
+    }
+
     pub fn undo(&mut self, level: &mut Level) {
-        if let Some(mut state) = self.undo_stack.pop() {
-            std::mem::swap(&mut state, level);
-            self.redo_stack.push(state);
-            self.buffer_state = level.clone();
+        if let Some(entry) = self.undo_stack.pop() {
+            entry.diff.revert(&mut self.buffer_state);
+            let diff = LevelDiff::new(&self.buffer_state, level);
+            *level = self.buffer_state.clone();
+            self.redo_stack.push(HistoryEntry {
+                label: entry.label,
+                diff,
+            });
             self.buffer_label = HistoryLabel::Unknown;
+            self.buffer_entry = false;
             log::debug!("Change undone");
         }
     }
 
     pub fn redo(&mut self, level: &mut Level) {
-        if let Some(mut state) = self.redo_stack.pop() {
-            std::mem::swap(&mut state, level);
-            self.undo_stack.push(state);
-            self.buffer_state = level.clone();
+        if let Some(entry) = self.redo_stack.pop() {
+            let before = level.clone();
+            entry.diff.apply(&mut self.buffer_state);
+            *level = self.buffer_state.clone();
+            self.undo_stack.push(HistoryEntry {
+                label: entry.label,
+                diff: LevelDiff::new(&before, level),
+            });
             self.buffer_label = HistoryLabel::Unknown;
+            self.buffer_entry = false;
             log::debug!("Change redone");
         }
     }
 
+    /// Undo or redo changes until `position` changes are applied.
+    pub fn jump(&mut self, level: &mut Level, position: usize) {
+        while self.undo_stack.len() > position {
+            self.undo(level);
+        }
+        while self.undo_stack.len() < position && !self.redo_stack.is_empty() {
+            self.redo(level);
+        }
+    }
+
     pub fn start_merge(&mut self, level: &Level, label: HistoryLabel) {
         if self.buffer_label.should_merge(&label) {
-            self.buffer_state = level.clone();
+            self.merge(level);
             self.buffer_label = HistoryLabel::Merge;
             return;
         }
 
-        if Some(&self.buffer_state) != self.undo_stack.last() {
-            // Push old changes
-            self.save_force(level, HistoryLabel::Merge);
-        } else {
-            self.buffer_label = HistoryLabel::Merge;
-        }
+        // Push old changes
+        self.save_force(level, HistoryLabel::Merge);
     }
 
     pub fn save_state(&mut self, level: &Level, label: HistoryLabel) {
@@ -101,7 +381,7 @@ impl History {
 
         if self.buffer_label.should_merge(&label) {
             // Merge changes
-            self.buffer_state = level.clone();
+            self.merge(level);
             return;
         }
 
@@ -110,22 +390,70 @@ impl History {
 
     /// Flush all buffered changes, if there are any.
     pub fn flush(&mut self, level: &Level, label: HistoryLabel) {
+        self.merge(level);
+        if self.buffer_entry
+            && label.name().is_some()
+            && let Some(entry) = self.undo_stack.last_mut()
+        {
+            entry.label = label;
+        }
         self.buffer_label = label;
-        self.buffer_state = level.clone();
         log::trace!("Flushed changes as {label:?}");
     }
 
+    /// Add the changes to the buffered entry.
+    fn merge(&mut self, level: &Level) {
+        if *level == self.buffer_state {
+            return;
+        }
+        if !self.buffer_entry {
+            self.push(level, self.buffer_label);
+            return;
+        }
+        let Some(entry) = self.undo_stack.last_mut() else {
+            self.push(level, self.buffer_label);
+            return;
+        };
+
+        let mut before = self.buffer_state.clone();
+        entry.diff.revert(&mut before);
+        entry.diff = LevelDiff::new(&before, level);
+        if entry.diff.is_empty() {
+            // Changes cancelled out
+            self.undo_stack.pop();
+            self.buffer_entry = false;
+        }
+        self.buffer_state = level.clone();
+    }
+
     /// Save the level without doing any checks.
     fn save_force(&mut self, level: &Level, label: HistoryLabel) {
-        // Push the change
         self.buffer_label = label;
-        let mut state = level.clone();
-        std::mem::swap(&mut state, &mut self.buffer_state);
+        self.buffer_entry = false;
+        self.push(level, label);
+        log::debug!("Saved changes to the stack, starting new buffer {label:?}");
+    }
 
-        self.undo_stack.push(state);
-        // TODO: limit capacity
-        self.redo_stack.clear();
+    /// Push the changes since the buffered state as a new entry.
+    fn push(&mut self, level: &Level, label: HistoryLabel) {
+        let diff = LevelDiff::new(&self.buffer_state, level);
+        if diff.is_empty() {
+            return;
+        }
 
-        log::debug!("Saved old state to the stack, starting new buffer {label:?}");
+        self.undo_stack.push(HistoryEntry { label, diff });
+        if self.undo_stack.len() > MAX_UNDO {
+            self.undo_stack.remove(0);
+        }
+        self.redo_stack.clear();
+        self.buffer_state = level.clone();
+        self.buffer_entry = true;
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+fn history_path(group_path: &std::path::Path, level_index: usize) -> std::path::PathBuf {
+    group_path
+        .join("history")
+        .join(format!("{level_index}.cbor"))
+}
@@ -29,6 +29,10 @@ pub struct LevelEditor {
     pub place_scale: Coord,
     /// The beat subdivision to snap to.
     pub beat_snap: BeatTime,
+    /// Whether new events should snap to the nearest onset of the music.
+    pub snap_to_onsets: bool,
+    /// Detected onsets of the music in the level time, sorted.
+    pub onsets: Vec<Time>,
 
     pub state: EditingState,
     /// Whether the last frame was scrolled through time.
@@ -53,7 +57,7 @@ pub enum TopLevelEventIdx {
     Timing(usize),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum EditorEventIdx {
     Event(usize),
     Waypoint(LightId, WaypointId),
@@ -108,7 +112,7 @@ impl Selection {
                     .map(|id| {
                         if let &TopLevelEventIdx::Event(i) = id
                             && let Some(event) = level.events.get(i)
-                            && let Event::Light(_) = event.event
+                            && event.event.as_light().is_some()
                         {
                             Some(LightId { event: i })
                         } else {
@@ -132,7 +136,7 @@ impl Selection {
                 .flat_map(|&id| {
                     if let TopLevelEventIdx::Event(i) = id
                         && let Some(event) = level.events.get(i)
-                        && let Event::Light(_) = &event.event
+                        && event.event.as_light().is_some()
                     {
                         Some(LightId { event: i })
                     } else {
@@ -400,13 +404,20 @@ impl LevelEditor {
             real_time: FloatTime::ZERO,
             timeline_light_hover: None,
 
-            history: History::new(&level.level.data),
+            history: History::load(
+                &level.group.cached.local.path,
+                level.level_index,
+                &level.level.data,
+            )
+            .unwrap_or_else(|| History::new(&level.level.data)),
             clipboard: Clipboard::new(),
             selection: Selection::new(),
 
             place_rotation: Angle::ZERO,
             place_scale: Coord::ONE,
             beat_snap: BeatTime::QUARTER,
+            snap_to_onsets: false,
+            onsets: Vec::new(),
 
             state: EditingState::Idle,
             was_scrolling_time: false,
@@ -440,24 +451,81 @@ impl LevelEditor {
         editor.current_time = self.current_time;
         editor.place_rotation = self.place_rotation;
         editor.place_scale = self.place_scale;
+        editor.snap_to_onsets = self.snap_to_onsets;
 
         editor
     }
 
+    /// The time at which new events are placed.
+    /// Snaps to the closest onset within half a beat subdivision if enabled.
+    pub fn placement_time(&self) -> Time {
+        let time = self.current_time.target;
+        if !self.snap_to_onsets {
+            return time;
+        }
+
+        let beat_time = self.level.timing.get_timing(time).beat_time;
+        let max_distance = self.beat_snap.as_time(beat_time) / 2;
+        let i = self.onsets.partition_point(|&onset| onset < time);
+        [i.checked_sub(1), Some(i)]
+            .into_iter()
+            .flatten()
+            .filter_map(|i| self.onsets.get(i).copied())
+            .filter(|onset| (onset - time).abs() <= max_distance)
+            .min_by_key(|onset| (onset - time).abs())
+            .unwrap_or(time)
+    }
+
     pub fn delete_light(&mut self, id: LightId) {
         if id.event >= self.level.events.len() {
             return;
         }
         self.level.events.swap_remove(id.event);
+        self.remove_empty_groups();
         self.selection.clear();
         self.save_state(default());
     }
 
+    /// Remove the groups that have no lights and update the indices of the rest.
+    pub fn remove_empty_groups(&mut self) {
+        let mut used = vec![false; self.level.groups.len()];
+        for event in &self.level.events {
+            if let Some(light) = event.event.as_light()
+                && let Some(used) = light.group.and_then(|group| used.get_mut(group))
+            {
+                *used = true;
+            }
+        }
+
+        let mut new_index = Vec::with_capacity(used.len());
+        let mut next = 0;
+        for &used in &used {
+            new_index.push(next);
+            if used {
+                next += 1;
+            }
+        }
+        if next == used.len() {
+            return;
+        }
+
+        let mut i = 0;
+        self.level.groups.retain(|_| {
+            i += 1;
+            used[i - 1]
+        });
+        for event in &mut self.level.events {
+            if let Some(light) = event.event.as_light_mut() {
+                light.group = light.group.and_then(|group| new_index.get(group).copied());
+            }
+        }
+    }
+
     pub fn delete_waypoint(&mut self, light_id: LightId, waypoint_id: WaypointId) {
         let Some(timed_event) = self.level.events.get_mut(light_id.event) else {
             return;
         };
-        let Event::Light(event) = &mut timed_event.event else {
+        let Some(event) = timed_event.event.as_light_mut() else {
             return;
         };
         match waypoint_id {
@@ -535,6 +603,30 @@ impl LevelEditor {
         }
     }
 
+    /// Undo or redo changes to get to the given point in history.
+    pub fn jump_history(&mut self, position: usize) {
+        match &mut self.state {
+            EditingState::Playing { .. } => {}
+            EditingState::Place { .. } => {}
+            EditingState::Idle | EditingState::Waypoints { .. } => {
+                self.history.jump(&mut self.level, position);
+            }
+        }
+    }
+
+    /// The rest transform of the group that the light belongs to.
+    /// Waypoints of grouped lights are edited relative to it.
+    pub fn group_pivot(&self, light_id: LightId) -> TransformLight {
+        self.level
+            .events
+            .get(light_id.event)
+            .and_then(|event| event.event.as_light()?.group)
+            .and_then(|group| self.level.groups.get(group))
+            .map_or_else(TransformLight::identity, |group| {
+                group.movement.initial.transform
+            })
+    }
+
     /// Save level changes to the history.
     #[track_caller]
     pub fn save_state(&mut self, label: HistoryLabel) {
@@ -611,13 +703,25 @@ impl LevelEditor {
                         time = self.level.timing.snap_to_best_alignment(time).0;
                     }
 
+                    let envelope = event.event.envelope;
                     let mut event = event.event.event;
-                    if let Event::Light(light) = &mut event {
+                    if let Some(light) = event.as_light_mut() {
                         // Beat align each waypoint
                         beat_align_waypoints(&self.level.timing, time, light);
+                        // The group might not exist when pasting from another level
+                        if light
+                            .group
+                            .is_some_and(|group| group >= self.level.groups.len())
+                        {
+                            light.group = None;
+                        }
                     }
 
-                    TimedEvent { time, event }
+                    TimedEvent {
+                        time,
+                        event,
+                        envelope,
+                    }
                 }));
 
                 // Change selection to the new events
@@ -710,6 +814,7 @@ impl LevelEditor {
                 Some(Level {
                     events,
                     timing: self.level.timing.clone(), // TODO: cheaper clone
+                    groups: self.level.groups.clone(),
                 })
             })
             .flatten();
@@ -785,11 +890,12 @@ impl LevelEditor {
         if let EditingState::Waypoints { light_id, state } = &self.state {
             let light_id = *light_id;
             if let Some(timed_event) = self.level.events.get(light_id.event)
-                && let Event::Light(light_event) = &timed_event.event
+                && let Some(light_event) = timed_event.event.as_light()
             {
                 let event_time = timed_event.time;
                 // If some waypoints overlap, render the temporaly closest one
                 let base_collider = Collider::new(vec2::ZERO, light_event.shape);
+                let pivot = self.group_pivot(light_id);
 
                 /// Waypoints past this time-distance are not rendered at all
                 const MAX_VISIBILITY: Time = 5 * TIME_IN_FLOAT_TIME;
@@ -816,8 +922,9 @@ impl LevelEditor {
                             WaypointEdit {
                                 visible: visible(time),
                                 original: Some(i),
-                                control: base_collider.transformed(trans_control),
-                                actual: base_collider.transformed(trans_actual),
+                                control: base_collider
+                                    .transformed(trans_control.relative_to(&pivot)),
+                                actual: base_collider.transformed(trans_actual.relative_to(&pivot)),
                             },
                             time,
                         )
@@ -859,7 +966,9 @@ impl LevelEditor {
                         translation: cursor_world_pos_snapped,
                         rotation: self.place_rotation,
                         scale: self.place_scale,
+                        stretch: vec2::splat(Coord::ONE),
                         hollow: r32(-1.0),
+                        pulse: R32::ZERO,
                     });
                     points.insert(
                         i,
@@ -1,4 +1,5 @@
 mod action;
+mod beat_detection;
 mod clipboard;
 mod config;
 mod grid;
@@ -13,6 +14,7 @@ pub mod ui;
 
 pub use self::{
     action::*,
+    beat_detection::*,
     clipboard::*,
     config::*,
     grid::*,
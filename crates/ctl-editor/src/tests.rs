@@ -98,3 +98,145 @@ fn test_shift_alignment_triplets() {
         (639, BeatTime::TWELFTH)
     );
 }
+
+#[test]
+fn test_history_diff_undo_redo() {
+    let mut level = Level {
+        timing: Timing::new(r32(120.0)),
+        ..default()
+    };
+    let initial = level.clone();
+    let mut history = History::new(&level);
+
+    level.timing.points.push(TimingPoint {
+        time: 1000,
+        beat_time: r32(0.25),
+    });
+    history.save_state(&level, HistoryLabel::Unknown);
+    let added = level.clone();
+
+    level.timing.points[0].beat_time = r32(0.4);
+    history.save_state(&level, HistoryLabel::Unknown);
+    let edited = level.clone();
+
+    assert_eq!(history.undo_stack.len(), 2);
+    assert_eq!(
+        history.undo_stack[1]
+            .diff
+            .timing
+            .as_ref()
+            .map(|diff| diff.inserted.len()),
+        Some(1)
+    );
+
+    history.undo(&mut level);
+    assert_eq!(level, added);
+    history.undo(&mut level);
+    assert_eq!(level, initial);
+    history.redo(&mut level);
+    assert_eq!(level, added);
+
+    history.jump(&mut level, 2);
+    assert_eq!(level, edited);
+    history.jump(&mut level, 0);
+    assert_eq!(level, initial);
+}
+
+#[test]
+fn test_history_save_load() {
+    let group_path = std::env::temp_dir().join(format!("ctl-history-test-{}", std::process::id()));
+    let initial = Level {
+        timing: Timing::new(r32(120.0)),
+        ..default()
+    };
+    let mut level = initial.clone();
+    let mut history = History::new(&level);
+    level.timing.points.push(TimingPoint {
+        time: 1000,
+        beat_time: r32(0.25),
+    });
+    history.save_state(&level, HistoryLabel::Unknown);
+    history.save(&group_path, 0);
+
+    let mut loaded = History::load(&group_path, 0, &level).expect("history should be loaded");
+    assert_eq!(loaded.position(), 1);
+    loaded.undo(&mut level);
+    assert_eq!(level, initial);
+
+    // The saved history belongs to the edited version of the level
+    assert!(History::load(&group_path, 0, &initial).is_none());
+    assert!(History::load(&group_path, 1, &initial).is_none());
+
+    let _ = std::fs::remove_dir_all(&group_path);
+}
+
+/// Sample rate of the synthetic music used for the beat detection tests.
+const CLICK_SAMPLE_RATE: f32 = 44100.0;
+/// Allowed error of the detected beat times (in ms), about two frames of the onset envelope.
+const CLICK_TIME_TOLERANCE: Time = 25;
+
+/// Synthetic metronome with a short decaying 1 kHz click on every beat.
+/// Each section is given by its start time (in seconds) and BPM, and lasts until the next one.
+fn click_track(sections: &[(f32, f32)], duration: f32) -> Vec<f32> {
+    let mut samples = vec![0.0; (duration * CLICK_SAMPLE_RATE) as usize];
+    let click_len = (0.01 * CLICK_SAMPLE_RATE) as usize;
+    for (i, &(start, bpm)) in sections.iter().enumerate() {
+        let end = sections.get(i + 1).map_or(duration, |&(time, _)| time);
+        let mut time = start;
+        while time < end {
+            let first = (time * CLICK_SAMPLE_RATE).round() as usize;
+            for (j, sample) in samples.iter_mut().skip(first).take(click_len).enumerate() {
+                let t = j as f32 / CLICK_SAMPLE_RATE;
+                let decay = 1.0 - j as f32 / click_len as f32;
+                *sample = (std::f32::consts::TAU * 1000.0 * t).sin() * decay;
+            }
+            time += 60.0 / bpm;
+        }
+    }
+    samples
+}
+
+#[test]
+fn test_beat_detection_constant() {
+    let samples = click_track(&[(0.25, 120.0)], 40.0);
+    let proposal = TimingProposal::detect(&samples, CLICK_SAMPLE_RATE);
+
+    assert_eq!(proposal.points.len(), 1);
+    let point = &proposal.points[0];
+    assert!((point.beat_time.as_f32() - 0.5).abs() < 1e-3);
+    assert!((point.time - 250).abs() <= CLICK_TIME_TOLERANCE);
+}
+
+#[test]
+fn test_beat_detection_tempo_change() {
+    let samples = click_track(&[(0.1, 120.0), (30.1, 150.0)], 60.0);
+    let proposal = TimingProposal::detect(&samples, CLICK_SAMPLE_RATE);
+
+    assert_eq!(proposal.points.len(), 2);
+    let (first, second) = (&proposal.points[0], &proposal.points[1]);
+    assert!((first.beat_time.as_f32() - 0.5).abs() < 1e-3);
+    assert!((first.time - 100).abs() <= CLICK_TIME_TOLERANCE);
+    assert!((second.beat_time.as_f32() - 0.4).abs() < 1e-3);
+    assert!((second.time - 30100).abs() <= CLICK_TIME_TOLERANCE);
+}
+
+#[test]
+fn test_beat_detection_silence() {
+    assert!(
+        TimingProposal::detect(&[], CLICK_SAMPLE_RATE)
+            .points
+            .is_empty()
+    );
+
+    let silence = vec![0.0; 20 * CLICK_SAMPLE_RATE as usize];
+    assert!(
+        TimingProposal::detect(&silence, CLICK_SAMPLE_RATE)
+            .points
+            .is_empty()
+    );
+    assert!(
+        MusicAnalysis::new(&silence, CLICK_SAMPLE_RATE)
+            .onsets
+            .is_empty()
+    );
+}
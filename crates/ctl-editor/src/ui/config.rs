@@ -95,6 +95,50 @@ impl EditorConfigUi {
                 }
             }
 
+            // Timing detection
+            let button_pos = bar.cut_top(context.font_size * 1.2);
+            let button = context
+                .state
+                .get_root_or(|| ButtonWidget::new("Detect Timing"));
+            button.update(button_pos, context);
+            if button.text.state.mouse_left.clicked {
+                actions.push(EditorAction::DetectTiming.into());
+            }
+
+            if let Some(proposal) = &editor.timing_proposal
+                && let Some(first) = proposal.points.first()
+            {
+                let info_pos = bar.cut_top(context.font_size);
+                let text = context.state.get_root_or(|| TextWidget::new(""));
+                text.text = format!(
+                    "{:.2} BPM at {:.3}s",
+                    60.0 / first.beat_time.as_f32(),
+                    time_to_seconds(first.time).as_f32()
+                )
+                .into();
+                text.update(info_pos, context);
+                if proposal.points.len() > 1 {
+                    let changes_pos = bar.cut_top(context.font_size);
+                    let text = context.state.get_root_or(|| TextWidget::new(""));
+                    text.text = format!("{} tempo changes", proposal.points.len() - 1).into();
+                    text.update(changes_pos, context);
+                }
+
+                let buttons = bar.cut_top(context.font_size * 1.2).split_columns(4);
+                for (pos, name, action) in [
+                    (buttons[0], "x2", EditorAction::ScaleTimingProposal(r32(0.5))),
+                    (buttons[1], "/2", EditorAction::ScaleTimingProposal(r32(2.0))),
+                    (buttons[2], "Accept", EditorAction::AcceptTimingProposal),
+                    (buttons[3], "Discard", EditorAction::DiscardTimingProposal),
+                ] {
+                    let button = context.state.get_root_or(|| ButtonWidget::new(name));
+                    button.update(pos, context);
+                    if button.text.state.mouse_left.clicked {
+                        actions.push(action.into());
+                    }
+                }
+            }
+
             bar.cut_top(context.layout_size * 0.5);
 
             // Music authors
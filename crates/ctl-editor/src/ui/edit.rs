@@ -100,6 +100,9 @@ impl EditorEditUi {
         // Placement
         left_bar = helper.layout_placement(self, tooltip, left_bar, actions, context);
 
+        // History
+        left_bar = helper.layout_history(self, left_bar, actions, context);
+
         // Copy buffer
         let buffer = right_bar.cut_top(context.font_size * 1.0);
         right_bar.cut_top(context.font_size * 0.5);
@@ -186,14 +189,11 @@ impl LayoutHelper<'_> {
         let button = context.state.get_root_or(|| {
             DropdownWidget::new(
                 "Light",
-                self.editor.config.shapes.iter().map(|shape| {
-                    let name = match shape {
-                        Shape::Circle { .. } => "Circle",
-                        Shape::Line { .. } => "Line",
-                        Shape::Rectangle { .. } => "Rectangle",
-                    };
-                    (name, *shape)
-                }),
+                self.editor
+                    .config
+                    .shapes
+                    .iter()
+                    .map(|shape| (shape_name(shape), *shape)),
             )
         });
         if let Some(i) = button.update(new_light, context)
@@ -219,6 +219,14 @@ impl LayoutHelper<'_> {
                     ("Curvature", LevelAction::NewCurvature(BeatTime::WHOLE)),
                     ("Noise", LevelAction::NewNoiseOffset(BeatTime::WHOLE)),
                     ("Spotlight", LevelAction::NewSpotlight(BeatTime::WHOLE)),
+                    ("Camera zoom", LevelAction::NewCameraZoom(BeatTime::WHOLE)),
+                    (
+                        "Camera rotate",
+                        LevelAction::NewCameraRotate(BeatTime::WHOLE),
+                    ),
+                    ("Camera pan", LevelAction::NewCameraPan(BeatTime::WHOLE)),
+                    ("Flash", LevelAction::NewFlash(BeatTime::HALF)),
+                    ("Tint", LevelAction::NewTint(BeatTime::WHOLE)),
                 ],
             )
         });
@@ -277,6 +285,26 @@ impl LayoutHelper<'_> {
         toggle.checked = self.editor.render_options.show_grid;
         tooltip.update(&toggle.state, "C-~", context);
 
+        let bands = bar.cut_top(context.font_size);
+        bar.cut_top(self.spacing);
+        let toggle = context
+            .state
+            .get_root_or(|| ToggleWidget::new("Frequency bands"));
+        toggle.update(bands, context);
+        if toggle.state.mouse_left.clicked {
+            actions.push(EditorAction::ToggleFrequencyBands.into());
+        }
+        toggle.checked = self.editor.show_frequency_bands;
+
+        let onsets = bar.cut_top(context.font_size);
+        bar.cut_top(self.spacing);
+        let toggle = context.state.get_root_or(|| ToggleWidget::new("Onsets"));
+        toggle.update(onsets, context);
+        if toggle.state.mouse_left.clicked {
+            actions.push(EditorAction::ToggleOnsets.into());
+        }
+        toggle.checked = self.editor.show_onsets;
+
         // let waypoints = bar.cut_top(button_height);
         // bar.cut_top(spacing);
         // update!(self.view_waypoints, waypoints);
@@ -328,6 +356,17 @@ impl LayoutHelper<'_> {
         button.checked = self.editor.snap_to_grid.temporary;
         tooltip.update(&button.state, "~", context);
 
+        let onset_snap = bar.cut_top(context.font_size);
+        bar.cut_top(self.spacing);
+        let button = context
+            .state
+            .get_root_or(|| ToggleWidget::new("Snap to onsets"));
+        button.update(onset_snap, context);
+        if button.state.mouse_left.clicked {
+            actions.push(LevelAction::ToggleOnsetSnap.into());
+        }
+        button.checked = self.level_editor.snap_to_onsets;
+
         let grid_size = bar.cut_top(self.value_height);
         bar.cut_top(self.spacing);
         {
@@ -344,6 +383,60 @@ impl LayoutHelper<'_> {
         bar
     }
 
+    /// History of changes, click to jump to any point
+    fn layout_history(
+        &self,
+        _ui: &mut EditorEditUi,
+        mut bar: Aabb2<f32>,
+        actions: &mut Vec<EditorStateAction>,
+        context: &UiContext,
+    ) -> Aabb2<f32> {
+        let title = bar.cut_top(self.title_size);
+        bar.cut_top(self.spacing);
+        let text = context
+            .state
+            .get_root_or(|| TextWidget::new("History").aligned(vec2(0.0, 0.5)));
+        text.update(title, context);
+        text.options.size = self.title_size;
+
+        let history = &self.level_editor.history;
+        let entries: Vec<_> = history.entries().collect();
+        let current = history.position();
+
+        // Show the positions around the current one, latest on top
+        let row_height = context.font_size * 0.8;
+        let rows = ((bar.height() / row_height) as usize).clamp(1, 10);
+        let last = (current + rows / 2).max(rows - 1).min(entries.len());
+        let first = (last + 1).saturating_sub(rows);
+        for position in (first..=last).rev() {
+            let row = bar.cut_top(row_height);
+            let name = match position.checked_sub(1) {
+                Some(i) => entries[i].name(),
+                None => "Start".to_string(),
+            };
+            let text = context
+                .state
+                .get_root_or(|| TextWidget::new("").aligned(vec2(0.0, 0.5)));
+            text.text = name.into();
+            text.update(row, context);
+            text.options.size = row_height * 0.9;
+            text.options.color = if position == current {
+                context.theme().highlight
+            } else if position > current {
+                // Undone changes
+                Color::lerp(context.theme().dark, context.theme().light, 0.5)
+            } else {
+                context.theme().light
+            };
+            if text.state.mouse_left.clicked && position != current {
+                actions.push(LevelAction::JumpHistory(position).into());
+            }
+        }
+
+        bar.cut_top(context.layout_size * 1.5);
+        bar
+    }
+
     /// Copy/paste buffer
     fn layout_copy_buffer(&self, _ui: &mut EditorEditUi, pos: Aabb2<f32>, context: &UiContext) {
         if let Some(ClipboardItem::Events { events, timing, .. }) =
@@ -351,7 +444,7 @@ impl LayoutHelper<'_> {
         {
             let mut count_lights = 0;
             for event in events {
-                if let Event::Light(_) = event.event.event {
+                if event.event.event.as_light().is_some() {
                     count_lights += 1;
                 }
             }
@@ -439,12 +532,37 @@ impl LayoutHelper<'_> {
                     );
                 }
                 tooltip.update(&button.text.state, "D", context);
+
+                let group_pos = bar.cut_top(self.button_height);
+                bar.cut_top(self.spacing);
+                let button = context.state.get_root_or(|| ButtonWidget::new("Group"));
+                button.update(group_pos, context);
+                if button.text.state.mouse_left.clicked {
+                    actions.push(LevelAction::GroupLights(selected.to_vec()).into());
+                }
+
+                if selected.iter().any(|id| {
+                    self.level_editor
+                        .level
+                        .events
+                        .get(id.event)
+                        .and_then(|event| event.event.as_light())
+                        .is_some_and(|light| light.group.is_some())
+                }) {
+                    let ungroup_pos = bar.cut_top(self.button_height);
+                    bar.cut_top(self.spacing);
+                    let button = context.state.get_root_or(|| ButtonWidget::new("Ungroup"));
+                    button.update(ungroup_pos, context);
+                    if button.text.state.mouse_left.clicked {
+                        actions.push(LevelAction::UngroupLights(selected.to_vec()).into());
+                    }
+                }
             }
             std::cmp::Ordering::Equal => {
                 // Exactly 1 light selected
                 let light_id = *selected.first().unwrap();
                 if let Some(event) = self.level_editor.level.events.get(light_id.event)
-                    && let Event::Light(light) = &event.event
+                    && let Some(light) = event.event.as_light()
                 {
                     let danger_pos = bar.cut_top(self.button_height);
                     bar.cut_top(self.spacing);
@@ -458,6 +576,15 @@ impl LayoutHelper<'_> {
                     button.checked = light.danger;
                     tooltip.update(&button.state, "D", context);
 
+                    let sustain_pos = bar.cut_top(self.button_height);
+                    bar.cut_top(self.spacing);
+                    let button = context.state.get_root_or(|| ToggleWidget::new("Sustain"));
+                    button.update(sustain_pos, context);
+                    if button.state.mouse_left.clicked {
+                        actions.push(LevelAction::ToggleSustain(light_id).into());
+                    }
+                    button.checked = light.sustain;
+
                     let timing = &self.level_editor.level.timing;
 
                     {
@@ -531,6 +658,32 @@ impl LayoutHelper<'_> {
                         context.update_focus(slider.state.hovered);
                     }
 
+                    if let Some(index) = light.group
+                        && let Some(group) = self.level_editor.level.groups.get(index)
+                    {
+                        self.layout_group(bar, index, group, light_id, context, actions);
+                    }
+
+                    match &event.event {
+                        Event::Pattern(pattern) => {
+                            self.layout_pattern(
+                                bar, event.time, pattern, light_id, context, actions,
+                            );
+                        }
+                        _ => {
+                            bar.cut_top(context.layout_size * 0.5);
+                            let pattern_pos = bar.cut_top(self.button_height);
+                            bar.cut_top(self.spacing);
+                            let button = context
+                                .state
+                                .get_root_or(|| ButtonWidget::new("Make Pattern"));
+                            button.update(pattern_pos, context);
+                            if button.text.state.mouse_left.clicked {
+                                actions.push(LevelAction::MakePattern(light_id).into());
+                            }
+                        }
+                    }
+
                     bar.cut_top(context.layout_size * 1.5);
 
                     let waypoints = bar.cut_top(self.title_size);
@@ -548,6 +701,337 @@ impl LayoutHelper<'_> {
             }
             std::cmp::Ordering::Less => {}
         }
+
+        self.layout_transform_tools(selected, bar, actions, context);
+    }
+
+    /// Tools transforming the whole selection at once.
+    fn layout_transform_tools(
+        &self,
+        selected: &[LightId],
+        bar: &mut Aabb2<f32>,
+        actions: &mut Vec<EditorStateAction>,
+        context: &UiContext,
+    ) {
+        if selected.is_empty() {
+            return;
+        }
+
+        bar.cut_top(context.layout_size * 0.5);
+        let title_pos = bar.cut_top(self.title_size);
+        let text = context
+            .state
+            .get_root_or(|| TextWidget::new("Transform").aligned(vec2(0.0, 0.5)));
+        text.update(title_pos, context);
+        text.options.size = self.title_size;
+
+        let pivot_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context
+            .state
+            .get_root_or(|| ToggleWidget::new("Around selection"));
+        let mut around_selection = button.checked;
+        button.update_state(pivot_pos, context, &mut around_selection);
+
+        // The center of the selection at the current time, or the origin
+        let anchor = || {
+            if !around_selection {
+                return vec2::ZERO;
+            }
+            let time = self.level_editor.current_time.target;
+            let positions: Vec<vec2<Coord>> = selected
+                .iter()
+                .filter_map(|id| {
+                    let event = self.level_editor.level.events.get(id.event)?;
+                    let light = event.event.as_light()?;
+                    Some(light.movement.get(time - event.time).translation)
+                })
+                .collect();
+            if positions.is_empty() {
+                return vec2::ZERO;
+            }
+            positions.iter().fold(vec2::ZERO, |acc, pos| acc + *pos) / r32(positions.len() as f32)
+        };
+
+        let axis_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let slider = context
+            .state
+            .get_root_or(|| ValueWidget::new_range("Mirror axis", 90.0, 0.0..=180.0, 15.0, 0));
+        let mut axis = slider.target;
+        slider.update(axis_pos, context, &mut axis);
+        context.update_focus(slider.state.hovered);
+
+        let mirror_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context.state.get_root_or(|| ButtonWidget::new("Mirror"));
+        button.update(mirror_pos, context);
+        if button.text.state.mouse_left.clicked {
+            actions.push(
+                LevelAction::MirrorLights(
+                    selected.to_vec(),
+                    anchor(),
+                    Angle::from_degrees(r32(axis)),
+                )
+                .into(),
+            );
+        }
+
+        let angle_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let slider = context
+            .state
+            .get_root_or(|| ValueWidget::new_range("Rotate", 0.0, -180.0..=180.0, 15.0, 0));
+        let mut angle = slider.target;
+        slider.update(angle_pos, context, &mut angle);
+        context.update_focus(slider.state.hovered);
+
+        let scale_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let slider = context
+            .state
+            .get_root_or(|| ValueWidget::new_range("Scale", 1.0, 0.1..=5.0, 0.1, 2));
+        let mut scale = slider.target;
+        slider.update(scale_pos, context, &mut scale);
+        context.update_focus(slider.state.hovered);
+
+        let apply_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context
+            .state
+            .get_root_or(|| ButtonWidget::new("Rotate & Scale"));
+        button.update(apply_pos, context);
+        if button.text.state.mouse_left.clicked {
+            actions.push(
+                LevelAction::RotateScaleLights(
+                    selected.to_vec(),
+                    anchor(),
+                    Angle::from_degrees(r32(angle)),
+                    r32(scale),
+                )
+                .into(),
+            );
+        }
+
+        let stretch_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let slider = context
+            .state
+            .get_root_or(|| ValueWidget::new_range("Time stretch", 2.0, 0.25..=4.0, 0.25, 2));
+        let mut factor = slider.target;
+        slider.update(stretch_pos, context, &mut factor);
+        context.update_focus(slider.state.hovered);
+
+        let apply_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context.state.get_root_or(|| ButtonWidget::new("Stretch"));
+        button.update(apply_pos, context);
+        if button.text.state.mouse_left.clicked {
+            // Stretch away from the start of the selection
+            let pivot = selected
+                .iter()
+                .filter_map(|id| self.level_editor.level.events.get(id.event))
+                .map(|event| event.time)
+                .min();
+            if let Some(pivot) = pivot {
+                actions.push(
+                    LevelAction::StretchLightsTime(selected.to_vec(), pivot, r32(factor)).into(),
+                );
+            }
+        }
+
+        let reverse_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context.state.get_root_or(|| ButtonWidget::new("Reverse"));
+        button.update(reverse_pos, context);
+        if button.text.state.mouse_left.clicked {
+            actions.push(LevelAction::ReverseLightsTime(selected.to_vec()).into());
+        }
+    }
+
+    fn layout_group(
+        &self,
+        bar: &mut Aabb2<f32>,
+        index: usize,
+        group: &LightGroup,
+        light_id: LightId,
+        context: &UiContext,
+        actions: &mut Vec<EditorStateAction>,
+    ) {
+        bar.cut_top(context.layout_size * 0.5);
+        let title_pos = bar.cut_top(self.title_size);
+        let text = context
+            .state
+            .get_root_or(|| TextWidget::new("").aligned(vec2(0.0, 0.5)));
+        text.text = group.name.clone().into();
+        text.update(title_pos, context);
+        text.options.size = self.title_size;
+
+        let ungroup_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context.state.get_root_or(|| ButtonWidget::new("Ungroup"));
+        button.update(ungroup_pos, context);
+        if button.text.state.mouse_left.clicked {
+            actions.push(LevelAction::UngroupLights(vec![light_id]).into());
+        }
+
+        let initial = group.movement.initial.transform;
+        let mut changed = group.clone();
+        let mut flush = false;
+
+        let spin = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let mut value = (group.movement.last.rotation - initial.rotation)
+            .as_degrees()
+            .as_f32();
+        let slider = context
+            .state
+            .get_root_or(|| ValueWidget::new_range("Spin", value, -720.0..=720.0, 15.0, 0));
+        if slider.update(spin, context, &mut value) {
+            changed.movement.last.rotation =
+                initial.rotation + Angle::from_degrees(r32(value.round()));
+        }
+        flush |= slider.control_state.mouse_left.just_released;
+        context.update_focus(slider.state.hovered);
+
+        let offset = group.movement.last.translation - initial.translation;
+        for (name, is_x) in [("Move X", true), ("Move Y", false)] {
+            let pos = bar.cut_top(self.value_height);
+            bar.cut_top(self.spacing);
+            let mut value = if is_x { offset.x } else { offset.y }.as_f32();
+            let slider = context
+                .state
+                .get_root_or(|| ValueWidget::new_range(name, value, -20.0..=20.0, 0.25, 2));
+            if slider.update(pos, context, &mut value) {
+                let target = &mut changed.movement.last.translation;
+                if is_x {
+                    target.x = initial.translation.x + r32(value);
+                } else {
+                    target.y = initial.translation.y + r32(value);
+                }
+            }
+            flush |= slider.control_state.mouse_left.just_released;
+            context.update_focus(slider.state.hovered);
+        }
+
+        let timing_point = self.level_editor.level.timing.get_timing(group.time);
+        let duration_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let mut duration = BeatTime::from_beats_float(
+            time_to_seconds(group.movement.initial.lerp_time) / timing_point.beat_time,
+        );
+        let slider = context.state.get_root_or(|| {
+            BeatValueWidget::new(
+                "Duration",
+                duration,
+                BeatTime::ZERO..=BeatTime::WHOLE * 10,
+                self.level_editor.beat_snap,
+            )
+        });
+        slider.scroll_by = self.level_editor.beat_snap;
+        if slider.update(duration_pos, context, &mut duration) {
+            changed.movement.initial.lerp_time = duration.as_time(timing_point.beat_time);
+        }
+        flush |= slider.control_state.mouse_left.just_released;
+        context.update_focus(slider.state.hovered);
+
+        if changed != *group {
+            actions.push(LevelAction::ChangeGroup(index, changed).into());
+        }
+        if flush {
+            actions.push(LevelAction::FlushChanges(Some(HistoryLabel::Group(index))).into());
+        }
+    }
+
+    fn layout_pattern(
+        &self,
+        bar: &mut Aabb2<f32>,
+        time: Time,
+        pattern: &PatternEvent,
+        light_id: LightId,
+        context: &UiContext,
+        actions: &mut Vec<EditorStateAction>,
+    ) {
+        bar.cut_top(context.layout_size * 0.5);
+        let title_pos = bar.cut_top(self.title_size);
+        let text = context
+            .state
+            .get_root_or(|| TextWidget::new("Pattern").aligned(vec2(0.0, 0.5)));
+        text.update(title_pos, context);
+        text.options.size = self.title_size;
+
+        let mut changed = pattern.clone();
+        let mut flush = false;
+
+        let count_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let mut value = pattern.count as f32;
+        let slider = context
+            .state
+            .get_root_or(|| ValueWidget::new_range("Count", value, 1.0..=64.0, 1.0, 0));
+        if slider.update(count_pos, context, &mut value) {
+            changed.count = value.round().max(1.0) as usize;
+        }
+        flush |= slider.control_state.mouse_left.just_released;
+        context.update_focus(slider.state.hovered);
+
+        let angle_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let mut value = pattern.angle_step.as_degrees().as_f32();
+        let slider = context
+            .state
+            .get_root_or(|| ValueWidget::new_range("Angle step", value, -180.0..=180.0, 5.0, 0));
+        if slider.update(angle_pos, context, &mut value) {
+            changed.angle_step = Angle::from_degrees(r32(value.round()));
+        }
+        flush |= slider.control_state.mouse_left.just_released;
+        context.update_focus(slider.state.hovered);
+
+        let timing_point = self.level_editor.level.timing.get_timing(time);
+        let step_pos = bar.cut_top(self.value_height);
+        bar.cut_top(self.spacing);
+        let mut step =
+            BeatTime::from_beats_float(time_to_seconds(pattern.time_step) / timing_point.beat_time);
+        let slider = context.state.get_root_or(|| {
+            BeatValueWidget::new(
+                "Time step",
+                step,
+                BeatTime::ZERO..=BeatTime::WHOLE * 4,
+                self.level_editor.beat_snap,
+            )
+        });
+        slider.scroll_by = self.level_editor.beat_snap;
+        if slider.update(step_pos, context, &mut step) {
+            changed.time_step = step.as_time(timing_point.beat_time);
+        }
+        flush |= slider.control_state.mouse_left.just_released;
+        context.update_focus(slider.state.hovered);
+
+        let mirror_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context.state.get_root_or(|| ToggleWidget::new("Mirror"));
+        button.update(mirror_pos, context);
+        if button.state.mouse_left.clicked {
+            changed.mirror = !pattern.mirror;
+            flush = true;
+        }
+        button.checked = pattern.mirror;
+
+        let expand_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context.state.get_root_or(|| ButtonWidget::new("Expand"));
+        button.update(expand_pos, context);
+        if button.text.state.mouse_left.clicked {
+            actions.push(LevelAction::ExpandPattern(light_id).into());
+            return;
+        }
+
+        if changed != *pattern {
+            actions.push(LevelAction::ChangePattern(light_id, changed).into());
+        }
+        if flush {
+            actions.push(LevelAction::FlushChanges(Some(HistoryLabel::Pattern(light_id))).into());
+        }
     }
 
     fn layout_selected_waypoints(
@@ -562,7 +1046,7 @@ impl LayoutHelper<'_> {
         let Some(event) = self.level_editor.level.events.get(light_id.event) else {
             return;
         };
-        let Event::Light(light) = &event.event else {
+        let Some(light) = event.event.as_light() else {
             return;
         };
 
@@ -679,6 +1163,25 @@ impl LayoutHelper<'_> {
                     LevelAction::ChangeHollow(light_id, selected, Change::Set(hollow)).into(),
                 );
 
+                let pulse_pos = bar.cut_top(self.value_height);
+                bar.cut_top(self.spacing);
+                let mut pulse = frame.pulse;
+                let value = context.state.get_root_or(|| {
+                    ValueWidget::new(
+                        "Pulse",
+                        pulse,
+                        ValueControl::Slider {
+                            min: R32::ZERO,
+                            max: R32::ONE,
+                        },
+                        r32(0.05),
+                        2,
+                    )
+                });
+                value.update(pulse_pos, context, &mut pulse);
+                actions
+                    .push(LevelAction::ChangePulse(light_id, selected, Change::Set(pulse)).into());
+
                 let scale = bar.cut_top(self.value_height);
                 bar.cut_top(self.spacing);
                 let mut value = frame.scale.as_f32();
@@ -699,6 +1202,32 @@ impl LayoutHelper<'_> {
                 }
                 context.update_focus(slider.state.hovered);
 
+                for (axis, name) in [(0, "Stretch X"), (1, "Stretch Y")] {
+                    let stretch = bar.cut_top(self.value_height);
+                    bar.cut_top(self.spacing);
+                    let mut value = frame.stretch[axis].as_f32();
+                    let slider = context
+                        .state
+                        .get_root_or(|| ValueWidget::new_range(name, value, 0.0..=20.0, 0.25, 2));
+                    if slider.update(stretch, context, &mut value) {
+                        let mut target = frame.stretch;
+                        target[axis] = r32(value);
+                        actions.push(
+                            LevelAction::StretchWaypoint(light_id, selected, Change::Set(target))
+                                .into(),
+                        );
+                    }
+                    if slider.control_state.mouse_left.just_released {
+                        actions.push(
+                            LevelAction::FlushChanges(Some(HistoryLabel::Stretch(
+                                light_id, selected,
+                            )))
+                            .into(),
+                        );
+                    }
+                    context.update_focus(slider.state.hovered);
+                }
+
                 let angle = bar.cut_top(self.value_height);
                 bar.cut_top(self.spacing);
                 let mut value = frame.rotation.as_degrees().as_f32();
@@ -751,16 +1280,7 @@ impl LayoutHelper<'_> {
                     });
 
                     let waypoint_interpolation = context.state.get_root_or(|| {
-                        DropdownValueWidget::new(
-                            "Interpolation",
-                            0,
-                            [
-                                ("Linear", MoveInterpolation::Linear),
-                                ("Smoothstep", MoveInterpolation::Smoothstep),
-                                ("EaseIn", MoveInterpolation::EaseIn),
-                                ("EaseOut", MoveInterpolation::EaseOut),
-                            ],
-                        )
+                        DropdownValueWidget::new("Interpolation", 0, easing_options())
                     });
 
                     waypoint_curve.update(curve, context, &mut curve_interpolation);
@@ -769,7 +1289,38 @@ impl LayoutHelper<'_> {
                             .into(),
                     );
 
-                    waypoint_interpolation.update(interpolation, context, &mut move_interpolation);
+                    // Custom bezier parameters are edited separately
+                    let is_bezier = matches!(move_interpolation, MoveInterpolation::Bezier { .. });
+                    let mut choice = if is_bezier {
+                        MoveInterpolation::default_bezier()
+                    } else {
+                        move_interpolation
+                    };
+                    waypoint_interpolation.update(interpolation, context, &mut choice);
+                    if !is_bezier || !matches!(choice, MoveInterpolation::Bezier { .. }) {
+                        move_interpolation = choice;
+                    }
+
+                    if let MoveInterpolation::Bezier { x1, y1, x2, y2 } = &mut move_interpolation {
+                        for (name, value, range) in [
+                            ("X1", x1, 0.0..=1.0),
+                            ("Y1", y1, -1.0..=2.0),
+                            ("X2", x2, 0.0..=1.0),
+                            ("Y2", y2, -1.0..=2.0),
+                        ] {
+                            let pos = bar.cut_top(self.value_height);
+                            bar.cut_top(self.spacing);
+                            let mut slider_value = value.as_f32();
+                            let slider = context.state.get_root_or(|| {
+                                ValueWidget::new_range(name, slider_value, range, 0.05, 2)
+                            });
+                            if slider.update(pos, context, &mut slider_value) {
+                                *value = r32(slider_value);
+                            }
+                            context.update_focus(slider.state.hovered);
+                        }
+                    }
+
                     actions.push(
                         LevelAction::SetWaypointInterpolation(
                             light_id,
@@ -780,6 +1331,48 @@ impl LayoutHelper<'_> {
                     );
                 }
 
+                // Danger toggle
+                if let WaypointId::Frame(i) = selected
+                    && let Some(waypoint) = light.movement.waypoints.get(i)
+                {
+                    let danger_pos = bar.cut_top(self.button_height);
+                    bar.cut_top(self.spacing);
+                    let mut danger = waypoint.danger;
+                    let dropdown = context.state.get_root_or(|| {
+                        DropdownValueWidget::new(
+                            "Danger",
+                            0,
+                            [
+                                ("Keep", None),
+                                ("Safe", Some(false)),
+                                ("Danger", Some(true)),
+                            ],
+                        )
+                    });
+                    dropdown.update(danger_pos, context, &mut danger);
+                    actions.push(LevelAction::SetWaypointDanger(light_id, selected, danger).into());
+
+                    // Shape morph
+                    let shape_pos = bar.cut_top(self.button_height);
+                    bar.cut_top(self.spacing);
+                    let mut shape = waypoint.shape;
+                    let dropdown = context.state.get_root_or(|| {
+                        DropdownValueWidget::new(
+                            "Morph",
+                            0,
+                            std::iter::once(("Keep", None)).chain(
+                                self.editor
+                                    .config
+                                    .shapes
+                                    .iter()
+                                    .map(|shape| (shape_name(shape), Some(*shape))),
+                            ),
+                        )
+                    });
+                    dropdown.update(shape_pos, context, &mut shape);
+                    actions.push(LevelAction::SetWaypointShape(light_id, selected, shape).into());
+                }
+
                 bar.cut_top(self.spacing);
             }
             std::cmp::Ordering::Less => {}
@@ -854,7 +1447,7 @@ impl LayoutHelper<'_> {
         let timing_point = timing.get_timing(event.time);
 
         match &event.event {
-            Event::Light(_) => {}
+            Event::Light(_) | Event::Pattern(_) => {}
             Event::Effect(effect) => match *effect {
                 EffectEvent::PaletteSwap(duration) => {
                     self.event_title_delete(
@@ -938,13 +1531,19 @@ impl LayoutHelper<'_> {
                 | EffectEvent::Vignette(duration, intensity)
                 | EffectEvent::ScreenCurvature(duration, intensity)
                 | EffectEvent::NoiseOffset(duration, intensity)
-                | EffectEvent::Spotlight(duration, intensity) => {
+                | EffectEvent::Spotlight(duration, intensity)
+                | EffectEvent::CameraZoom(duration, intensity)
+                | EffectEvent::Flash(duration, intensity)
+                | EffectEvent::Tint(duration, intensity, _) => {
                     let name = match effect {
                         EffectEvent::CameraShake(..) => "Camera Shake",
                         EffectEvent::Vignette(..) => "Vignette",
                         EffectEvent::ScreenCurvature(..) => "Curvature",
                         EffectEvent::NoiseOffset(..) => "Noise",
                         EffectEvent::Spotlight(..) => "Spotlight",
+                        EffectEvent::CameraZoom(..) => "Camera Zoom",
+                        EffectEvent::Flash(..) => "Flash",
+                        EffectEvent::Tint(..) => "Tint",
                         _ => "unknown",
                     };
                     self.event_title_delete(
@@ -1011,9 +1610,218 @@ impl LayoutHelper<'_> {
                                 .into(),
                         );
                     }
+
+                    if let EffectEvent::Tint(_, _, current) = effect {
+                        bar.cut_top(self.spacing);
+                        let color_pos = bar.cut_top(self.button_height);
+                        let mut color = *current;
+                        let dropdown = context.state.get_root_or(|| {
+                            DropdownValueWidget::new(
+                                "Color",
+                                0,
+                                [
+                                    ("Highlight", TintColor::Highlight),
+                                    ("Danger", TintColor::Danger),
+                                    ("Light", TintColor::Light),
+                                    ("Dark", TintColor::Dark),
+                                ],
+                            )
+                        });
+                        dropdown.update(color_pos, context, &mut color);
+                        if color != *current {
+                            actions.push(LevelAction::SetTintColor(event_i, color).into());
+                        }
+                    }
+                }
+                EffectEvent::CameraRotate(duration, _) | EffectEvent::CameraPan(duration, _) => {
+                    let name = match effect {
+                        EffectEvent::CameraRotate(..) => "Camera Rotate",
+                        _ => "Camera Pan",
+                    };
+                    self.event_title_delete(
+                        EditorEventIdx::Event(event_i),
+                        bar,
+                        name,
+                        tooltip,
+                        actions,
+                        context,
+                    );
+
+                    let duration_pos = bar.cut_top(self.value_height);
+                    let mut duration = BeatTime::from_beats_float(
+                        time_to_seconds(duration) / timing_point.beat_time,
+                    );
+                    let slider = context.state.get_root_or(|| {
+                        BeatValueWidget::new(
+                            "Duration",
+                            duration,
+                            BeatTime::ZERO..=BeatTime::WHOLE * 10,
+                            self.level_editor.beat_snap,
+                        )
+                    });
+                    slider.scroll_by = self.level_editor.beat_snap;
+                    if slider.update(duration_pos, context, &mut duration) {
+                        actions.push(
+                            LevelAction::ChangeEffectDuration(
+                                event_i,
+                                Change::Set(duration.as_time(timing_point.beat_time)),
+                            )
+                            .into(),
+                        );
+                    }
+                    if slider.control_state.mouse_left.just_released {
+                        actions.push(
+                            LevelAction::FlushChanges(Some(HistoryLabel::EventDuration(event_i)))
+                                .into(),
+                        );
+                    }
+
+                    match *effect {
+                        EffectEvent::CameraRotate(_, angle) => {
+                            let angle_pos = bar.cut_top(self.value_height);
+                            let mut value = angle.as_degrees().as_f32();
+                            let slider = context.state.get_root_or(|| {
+                                ValueWidget::new_range("Angle", value, -180.0..=180.0, 5.0, 0)
+                            });
+                            if slider.update(angle_pos, context, &mut value) {
+                                actions.push(
+                                    LevelAction::ChangeCameraRotation(
+                                        event_i,
+                                        Change::Set(Angle::from_degrees(r32(value.round()))),
+                                    )
+                                    .into(),
+                                );
+                            }
+                            if slider.control_state.mouse_left.just_released {
+                                actions.push(
+                                    LevelAction::FlushChanges(Some(HistoryLabel::EffectIntensity(
+                                        event_i,
+                                    )))
+                                    .into(),
+                                );
+                            }
+                        }
+                        EffectEvent::CameraPan(_, offset) => {
+                            for (name, is_x) in [("Pan X", true), ("Pan Y", false)] {
+                                let pos = bar.cut_top(self.value_height);
+                                bar.cut_top(self.spacing);
+                                let mut value = if is_x { offset.x } else { offset.y }.as_f32();
+                                let slider = context.state.get_root_or(|| {
+                                    ValueWidget::new_range(name, value, -10.0..=10.0, 0.25, 2)
+                                });
+                                if slider.update(pos, context, &mut value) {
+                                    let mut target = offset;
+                                    if is_x {
+                                        target.x = r32(value);
+                                    } else {
+                                        target.y = r32(value);
+                                    }
+                                    actions.push(
+                                        LevelAction::ChangeCameraPan(event_i, Change::Set(target))
+                                            .into(),
+                                    );
+                                }
+                                if slider.control_state.mouse_left.just_released {
+                                    actions.push(
+                                        LevelAction::FlushChanges(Some(
+                                            HistoryLabel::EffectIntensity(event_i),
+                                        ))
+                                        .into(),
+                                    );
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
                 }
             },
         }
+
+        if let Event::Effect(effect) = &event.event {
+            self.layout_envelope(event_i, event, effect.duration(), bar, actions, context);
+        }
+    }
+
+    fn layout_envelope(
+        &self,
+        event_i: usize,
+        event: &TimedEvent,
+        duration: Time,
+        bar: &mut Aabb2<f32>,
+        actions: &mut Vec<EditorStateAction>,
+        context: &UiContext,
+    ) {
+        bar.cut_top(context.layout_size * 0.5);
+        let toggle_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let button = context.state.get_root_or(|| ToggleWidget::new("Envelope"));
+        button.update(toggle_pos, context);
+        button.checked = event.envelope.is_some();
+        if button.state.mouse_left.clicked {
+            let envelope = match event.envelope {
+                Some(_) => None,
+                None => Some(EffectEnvelope {
+                    attack: duration / 4,
+                    release: duration / 4,
+                    easing: MoveInterpolation::Smoothstep,
+                }),
+            };
+            actions.push(LevelAction::SetEffectEnvelope(event_i, envelope).into());
+            actions.push(LevelAction::FlushChanges(Some(HistoryLabel::Envelope(event_i))).into());
+        }
+
+        let Some(envelope) = event.envelope else {
+            return;
+        };
+        let mut changed = envelope;
+        let mut flush = false;
+
+        let timing_point = self.level_editor.level.timing.get_timing(event.time);
+        for (name, is_attack) in [("Attack", true), ("Release", false)] {
+            let pos = bar.cut_top(self.value_height);
+            bar.cut_top(self.spacing);
+            let time = if is_attack {
+                envelope.attack
+            } else {
+                envelope.release
+            };
+            let mut value =
+                BeatTime::from_beats_float(time_to_seconds(time) / timing_point.beat_time);
+            let slider = context.state.get_root_or(|| {
+                BeatValueWidget::new(
+                    name,
+                    value,
+                    BeatTime::ZERO..=BeatTime::WHOLE * 10,
+                    self.level_editor.beat_snap,
+                )
+            });
+            slider.scroll_by = self.level_editor.beat_snap;
+            if slider.update(pos, context, &mut value) {
+                let time = value.as_time(timing_point.beat_time);
+                if is_attack {
+                    changed.attack = time;
+                } else {
+                    changed.release = time;
+                }
+            }
+            flush |= slider.control_state.mouse_left.just_released;
+            context.update_focus(slider.state.hovered);
+        }
+
+        let easing_pos = bar.cut_top(self.button_height);
+        bar.cut_top(self.spacing);
+        let dropdown = context
+            .state
+            .get_root_or(|| DropdownValueWidget::new("Easing", 0, easing_options()));
+        dropdown.update(easing_pos, context, &mut changed.easing);
+
+        if changed != envelope {
+            actions.push(LevelAction::SetEffectEnvelope(event_i, Some(changed)).into());
+            flush |= changed.easing != envelope.easing;
+        }
+        if flush {
+            actions.push(LevelAction::FlushChanges(Some(HistoryLabel::Envelope(event_i))).into());
+        }
     }
 
     fn layout_selected_timing(
@@ -1056,3 +1864,46 @@ impl LayoutHelper<'_> {
         actions.push(LevelAction::TimingUpdate(timing_i, r32(60.0) / bpm_value).into());
     }
 }
+
+fn shape_name(shape: &Shape) -> &'static str {
+    match shape {
+        Shape::Circle { .. } => "Circle",
+        Shape::Line { .. } => "Line",
+        Shape::Rectangle { .. } => "Rectangle",
+        Shape::Polygon { sides: 3, .. } => "Triangle",
+        Shape::Polygon { sides: 6, .. } => "Hexagon",
+        Shape::Polygon { .. } => "Polygon",
+        Shape::Arc { angle, .. } if *angle >= r32(360.0) => "Ring",
+        Shape::Arc { .. } => "Arc",
+        Shape::Convex { .. } => "Convex",
+        Shape::Ellipse { .. } => "Ellipse",
+    }
+}
+
+fn easing_options() -> Vec<(String, MoveInterpolation)> {
+    let mut options = vec![
+        ("Linear".to_owned(), MoveInterpolation::Linear),
+        ("Smoothstep".to_owned(), MoveInterpolation::Smoothstep),
+        ("EaseIn".to_owned(), MoveInterpolation::EaseIn),
+        ("EaseOut".to_owned(), MoveInterpolation::EaseOut),
+    ];
+    let families: [(&str, fn(EaseDirection) -> MoveInterpolation); 6] = [
+        ("Cubic", MoveInterpolation::Cubic),
+        ("Quart", MoveInterpolation::Quart),
+        ("Expo", MoveInterpolation::Expo),
+        ("Back", MoveInterpolation::Back),
+        ("Elastic", MoveInterpolation::Elastic),
+        ("Bounce", MoveInterpolation::Bounce),
+    ];
+    for (name, easing) in families {
+        for (dir_name, dir) in [
+            ("In", EaseDirection::In),
+            ("Out", EaseDirection::Out),
+            ("InOut", EaseDirection::InOut),
+        ] {
+            options.push((format!("{name}{dir_name}"), easing(dir)));
+        }
+    }
+    options.push(("Bezier".to_owned(), MoveInterpolation::default_bezier()));
+    options
+}
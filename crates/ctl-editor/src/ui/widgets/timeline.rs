@@ -11,7 +11,9 @@
 
 use super::*;
 
-use crate::{Change, EditorAction, HistoryLabel, LevelAction, LevelEditor, LightId};
+use crate::{
+    Change, EditorAction, HistoryLabel, LevelAction, LevelEditor, LightId, MusicAnalysis,
+};
 
 use std::collections::BTreeMap;
 
@@ -43,6 +45,8 @@ pub struct TimelineWidget {
     marks: Vec<(vec2<f32>, Color)>,
     /// Ticks with position and subdivision indicator used to select color and texture.
     ticks: Vec<(vec2<f32>, i64)>,
+    /// Positions of the detected music onsets.
+    onset_ticks: Vec<vec2<f32>>,
     selection_area: Option<Aabb2<f32>>,
 
     /// Pixels per unit.
@@ -57,6 +61,8 @@ pub struct TimelineWidget {
     cached_music: Option<Rc<ctl_local::LocalMusic>>,
     music_waveform: Vec<vec2<f32>>,
     music_offset: Time,
+    /// Frequency bands to render instead of the waveform.
+    music_bands: Option<Rc<MusicAnalysis>>,
 }
 
 struct HighlightBar {
@@ -88,6 +94,7 @@ impl TimelineWidget {
             dots: Vec::new(),
             marks: Vec::new(),
             ticks: Vec::new(),
+            onset_ticks: Vec::new(),
             selection_area: None,
 
             ppu: 2.0,
@@ -99,6 +106,7 @@ impl TimelineWidget {
             cached_music: None,
             music_waveform: Vec::new(),
             music_offset: 0,
+            music_bands: None,
         }
     }
 
@@ -153,6 +161,10 @@ impl TimelineWidget {
             );
         }
         self.music_offset = editor.group.cached.local.data.music_offset;
+        self.music_bands = editor
+            .music_analysis
+            .clone()
+            .filter(|_| editor.show_frequency_bands);
 
         // Selection mode for clicking on the icons on the timeline
         let selection_mode = if context.mods.shift {
@@ -217,7 +229,7 @@ impl TimelineWidget {
                 let single = match idx {
                     EditorEventIdx::Event(idx) => {
                         if let Some(event) = level_editor.level.events.get(idx)
-                            && let Event::Light(_) = event.event
+                            && event.event.as_light().is_some()
                         {
                             Selection::Lights(vec![LightId { event: idx }])
                         } else {
@@ -272,7 +284,7 @@ impl TimelineWidget {
                 .light_single()
                 .and_then(|id| level_editor.level.events.get(id.event))
                 .and_then(|event| {
-                    if let Event::Light(light) = &event.event {
+                    if let Some(light) = event.event.as_light() {
                         let from_time = event.time;
                         let from = render_time(&self.highlight_line, from_time).center();
                         let to_time = event.time + light.movement.duration();
@@ -299,7 +311,7 @@ impl TimelineWidget {
                     })
                     .and_then(|event| {
                         let duration = match &event.event {
-                            Event::Light(_) => return None,
+                            Event::Light(_) | Event::Pattern(_) => return None,
                             Event::Effect(effect) => effect.duration(),
                         };
                         let from_time = event.time;
@@ -591,7 +603,10 @@ impl TimelineWidget {
                 < self.visible_scroll() / 2;
 
             match &event.event {
-                Event::Light(light_event) => {
+                Event::Light(light_event)
+                | Event::Pattern(PatternEvent {
+                    light: light_event, ..
+                }) => {
                     let light_id = LightId { event: event_i };
                     // Waypoints view when single light is selected
                     // and not in area selection mode (unless selecting waypoints)
@@ -795,6 +810,12 @@ impl TimelineWidget {
                                 Shape::Circle { .. } => atlas.timeline_circle(),
                                 Shape::Line { .. } => atlas.timeline_square(),
                                 Shape::Rectangle { .. } => atlas.timeline_square(),
+                                Shape::Polygon { .. } | Shape::Convex { .. } => {
+                                    atlas.timeline_square()
+                                }
+                                Shape::Arc { .. } | Shape::Ellipse { .. } => {
+                                    atlas.timeline_circle()
+                                }
                             };
                             // TODO: somehow mask this with other stuff
                             let icon = context
@@ -990,6 +1011,11 @@ impl TimelineWidget {
                             EffectEvent::ScreenCurvature(..) => atlas.timeline_curvature(),
                             EffectEvent::NoiseOffset(..) => atlas.timeline_noise(),
                             EffectEvent::Spotlight(..) => atlas.mod_spotlight(),
+                            EffectEvent::CameraZoom(..) => atlas.plus(),
+                            EffectEvent::CameraRotate(..) => atlas.reset(),
+                            EffectEvent::CameraPan(..) => atlas.arrow_right(),
+                            EffectEvent::Flash(..) => atlas.star(),
+                            EffectEvent::Tint(..) => atlas.fill(),
                         };
 
                         regular_event(
@@ -1119,6 +1145,25 @@ impl TimelineWidget {
         // Sort by descending subdivision: whole beats first, then half, quarter, etc.
         self.ticks.sort_by_key(|(_, subdivision)| -*subdivision);
 
+        // Music onsets
+        self.onset_ticks.clear();
+        if editor.show_onsets
+            && let Some(analysis) = &editor.music_analysis
+        {
+            let (from, to) = self.visible_range();
+            let from = analysis
+                .onsets
+                .partition_point(|&onset| onset - self.music_offset < from);
+            for &onset in &analysis.onsets[from..] {
+                let time = onset - self.music_offset;
+                if time > to {
+                    break;
+                }
+                self.onset_ticks
+                    .push(render_time(&self.main_line, time).center());
+            }
+        }
+
         // Time marks
         self.marks.clear();
         if let Some(level) = &level_editor.level_state.dynamic_level {
@@ -1235,7 +1280,7 @@ impl TimelineWidget {
                             .events
                             .get(light_id.event)
                             .and_then(|event| {
-                                if let Event::Light(light) = &event.event
+                                if let Some(light) = event.event.as_light()
                                     && let Some(waypoint_time) =
                                         light.movement.get_time(waypoint_id)
                                 {
@@ -1383,10 +1428,9 @@ impl TimelineWidget {
 
 /// The time at the start of the event that exists but is not visualized.
 fn pre_event_time(event: &Event) -> Time {
-    match event {
-        Event::Light(light) => light.movement.get_fade_in(),
-        _ => 0,
-    }
+    event
+        .as_light()
+        .map_or(0, |light| light.movement.get_fade_in())
 }
 
 impl Widget for TimelineWidget {
@@ -1518,7 +1562,32 @@ impl Widget for TimelineWidget {
         }
 
         let waveform = &self.music_waveform;
-        if !waveform.is_empty() {
+        if let Some(analysis) = &self.music_bands {
+            // Frequency bands - low at the bottom, high at the top
+            let (from, to) = self.visible_range();
+            let frame_of = |time: Time| {
+                let seconds = time_to_seconds(time + self.music_offset).as_f32();
+                ((seconds / analysis.frame_time).max(0.0) as usize).min(analysis.bands.len())
+            };
+            let (from, to) = (frame_of(from), frame_of(to + 1));
+            let rows = bounds.split_rows(3);
+            let colors = [theme.highlight, theme.light, theme.danger];
+            let frame_width = analysis.frame_time * TIME_IN_FLOAT_TIME as f32 * self.scale;
+            for (frame, energy) in analysis.bands[from..to].iter().enumerate() {
+                let time = seconds_to_time(r32((from + frame) as f32 * analysis.frame_time))
+                    - self.music_offset;
+                let x = self.main_line.position.center().x
+                    + (time + self.scroll) as f32 * self.scale;
+                for (band, (row, color)) in rows.iter().rev().zip(colors).enumerate() {
+                    let quad = Aabb2 {
+                        min: vec2(x, row.min.y),
+                        max: vec2(x + frame_width, row.max.y),
+                    };
+                    let color = ctl_util::with_alpha(color, energy[band] * 0.5);
+                    geometry.merge(context.geometry.quad(quad, color));
+                }
+            }
+        } else if !waveform.is_empty() {
             // Music waveform - find the visible range
             let (from, to) = self.visible_range();
             // TODO: figure out why the rendering is not exact to the widget size
@@ -1562,6 +1631,17 @@ impl Widget for TimelineWidget {
             }
         }
 
+        // Music onsets
+        let onset_height = self.main_line.position.height();
+        for &pos in &self.onset_ticks {
+            let tick = Aabb2::point(pos).extend_symmetric(vec2(pixel * 0.5, onset_height * 0.5));
+            geometry.merge(
+                context
+                    .geometry
+                    .quad(tick, ctl_util::with_alpha(theme.highlight, 0.5)),
+            );
+        }
+
         // NOTE: mask is done manually because it weirdly affects the rendering order
         let width = pixel * 2.0;
         geometry.merge(
@@ -1728,7 +1808,7 @@ fn editor_event_time(id: EditorEventIdx, level_editor: &LevelEditor) -> Option<T
         }
         EditorEventIdx::Waypoint(light_id, waypoint_id) => {
             let event = level_editor.level.events.get(light_id.event)?;
-            if let Event::Light(light) = &event.event {
+            if let Some(light) = event.event.as_light() {
                 let time = light.movement.get_time(waypoint_id)?;
                 Some(event.time + time)
             } else {
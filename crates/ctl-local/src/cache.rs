@@ -6,6 +6,11 @@ use generational_arena::Index;
 
 type TaskRes<T> = Option<Task<anyhow::Result<T>>>;
 
+/// Number of level groups fetched per page, same as the cap of the server.
+/// A shorter page means that the list is complete.
+#[cfg(feature = "online")]
+const GROUP_LIST_PAGE_SIZE: u32 = 50;
+
 pub struct LevelCache {
     geng: Geng,
     pub inner: RefCell<LevelCacheImpl>,
@@ -17,6 +22,10 @@ pub struct LevelCacheImpl {
 
     /// List of downloadable level groups.
     pub group_list: CacheState<Vec<LevelSetInfo>>,
+    /// Query used to fetch the last page of `group_list`.
+    pub group_list_query: LevelSetsQuery,
+    /// Whether the last fetched page was the final one.
+    pub group_list_complete: bool,
 
     pub groups: Arena<Rc<CachedGroup>>,
 
@@ -34,19 +43,23 @@ pub struct CacheTasks {
 
     fs: VecDeque<Task<anyhow::Result<()>>>,
 
-    fetch_groups: TaskRes<Vec<LevelSetInfo>>,
+    fetch_groups: TaskRes<(LevelSetsQuery, Vec<LevelSetInfo>)>,
     // downloading_groups: HashSet<Id>,
     download_group: VecDeque<(Id, Task<Result<CachedGroup>>)>,
     get_recommended: TaskRes<Vec<LevelSetInfo>>,
+    /// Requests to delete uploaded groups from the server,
+    /// the groups are unlinked once the request succeeds.
+    delete_group: VecDeque<(Index, Task<anyhow::Result<()>>)>,
 
     notifications: Vec<String>,
 }
 
 enum CacheAction {
-    GroupList(Vec<LevelSetInfo>),
+    GroupList(LevelSetsQuery, Vec<LevelSetInfo>),
     Group(Box<CachedGroup>),
     #[cfg(feature = "online")]
     DownloadGroups(Vec<Id>),
+    UnlinkGroup(Index),
 }
 
 impl CacheTasks {
@@ -59,6 +72,7 @@ impl CacheTasks {
             // downloading_groups: HashSet::new(),
             download_group: VecDeque::new(),
             get_recommended: None,
+            delete_group: VecDeque::new(),
 
             notifications: Vec::new(),
         }
@@ -70,6 +84,7 @@ impl CacheTasks {
             || self.fetch_groups.is_some()
             || !self.download_group.is_empty()
             || self.get_recommended.is_some()
+            || !self.delete_group.is_empty()
     }
 
     fn poll(&mut self) -> Option<CacheAction> {
@@ -90,8 +105,8 @@ impl CacheTasks {
             match task.poll() {
                 Err(task) => self.fetch_groups = Some(task),
                 Ok(result) => {
-                    if let Ok(groups) = result {
-                        return Some(CacheAction::GroupList(groups));
+                    if let Ok((query, groups)) = result {
+                        return Some(CacheAction::GroupList(query, groups));
                     }
                 }
             }
@@ -107,6 +122,12 @@ impl CacheTasks {
                     return Some(CacheAction::DownloadGroups(group_ids));
                 }
             }
+        } else if let Some((group_index, task)) = self.delete_group.pop_front() {
+            match task.poll() {
+                Err(task) => self.delete_group.push_front((group_index, task)),
+                Ok(Err(err)) => error!("Failed to delete the group from the server: {:?}", err),
+                Ok(Ok(())) => return Some(CacheAction::UnlinkGroup(group_index)),
+            }
         } else if let Some((group_id, task)) = self.download_group.pop_front() {
             match task.poll() {
                 Err(task) => self.download_group.push_front((group_id, task)),
@@ -130,6 +151,8 @@ impl LevelCache {
             tasks: CacheTasks::new(client),
 
             group_list: CacheState::Offline,
+            group_list_query: LevelSetsQuery::default(),
+            group_list_complete: false,
 
             groups: Arena::new(),
 
@@ -361,21 +384,45 @@ impl LevelCache {
         self.inner.borrow_mut().groups.insert(group)
     }
 
+    /// Fetch a page of the downloadable level groups.
+    /// The first page replaces the current list (and cancels the pending fetch),
+    /// later pages are appended to it.
     #[cfg(feature = "online")]
-    pub fn fetch_groups(&self) {
+    pub fn fetch_groups(&self, query: LevelSetsQuery) {
+        let limit = query.limit.unwrap_or(GROUP_LIST_PAGE_SIZE);
+        let query = LevelSetsQuery {
+            limit: Some(limit.min(GROUP_LIST_PAGE_SIZE)),
+            ..query
+        };
         let mut inner = self.inner.borrow_mut();
-        if inner.tasks.fetch_groups.is_none()
+        let first_page = query.page == 0;
+        if (first_page || inner.tasks.fetch_groups.is_none())
             && let Some(client) = inner.tasks.client.clone()
         {
             let future = async move {
-                let groups = client
-                    .get_group_list(&LevelSetsQuery { recommended: false })
-                    .await?;
-                Ok(groups)
+                let groups = client.get_group_list(&query).await?;
+                Ok((query, groups))
             };
             inner.tasks.fetch_groups = Some(Task::new(&self.geng, future));
-            inner.group_list = CacheState::Loading;
+            if first_page {
+                inner.group_list = CacheState::Loading;
+            }
+        }
+    }
+
+    /// Fetch the page following the last fetched one, unless the list is complete.
+    #[cfg(feature = "online")]
+    pub fn fetch_more_groups(&self) {
+        let inner = self.inner.borrow();
+        if inner.group_list_complete || !matches!(inner.group_list, CacheState::Loaded(_)) {
+            return;
         }
+        let query = LevelSetsQuery {
+            page: inner.group_list_query.page + 1,
+            ..inner.group_list_query.clone()
+        };
+        drop(inner);
+        self.fetch_groups(query);
     }
 
     #[cfg(feature = "online")]
@@ -386,7 +433,10 @@ impl LevelCache {
         {
             let future = async move {
                 let list = client
-                    .get_group_list(&LevelSetsQuery { recommended: true })
+                    .get_group_list(&LevelSetsQuery {
+                        recommended: true,
+                        ..Default::default()
+                    })
                     .await?;
                 Ok(list)
             };
@@ -464,7 +514,17 @@ impl LevelCache {
         let mut inner = self.inner.borrow_mut();
         if let Some(action) = inner.tasks.poll() {
             match action {
-                CacheAction::GroupList(groups) => inner.group_list = CacheState::Loaded(groups),
+                CacheAction::GroupList(query, groups) => {
+                    inner.group_list_complete = query
+                        .limit
+                        .is_some_and(|limit| groups.len() < limit as usize)
+                        || groups.is_empty();
+                    match &mut inner.group_list {
+                        CacheState::Loaded(list) if query.page > 0 => list.extend(groups),
+                        _ => inner.group_list = CacheState::Loaded(groups),
+                    }
+                    inner.group_list_query = query;
+                }
                 CacheAction::Group(group) => {
                     let name = group
                         .local
@@ -485,10 +545,57 @@ impl LevelCache {
                         self.download_group(group_id);
                     }
                 }
+                CacheAction::UnlinkGroup(group_index) => {
+                    drop(inner);
+                    self.unlink_group(group_index);
+                }
             }
         }
     }
 
+    /// Delete the uploaded group from the server, keeping the local copy.
+    #[cfg(feature = "online")]
+    pub fn delete_group_online(&self, group_index: Index) {
+        let mut inner = self.inner.borrow_mut();
+        let Some(group_id) = inner
+            .groups
+            .get(group_index)
+            .map(|group| group.local.meta.id)
+            .filter(|&id| id != 0)
+        else {
+            log::error!("Group {group_index:?} is not uploaded to the server");
+            return;
+        };
+        if let Some(client) = inner.tasks.client.clone() {
+            let future = async move {
+                client.delete_group(group_id).await?;
+                Ok(())
+            };
+            let task = Task::new(&self.geng, future);
+            inner.tasks.delete_group.push_back((group_index, task));
+        }
+    }
+
+    /// Turn the group into a local one after it was removed from the user's uploads.
+    fn unlink_group(&self, group_index: Index) -> Option<Rc<CachedGroup>> {
+        let inner = self.inner.borrow();
+        let cached = inner.groups.get(group_index)?;
+        let mut new_group: LocalGroup = cached.local.clone();
+        new_group.meta.id = 0;
+        for level in &mut new_group.meta.levels {
+            level.id = 0;
+        }
+        drop(inner);
+
+        let group = self.update_group_local(group_index, new_group, None).ok()?;
+        let mut inner = self.inner.borrow_mut();
+        let cached = inner.groups.get_mut(group_index)?;
+        let mut new_cached: CachedGroup = (*group).clone();
+        new_cached.origin = None;
+        *cached = Rc::new(new_cached);
+        Some(Rc::clone(cached))
+    }
+
     /// Update levelset info based on the downloaded data.
     pub fn synchronize_meta(
         &self,
@@ -2,11 +2,15 @@ use ctl_client::Nertboard;
 use ctl_core::{
     auth::UserLogin,
     prelude::{HealthConfig, LevelModifiers},
-    score::{ScoreCategory, ScoreEntry, ScoreMeta, ServerScore},
+    replay::Replay,
+    score::{ScoreCategory, ScoreEntry, ScoreMeta, ServerScore, UserProfile},
     types::{Id, LevelInfo, MusicInfo, UserInfo},
 };
 #[cfg(feature = "online")]
-use ctl_core::{prelude::Uuid, score::SubmitScore};
+use ctl_core::{
+    prelude::Uuid,
+    score::{FetchScoresQuery, SubmitScore},
+};
 use ctl_util::Task;
 use geng::prelude::*;
 
@@ -14,6 +18,12 @@ use crate::{achievements::Achievements, fs::LocalLevelId};
 
 /// The maximum number of scores saved locally per level.
 const LOCAL_SCORES_LIMIT_PER_LEVEL: usize = 50;
+/// The maximum number of replays kept locally per level.
+/// Only the replays of the best scores are kept.
+const LOCAL_REPLAYS_LIMIT_PER_LEVEL: usize = 5;
+/// The maximum number of scores fetched from the server for a leaderboard.
+#[cfg(feature = "online")]
+const SERVER_SCORES_LIMIT: u32 = 100;
 
 #[derive(Debug)]
 pub enum LeaderboardStatus {
@@ -24,7 +34,11 @@ pub enum LeaderboardStatus {
 }
 
 struct BoardUpdate {
+    /// Category the scores were fetched for.
+    category: ScoreCategory,
     scores: Vec<ScoreEntry>,
+    /// Whether a new score was submitted.
+    submitted: bool,
 }
 
 #[derive(Clone)]
@@ -49,7 +63,10 @@ pub struct LeaderboardImpl {
     task: Option<Task<ctl_client::Result<BoardUpdate>>>,
     new_score_task: Option<Task<anyhow::Result<NewScore>>>,
     highscores_task: Option<Task<anyhow::Result<HashMap<LocalLevelId, SavedScore>>>>,
+    profile_task: Option<Task<ctl_client::Result<UserProfile>>>,
     pub status: LeaderboardStatus,
+    /// Play statistics of the logged in user.
+    pub profile: Option<UserProfile>,
     pub loaded: LoadedBoard,
 }
 
@@ -58,6 +75,9 @@ pub struct SavedScore {
     pub user: UserInfo,
     pub score: i32,
     pub meta: ScoreMeta,
+    /// Recorded input of the run, if it was kept.
+    #[serde(default)]
+    pub replay: Option<Replay>,
 }
 
 pub struct LoadedBoard {
@@ -126,7 +146,9 @@ impl LeaderboardImpl {
             task: None,
             new_score_task: None,
             highscores_task: None,
+            profile_task: None,
             status: LeaderboardStatus::None,
+            profile: None,
             loaded: LoadedBoard::new(),
         };
         leaderboard.refresh_local_highscores();
@@ -288,6 +310,20 @@ impl LeaderboardImpl {
             };
             self.log_task = Some(Task::new(&self.geng, future));
             self.user = None;
+            self.profile = None;
+        }
+    }
+
+    /// Fetch the play statistics of the logged in user.
+    pub fn fetch_profile(&mut self) {
+        #[cfg(feature = "online")]
+        if let Some(client) = &self.client
+            && let Some(user) = &self.user
+        {
+            let client = Arc::clone(client);
+            let user_id = user.id;
+            let future = async move { client.get_user_profile(user_id).await };
+            self.profile_task = Some(Task::new(&self.geng, future));
         }
     }
 
@@ -306,6 +342,7 @@ impl LeaderboardImpl {
 
                             self.loaded.player = Some(user.id);
                             self.user = Some(user);
+                            self.fetch_profile();
                         }
                         Ok(Err(err)) => {
                             if err == "Logged out" {
@@ -324,6 +361,14 @@ impl LeaderboardImpl {
             }
         }
 
+        if let Some(task) = self.profile_task.take() {
+            match task.poll() {
+                Err(task) => self.profile_task = Some(task),
+                Ok(Ok(profile)) => self.profile = Some(profile),
+                Ok(Err(err)) => log::error!("Failed to fetch the profile: {err:?}"),
+            }
+        }
+
         if let Some(task) = self.task.take() {
             match task.poll() {
                 Err(task) => self.task = Some(task),
@@ -331,7 +376,16 @@ impl LeaderboardImpl {
                     Ok(update) => {
                         log::debug!("Successfully loaded the leaderboard");
                         self.status = LeaderboardStatus::Done;
-                        self.load_scores(update.scores);
+                        if update.submitted {
+                            self.fetch_profile();
+                        }
+                        if update.category == self.loaded.category {
+                            self.load_scores(update.scores);
+                        } else {
+                            // Category has changed while the scores were loading
+                            #[cfg(feature = "online")]
+                            self.refetch();
+                        }
                     }
                     Err(err) => {
                         log::error!("Loading leaderboard failed: {err:?}");
@@ -407,7 +461,7 @@ impl LeaderboardImpl {
         self.loaded.refresh();
     }
 
-    /// Change category filter using the cached scores if available.
+    /// Change category filter and fetch the leaderboard for it.
     pub fn change_category(&mut self, category: ScoreCategory) {
         if self.loaded.category == category {
             return; // Unchanged
@@ -416,13 +470,14 @@ impl LeaderboardImpl {
         self.loaded.category = category;
         self.update_local(None);
         match self.status {
-            LeaderboardStatus::None | LeaderboardStatus::Failed => {
-                #[cfg(feature = "online")]
-                self.refetch();
+            LeaderboardStatus::Pending => {
+                // Refetched once the active task finishes
             }
-            LeaderboardStatus::Pending => {}
-            LeaderboardStatus::Done => {
+            LeaderboardStatus::None | LeaderboardStatus::Failed | LeaderboardStatus::Done => {
+                // Scores are filtered by category on the server
                 self.loaded.refresh();
+                #[cfg(feature = "online")]
+                self.refetch();
             }
         }
     }
@@ -438,11 +493,18 @@ impl LeaderboardImpl {
         if let Some(client) = &self.client {
             let board = Arc::clone(client);
             let level = self.loaded.level.id;
+            let category = self.loaded.category.clone();
             let future = async move {
                 log::debug!("Fetching scores for level {level}...");
-                board.fetch_scores(level).await.map(|scores| BoardUpdate {
-                    scores: load_server_scores(scores),
-                })
+                let query = scores_query(&category);
+                board
+                    .fetch_scores(level, &query)
+                    .await
+                    .map(|scores| BoardUpdate {
+                        category,
+                        scores: load_server_scores(scores),
+                        submitted: false,
+                    })
             };
             self.task = Some(Task::new(&self.geng, future));
             self.status = LeaderboardStatus::Pending;
@@ -458,6 +520,7 @@ impl LeaderboardImpl {
         music: MusicInfo,
         level: LevelInfo,
         meta: ScoreMeta,
+        replay: Option<Replay>,
     ) {
         log::debug!("Reloading leaderboard for level {}", level.hash);
         let score = score.map(|score| SavedScore {
@@ -473,6 +536,7 @@ impl LeaderboardImpl {
             ),
             score,
             meta: meta.clone(),
+            replay,
         });
 
         self.loaded.music = music;
@@ -488,12 +552,14 @@ impl LeaderboardImpl {
             }
             let board = Arc::clone(board);
             let level_hash = self.loaded.level.hash.clone();
+            let category = meta.category;
             let future = async move {
                 let score = score.and_then(|score| {
                     meta_to_string(&score.meta).ok().map(|meta| SubmitScore {
                         score: score.score,
                         meta,
                         level_hash,
+                        replay: score.replay,
                     })
                 });
 
@@ -503,9 +569,15 @@ impl LeaderboardImpl {
                 }
 
                 log::debug!("Fetching scores...");
-                let scores = board.fetch_scores(level.id).await?;
+                let scores = board
+                    .fetch_scores(level.id, &scores_query(&category))
+                    .await?;
                 let scores = load_server_scores(scores);
-                Ok(BoardUpdate { scores })
+                Ok(BoardUpdate {
+                    category,
+                    scores,
+                    submitted: score.is_some(),
+                })
             };
             self.task = Some(Task::new(&self.geng, future));
             self.status = LeaderboardStatus::Pending;
@@ -536,6 +608,15 @@ impl LeaderboardImpl {
                     scores.drain(..scores.len() - LOCAL_SCORES_LIMIT_PER_LEVEL);
                 }
 
+                // Only keep the replays of the best scores
+                let mut best: Vec<usize> = (0..scores.len())
+                    .filter(|&i| scores[i].replay.is_some())
+                    .collect();
+                best.sort_by_key(|&i| -scores[i].score);
+                for &i in best.iter().skip(LOCAL_REPLAYS_LIMIT_PER_LEVEL) {
+                    scores[i].replay = None;
+                }
+
                 fs.save_local_scores(&level_id, &scores)
                     .await
                     .with_context(|| "when saving local scores")?;
@@ -576,24 +657,8 @@ impl LoadedBoard {
         let mut scores = self.all_scores.clone();
 
         // Filter for the same meta
-        scores.retain(|entry| {
-            !entry.user.name.is_empty() && entry.score.category.version == self.category.version
-        });
-
-        {
-            // TODO: leave unique on server
-            // Only leave unique players
-            let mut i = 0;
-            let mut ids_seen = HashSet::new();
-            while i < scores.len() {
-                if !ids_seen.contains(&scores[i].user.id) {
-                    ids_seen.insert(scores[i].user.id);
-                    i += 1;
-                } else {
-                    scores.remove(i);
-                }
-            }
-        }
+        // The server only keeps the best score of each player in a category
+        scores.retain(|entry| !entry.user.name.is_empty() && entry.score.category == self.category);
 
         self.filtered = scores;
         self.my_position = self.local_high.as_ref().and_then(|score| {
@@ -604,6 +669,14 @@ impl LoadedBoard {
     }
 }
 
+#[cfg(feature = "online")]
+fn scores_query(category: &ScoreCategory) -> FetchScoresQuery {
+    FetchScoresQuery {
+        category: Some(category.key()),
+        limit: Some(SERVER_SCORES_LIMIT),
+    }
+}
+
 #[allow(dead_code)]
 fn meta_to_string(meta: &ScoreMeta) -> anyhow::Result<String> {
     Ok(ron::ser::to_string(meta)?)
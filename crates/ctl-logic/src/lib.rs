@@ -6,3 +6,4 @@ pub use self::model::*;
 pub use ctl_assets::{Assets, Options, Theme, ThemeColor, VolumeOptions};
 pub use ctl_context::*;
 pub use ctl_core::prelude::*;
+pub use ctl_core::simulation::{Simulation, SimulationEvent, State};
@@ -1,6 +1,6 @@
 mod event;
 
-use ctl_core::score::PauseIndicator;
+use ctl_core::replay::ReplayTick;
 
 use super::*;
 
@@ -8,41 +8,32 @@ impl Model {
     /// Initialize the level by playing the events from the negative time.
     pub fn init(&mut self, target_time: Time) {
         log::info!("Starting at the requested time {target_time}...");
-
-        // NOTE: double conversion in case of floating point errors
-        // since `play_time` is used to recalculate `play_time_ms` every frame
-        self.play_time = time_to_seconds(target_time);
-        self.play_time_ms = seconds_to_time(self.play_time) - self.music_offset;
-        self.completion_time = FloatTime::ZERO;
-
-        self.player.health.set_ratio(FloatTime::ONE);
-        self.state = State::Starting {
-            start_timer: r32(1.0),
-            music_start_time: target_time,
-        };
+        self.sim.init(target_time);
     }
 
     pub fn update(&mut self, player_target: vec2<Coord>, delta_time: FloatTime, is_paused: bool) {
         let options = self.context.get_options();
         self.context.music.set_volume(options.volume.music());
 
-        self.real_time += delta_time;
+        let Some((player_target, delta_time, is_paused)) =
+            self.playback_input(player_target, delta_time, is_paused)
+        else {
+            // Playback is paused by the viewer
+            self.real_time += delta_time;
+            return;
+        };
 
-        if is_paused && let State::Playing = self.state {
-            // Update pause indicator
-            if self
-                .pauses
-                .last_mut()
-                .is_none_or(|ind| ind.time != self.play_time_ms)
-            {
-                self.pauses.push(PauseIndicator {
-                    time: self.play_time_ms,
-                    duration: FloatTime::ZERO,
-                });
-            };
-            let indicator = self.pauses.last_mut().unwrap();
-            indicator.duration += delta_time;
+        if !self.sim.state.ended() {
+            self.recording.record(ReplayTick {
+                delta_time,
+                position: player_target,
+                paused: is_paused,
+            });
+        }
 
+        self.real_time += delta_time;
+
+        if self.sim.update_pause(delta_time, is_paused) {
             return;
         }
 
@@ -50,11 +41,19 @@ impl Model {
         let delta_time = delta_time * self.level.config.modifiers.time_scale;
         self.vfx.update(delta_time);
 
+        // Camera zoom, rotation and pan
+        self.camera.fov = camera_fov(self.vfx.camera_zoom());
+        self.camera.rotation = self.vfx.camera_rotation();
+        let pan = self.vfx.camera_pan();
+
         // Camera shake
-        if let State::Playing = self.state {
-            self.camera.center = self.camera.center * 0.5
+        if let State::Playing = self.sim.state {
+            self.camera.center = pan
+                + (self.camera.center - pan) * 0.5
                 + Angle::from_degrees(thread_rng().gen_range(0.0..=360.0)).unit_vec()
                     * self.vfx.camera_shake.as_f32();
+        } else {
+            self.camera.center = pan;
         }
 
         let delta_ms = seconds_to_time(delta_time);
@@ -70,37 +69,74 @@ impl Model {
             }
         }
 
-        // Move
-        self.player.collider.position = player_target;
+        let events = self
+            .sim
+            .update(player_target, delta_time, is_paused, Some(&mut self.vfx));
 
         self.update_timers(delta_time);
-        self.render_level();
-        self.update_player(delta_time);
-        self.update_state(delta_time, is_paused);
+        if !self.level.config.modifiers.clean_auto {
+            // Player tail
+            self.sim.player.update_tail(delta_time);
+        }
+        for event in events {
+            self.handle_event(event);
+        }
+        self.update_buttons(delta_time);
     }
 
-    fn update_timers(&mut self, delta_time: FloatTime) {
-        let options = self.context.get_options();
-
-        if let State::Starting { .. } = self.state {
-        } else {
-            self.play_time += delta_time;
-            self.play_time_ms = seconds_to_time(self.play_time) - self.music_offset;
+    /// Substitutes the player input with the recorded one when playing back a replay.
+    /// Returns `None` if the playback should not advance this tick.
+    fn playback_input(
+        &mut self,
+        player_target: vec2<Coord>,
+        delta_time: FloatTime,
+        is_paused: bool,
+    ) -> Option<(vec2<Coord>, FloatTime, bool)> {
+        let Some(playback) = &mut self.playback else {
+            return Some((player_target, delta_time, is_paused));
+        };
+        if is_paused {
+            return None;
         }
-        if let State::Playing = self.state {
-            self.completion_time += delta_time;
+
+        let was_paused = playback.last_tick().is_some_and(|tick| tick.paused);
+        let Some(tick) = playback.next_tick() else {
+            // Replay is over, give control back to the viewer
+            return Some((player_target, delta_time, is_paused));
+        };
+
+        if let State::Playing = self.sim.state
+            && tick.paused != was_paused
+        {
+            // Keep the music in sync with the recorded pauses
+            if tick.paused {
+                self.context.music.stop();
+            } else if let Some(music) = &self.level.group.music {
+                self.context
+                    .music
+                    .play_from_time(music, self.sim.play_time_ms, false);
+            }
         }
 
+        Some((tick.position, tick.delta_time, tick.paused))
+    }
+
+    fn update_timers(&mut self, delta_time: FloatTime) {
+        let options = self.context.get_options();
+
         self.switch_time += delta_time;
 
-        if let State::Lost { .. } | State::Finished = self.state {
+        if let State::Lost { .. } | State::Finished = self.sim.state {
             if self.buttons_active {
                 self.button_time += delta_time;
-            } else if !self.exit_button.base_collider.check(&self.player.collider)
+            } else if !self
+                .exit_button
+                .base_collider
+                .check(&self.sim.player.collider)
                 && !self
                     .restart_button
                     .base_collider
-                    .check(&self.player.collider)
+                    .check(&self.sim.player.collider)
             {
                 // Activate buttons once player is away from them
                 self.buttons_active = true;
@@ -108,11 +144,11 @@ impl Model {
         }
 
         let switch_t = 1.0 - self.switch_time.as_f32() / 2.0;
-        if let State::Lost { .. } = self.state {
+        if let State::Lost { .. } = self.sim.state {
             let speed = (switch_t - 0.1).max(0.5);
             self.context.music.set_speed(speed);
         }
-        if let State::Lost { .. } | State::Finished = self.state {
+        if let State::Lost { .. } | State::Finished = self.sim.state {
             let volume = switch_t * options.volume.music();
             if volume < 0.0 {
                 self.context.music.stop();
@@ -122,161 +158,42 @@ impl Model {
         }
     }
 
-    /// Update renderable level state based on the data and play_time.
-    fn render_level(&mut self) {
-        let ignore_time = match self.state {
-            State::Lost {
-                death_time_ms: death_beat_time,
-            } => Some(death_beat_time),
-            State::Finished => self.level.end_time,
-            _ => None,
-        };
-        self.level_state = LevelState::render(
-            &self.level.level.data,
-            self.play_time_ms.max(0),
-            ignore_time,
-            Some(&mut self.vfx),
-            true,
-        );
-    }
-
-    /// Update player's light state and check for missed rhythm.
-    fn update_player(&mut self, delta_time: FloatTime) {
-        let get_light = |id: Option<usize>, pass: bool| {
-            id.and_then(|id| {
-                self.level_state
-                    .lights
-                    .iter()
-                    .find(|light| light.event_id == Some(id))
-                    .filter(|light| {
-                        // NOTE
-                        // Can only miss after the waypoint, not before, hence no buffer time
-                        // (allows for unpunished early exit)
-                        //
-                        // A miss occurs when the player was inside a light that was leaving its waypoint
-                        // and has missed the coyote time
-                        //
-                        // `pass` used to extend the coyote time for `last_light`,
-                        // because otherwise we cannot detect a miss
-                        // as both will get set to `None` at the same frame
-                        // (allows for unpunished late entrance)
-                        let (time, waypoint) = light.closest_waypoint;
-                        matches!(waypoint, WaypointId::Frame(_))
-                            && time < 0
-                            && (time > -COYOTE_TIME || pass && time > -COYOTE_TIME * 2)
-                    })
-                    .map(|light| (id, light.closest_waypoint.1))
-            })
-        };
-        let last_light = get_light(self.player.closest_light, true);
-
-        // Update light state
-        self.player.reset_distance();
-        self.recent_rhythm
-            .retain(|_, time| (self.level_state.time() - *time).abs() <= COYOTE_TIME * 2);
-        for light in self.level_state.lights.iter() {
-            self.player
-                .update_light_distance(light, &self.recent_rhythm);
+    /// Update Restart and Exit buttons after the level has ended.
+    fn update_buttons(&mut self, delta_time: FloatTime) {
+        if !self.sim.state.ended() || self.button_time <= FloatTime::ONE {
+            // 1 second before the UI is active
+            return;
         }
 
-        if let State::Playing = self.state {
-            // Check missed rhythm
-            let light = get_light(self.player.closest_light, false);
-
-            if last_light.is_some()
-                && last_light != light
-                && last_light.is_none_or(|last_light| !self.recent_rhythm.contains_key(&last_light))
-            {
-                // Light has changed and no perfect rhythm
-                self.score.metrics.discrete.missed_rhythm();
-                self.handle_event(GameEvent::Rhythm { perfect: false });
-            }
+        let hovering = self
+            .restart_button
+            .base_collider
+            .check(&self.sim.player.collider);
+        if hovering && self.cursor_clicked {
+            self.restart_button.force_fade = true;
         }
-
-        if !self.level.config.modifiers.clean_auto {
-            // Player tail
-            self.player.update_tail(delta_time);
+        self.restart_button.update(hovering, delta_time);
+        self.sim
+            .player
+            .update_distance_simple(&self.restart_button.base_collider);
+        if self.restart_button.is_fading() {
+            self.restart(true);
+            return;
         }
-    }
 
-    fn update_state(&mut self, delta_time: FloatTime, is_paused: bool) {
-        match &mut self.state {
-            State::Starting {
-                start_timer,
-                music_start_time,
-            } => {
-                let music_start_time = *music_start_time;
-                *start_timer -= delta_time;
-                if !is_paused && *start_timer <= FloatTime::ZERO && self.player.is_perfect {
-                    self.start(music_start_time);
-                }
-            }
-            State::Playing => {
-                if self.level_state.is_finished
-                    || self
-                        .level
-                        .end_time
-                        .is_some_and(|end| self.play_time_ms >= end)
-                {
-                    self.finish();
-                } else if !self.level.config.modifiers.clean_auto {
-                    // Player health
-                    if let Some(distance) = self.player.danger_distance {
-                        let multiplier = (r32(1.0) - distance + r32(0.5)).min(r32(1.0));
-                        self.player.health.change(
-                            -self.level.config.health.danger_decrease_rate
-                                * multiplier
-                                * delta_time,
-                        );
-                    } else if self.player.light_distance.is_some() {
-                        self.player
-                            .health
-                            .change(self.level.config.health.restore_rate * delta_time);
-                    } else {
-                        self.player
-                            .health
-                            .change(-self.level.config.health.dark_decrease_rate * delta_time);
-                    }
-
-                    let perfect_rhythm = self.score.update(&self.player, delta_time);
-                    if perfect_rhythm {
-                        self.handle_event(GameEvent::Rhythm { perfect: true });
-                    }
-
-                    if !self.level.config.modifiers.nofail && self.player.health.is_min() {
-                        self.lose();
-                    }
-                }
-            }
-            _ if self.button_time > FloatTime::ONE => {
-                // 1 second before the UI is active
-                let hovering = self
-                    .restart_button
-                    .base_collider
-                    .check(&self.player.collider);
-                if hovering && self.cursor_clicked {
-                    self.restart_button.force_fade = true;
-                }
-                self.restart_button.update(hovering, delta_time);
-                self.player
-                    .update_distance_simple(&self.restart_button.base_collider);
-                if self.restart_button.is_fading() {
-                    self.restart(true);
-                }
-
-                // 1 second before the UI is active
-                let hovering = self.exit_button.base_collider.check(&self.player.collider);
-                if hovering && self.cursor_clicked {
-                    self.exit_button.force_fade = true;
-                }
-                self.exit_button.update(hovering, delta_time);
-                self.player
-                    .update_distance_simple(&self.exit_button.base_collider);
-                if self.exit_button.hover_time.is_max() {
-                    self.transition = Some(Transition::Exit);
-                }
-            }
-            _ => (),
+        let hovering = self
+            .exit_button
+            .base_collider
+            .check(&self.sim.player.collider);
+        if hovering && self.cursor_clicked {
+            self.exit_button.force_fade = true;
+        }
+        self.exit_button.update(hovering, delta_time);
+        self.sim
+            .player
+            .update_distance_simple(&self.exit_button.base_collider);
+        if self.exit_button.hover_time.is_max() {
+            self.transition = Some(Transition::Exit);
         }
     }
 
@@ -289,18 +206,27 @@ impl Model {
 
     pub fn restart(&mut self, transition: bool) {
         log::info!("Restarting...");
-        *self = Self::new(
-            self.context.clone(),
-            PlayLevel {
-                transition_button: transition.then_some(self.restart_button.clone()),
-                ..self.level.clone()
-            },
-            self.leaderboard.clone(),
-        );
+        let level = PlayLevel {
+            transition_button: transition.then_some(self.restart_button.clone()),
+            ..self.level.clone()
+        };
+        *self = match self.playback.take() {
+            Some(playback) => Self::new_replay(
+                self.context.clone(),
+                level,
+                self.leaderboard.clone(),
+                playback.replay,
+            ),
+            None => Self::new(self.context.clone(), level, self.leaderboard.clone()),
+        };
     }
 
     pub fn start(&mut self, music_start_time: Time) {
-        self.state = State::Playing;
+        self.sim.state = State::Playing;
+        self.play_music(music_start_time);
+    }
+
+    pub(super) fn play_music(&mut self, music_start_time: Time) {
         if let Some(music) = &self.level.group.music {
             let speed = self.level.config.modifiers.time_scale;
             log::debug!("Starting music at {music_start_time}, speed: x{speed:.2}");
@@ -318,22 +244,8 @@ impl Model {
         }
     }
 
-    pub fn finish(&mut self) {
-        self.state = State::Finished;
-        self.switch_time = FloatTime::ZERO;
-        self.get_leaderboard();
-    }
-
-    pub fn lose(&mut self) {
-        self.state = State::Lost {
-            death_time_ms: self.play_time_ms,
-        };
-        self.switch_time = FloatTime::ZERO;
-        self.get_leaderboard();
-    }
-
     pub fn get_leaderboard(&mut self) {
-        let submit_score = !self.is_practice();
+        let submit_score = !self.is_practice() && !self.is_replay();
         self.transition = Some(Transition::LoadLeaderboard { submit_score });
     }
 
@@ -341,19 +253,13 @@ impl Model {
         self.level.start_time != Time::ZERO || self.level.end_time.is_some()
     }
 
+    /// Whether a recorded replay is being played back.
+    pub fn is_replay(&self) -> bool {
+        self.playback.is_some()
+    }
+
     /// Calculates the current completion percentage (in range 0..=1).
     pub fn current_completion(&self) -> R32 {
-        let t = if let State::Finished = self.state {
-            // Finished the level, avoid floating point imprecision
-            R32::ONE
-        } else {
-            let duration = self
-                .level
-                .end_time
-                .unwrap_or_else(|| self.level.level.data.last_time())
-                - self.level.start_time;
-            self.completion_time / time_to_seconds(duration)
-        };
-        t.clamp(R32::ZERO, R32::ONE)
+        self.sim.current_completion()
     }
 }
@@ -1,6 +1,6 @@
 use super::*;
 
-use ctl_core::score::PauseIndicator;
+use ctl_core::replay::{Replay, ReplayPlayback};
 use ctl_local::{CachedGroup, Leaderboard, LocalMusic};
 use generational_arena::Index;
 
@@ -30,11 +30,6 @@ pub struct PlayLevel {
     pub transition_button: Option<HoverButton>,
 }
 
-#[derive(Debug, Clone)]
-pub enum GameEvent {
-    Rhythm { perfect: bool },
-}
-
 #[derive(Debug, Clone)]
 pub struct HoverButton {
     pub base_collider: Collider,
@@ -108,30 +103,6 @@ impl HoverButton {
     }
 }
 
-#[derive(Debug, Clone)]
-pub enum State {
-    /// Wait for the player to hover the light and some additional time.
-    Starting {
-        /// Time until we can start the game.
-        start_timer: FloatTime,
-        /// Time to start playing music from.
-        music_start_time: Time,
-    },
-    Playing,
-    Lost {
-        /// The time of death.
-        death_time_ms: Time,
-    },
-    Finished,
-}
-
-impl State {
-    /// Returns `true` when the player finished or lost the level.
-    pub fn ended(&self) -> bool {
-        matches!(self, Self::Lost { .. } | Self::Finished)
-    }
-}
-
 pub enum Transition {
     LoadLeaderboard { submit_score: bool },
     Exit,
@@ -143,8 +114,10 @@ pub struct Rhythm {
     pub position: vec2<Coord>,
     /// Time since the beat.
     pub time: Bounded<Time>,
-    /// Whether player input was perfect at the beat.
-    pub perfect: bool,
+    /// Judgement of the player input at the beat.
+    pub judgement: Judgement,
+    /// Signed offset of the hit (negative when early), `None` when missed.
+    pub offset: Option<Time>,
 }
 
 pub struct Model {
@@ -153,39 +126,28 @@ pub struct Model {
     pub leaderboard: Leaderboard,
 
     pub camera: Camera2d,
-    pub player: Player,
     /// Whether the cursor clicked last frame.
     pub cursor_clicked: bool,
     pub vfx: Vfx,
 
-    pub music_offset: Time,
     /// The level being played. Not changed, apart from music being played.
     pub level: PlayLevel,
-    /// Current state of the level.
-    pub level_state: LevelState,
-    pub state: State,
-    pub score: Score,
+    /// Gameplay of the level: player, lights and score.
+    pub sim: Simulation,
     /// Button that was used to transition into the game.
     pub transition_button: Option<HoverButton>,
 
-    /// List collected rhythm (event_id, waypoint_id)
-    /// with times of their collection.
-    pub recent_rhythm: HashMap<(usize, WaypointId), Time>,
     /// Waypoint rhythms tracking player accuracy.
     pub rhythms: Vec<Rhythm>,
-    /// Times when the game was paused.
-    pub pauses: Vec<PauseIndicator>,
+    /// Player input recorded during the run.
+    pub recording: Replay,
+    /// Replay being played back instead of the player input.
+    pub playback: Option<ReplayPlayback>,
 
     /// Real time that has passed since the level was opened.
     pub real_time: FloatTime,
     /// Time since the last state change.
     pub switch_time: FloatTime,
-    /// Time since the level was started playing.
-    pub play_time: FloatTime,
-    /// Current exact play time in milliseconds.
-    pub play_time_ms: Time,
-    /// Time since the level started playing, counting towards level completion.
-    pub completion_time: FloatTime,
     /// Restart and Exit buttons timer.
     pub button_time: FloatTime,
     /// Whether Restart and Exit buttons are activated.
@@ -201,7 +163,7 @@ impl Model {
         let start_time = level.start_time;
         let mut model = Self::empty(context, level);
         if let Some(player) = &*leaderboard.get_user() {
-            model.player.info = UserInfo {
+            model.sim.player.info = UserInfo {
                 id: player.id,
                 name: player.name.clone(),
             };
@@ -212,9 +174,36 @@ impl Model {
         model
     }
 
+    /// Create a model that plays back the recorded input of the replay
+    /// instead of listening to the player.
+    pub fn new_replay(
+        context: Context,
+        mut level: PlayLevel,
+        leaderboard: Leaderboard,
+        replay: Replay,
+    ) -> Self {
+        if replay.level_hash != level.level.meta.hash {
+            log::warn!(
+                "Playing back a replay that was recorded on a different version of the level"
+            );
+        }
+
+        level.config = replay.config.clone();
+        level.start_time = replay.start_time;
+        let mut model = Self::new(context, level, leaderboard);
+        model.sim.music_offset = replay.music_offset;
+        model.recording.music_offset = replay.music_offset;
+        model.playback = Some(ReplayPlayback::new(replay));
+
+        model.init(model.level.start_time);
+        model
+    }
+
     pub fn empty(context: Context, level: PlayLevel) -> Self {
         context.music.stop();
         let options = context.get_options();
+        let music_offset = seconds_to_time(r32(options.gameplay.music_offset * 1e-3))
+            + level.group.cached.local.data.music_offset;
         Self {
             transition: None,
             leaderboard: Leaderboard::empty(
@@ -227,42 +216,30 @@ impl Model {
             camera: Camera2d {
                 center: vec2::ZERO,
                 rotation: Angle::ZERO,
-                fov: Camera2dFov::Cover {
-                    width: 17.778,
-                    height: 10.0,
-                    scale: 1.0,
-                },
+                fov: camera_fov(1.0),
             },
-            player: Player::new(
-                Collider::new(
-                    vec2::ZERO,
-                    Shape::Circle {
-                        radius: level.config.player.radius,
-                    },
-                ),
-                level.config.health.max,
-            ),
             cursor_clicked: false,
             vfx: Vfx::new(),
 
-            music_offset: seconds_to_time(r32(options.gameplay.music_offset * 1e-3))
-                + level.group.cached.local.data.music_offset,
-            level_state: LevelState::default(),
-            state: State::Starting {
-                start_timer: FloatTime::ZERO, // reset during init
-                music_start_time: Time::ZERO,
-            },
-            score: Score::new(level.config.modifiers.multiplier()),
+            sim: Simulation::new(
+                level.level.data.clone(),
+                level.config.clone(),
+                music_offset,
+                level.start_time,
+                level.end_time,
+            ),
 
-            recent_rhythm: HashMap::new(),
             rhythms: Vec::new(),
-            pauses: Vec::new(),
+            recording: Replay::new(
+                level.level.meta.hash.clone(),
+                level.config.clone(),
+                music_offset,
+                level.start_time,
+            ),
+            playback: None,
 
             real_time: FloatTime::ZERO,
             switch_time: FloatTime::ZERO,
-            play_time: FloatTime::ZERO,
-            play_time_ms: Time::ZERO,
-            completion_time: FloatTime::ZERO,
             button_time: FloatTime::ZERO,
             buttons_active: false,
 
@@ -280,3 +257,12 @@ impl Model {
         }
     }
 }
+
+/// Field of view of the gameplay camera zoomed in by the given factor.
+pub fn camera_fov(zoom: f32) -> Camera2dFov {
+    Camera2dFov::Cover {
+        width: 17.778,
+        height: 10.0,
+        scale: 1.0 / zoom,
+    }
+}
@@ -0,0 +1,8 @@
+-- Level sets curated by admins to be recommended to the players.
+CREATE TABLE level_sets_recommended
+(
+    level_set_id INTEGER NOT NULL PRIMARY KEY,
+    ord INTEGER NOT NULL, -- Position in the recommended list
+    added_at DATE NOT NULL,
+    FOREIGN KEY(level_set_id) REFERENCES level_sets(level_set_id)
+);
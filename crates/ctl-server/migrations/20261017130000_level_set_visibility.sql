@@ -0,0 +1,7 @@
+-- Unlisted level sets are hidden from the list, but can still be accessed directly.
+ALTER TABLE level_sets
+ADD COLUMN listed BIT NOT NULL DEFAULT TRUE;
+
+-- Deleted level sets are kept in the database to keep their scores archived.
+ALTER TABLE level_sets
+ADD COLUMN deleted_at DATE;
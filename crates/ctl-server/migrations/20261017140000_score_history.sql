@@ -0,0 +1,3 @@
+-- Every score submission is stored to keep the history of the plays.
+CREATE INDEX scores_level_user ON scores (level_id, user_id);
+CREATE INDEX scores_user ON scores (user_id, submitted_at);
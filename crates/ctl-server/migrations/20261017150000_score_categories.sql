@@ -0,0 +1,4 @@
+-- Scores are ranked separately for each category (modifiers and health config).
+-- The key is filled in from the score meta on startup for existing scores.
+ALTER TABLE scores ADD COLUMN category TEXT;
+CREATE INDEX scores_level_category ON scores (level_id, category);
@@ -0,0 +1,19 @@
+-- Difficulty of the level, computed from the level data when it is uploaded.
+-- Levels uploaded earlier get it calculated on their first score submission.
+ALTER TABLE levels ADD COLUMN difficulty REAL;
+-- Performance points awarded for the score.
+ALTER TABLE scores ADD COLUMN performance REAL;
+-- Scores submitted before the rankings have no verified replay
+-- to calculate the performance from, so they are not ranked.
+UPDATE scores SET performance = 0;
+
+-- Global player ranking, recalculated on every score submission.
+CREATE TABLE player_ratings
+(
+    user_id INTEGER NOT NULL PRIMARY KEY,
+    rating REAL NOT NULL,
+    ranked_levels INTEGER NOT NULL,
+    updated_at DATE NOT NULL,
+    FOREIGN KEY(user_id) REFERENCES users(user_id)
+);
+CREATE INDEX player_ratings_rating ON player_ratings (rating DESC);
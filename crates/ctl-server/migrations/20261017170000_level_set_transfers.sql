@@ -0,0 +1,9 @@
+-- Ownership transfers offered by the owner, waiting for the new owner to accept them.
+CREATE TABLE level_set_transfers
+(
+    level_set_id INTEGER NOT NULL PRIMARY KEY,
+    new_owner_id INTEGER NOT NULL,
+    offered_at DATE NOT NULL,
+    FOREIGN KEY(level_set_id) REFERENCES level_sets(level_set_id),
+    FOREIGN KEY(new_owner_id) REFERENCES users(user_id)
+);
@@ -32,8 +32,14 @@ pub enum RequestError {
     InvalidLevel,
     #[error("Level hash mismatch")]
     LevelHashMismatch,
-    // #[error("User {0} not found")]
-    // NoSuchUser(Id),
+    #[error("Score submission is missing a replay")]
+    MissingReplay,
+    #[error("Replay is invalid")]
+    InvalidReplay,
+    #[error("Score does not match the replay")]
+    ScoreMismatch,
+    #[error("User {0} not found")]
+    NoSuchUser(Id),
     #[error("Artist {0} not found")]
     NoSuchMusician(Id),
     #[error("Level set {0} not found")]
@@ -71,8 +77,11 @@ impl RequestError {
             RequestError::InvalidName(_) => StatusCode::BAD_REQUEST,
             RequestError::InvalidLevel => StatusCode::BAD_REQUEST,
             RequestError::LevelHashMismatch => StatusCode::BAD_REQUEST,
+            RequestError::MissingReplay => StatusCode::BAD_REQUEST,
+            RequestError::InvalidReplay => StatusCode::BAD_REQUEST,
+            RequestError::ScoreMismatch => StatusCode::BAD_REQUEST,
             RequestError::FileNotFound(_) => StatusCode::NOT_FOUND,
-            // RequestError::NoSuchUser(_) => StatusCode::NOT_FOUND,
+            RequestError::NoSuchUser(_) => StatusCode::NOT_FOUND,
             RequestError::NoSuchMusician(_) => StatusCode::NOT_FOUND,
             RequestError::NoSuchMusic(_) => StatusCode::NOT_FOUND,
             RequestError::NoSuchLevelSet(_) => StatusCode::NOT_FOUND,
@@ -1,6 +1,46 @@
 use super::types::DatabasePool;
 
+use sqlx::Row;
+
 pub async fn init_database(database: &DatabasePool) -> color_eyre::Result<()> {
     sqlx::migrate!().run(database).await?;
+    fill_score_categories(database).await?;
+    Ok(())
+}
+
+/// Compute the category key of the scores submitted before categories were stored.
+async fn fill_score_categories(database: &DatabasePool) -> color_eyre::Result<()> {
+    let rows = sqlx::query("SELECT rowid, extra_info FROM scores WHERE category IS NULL")
+        .fetch_all(database)
+        .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    tracing::info!("Filling in the category of {} scores", rows.len());
+    let mut trans = database.begin().await?;
+    for row in rows {
+        let rowid: i64 = row.try_get("rowid")?;
+        let meta: Option<String> = row.try_get("extra_info")?;
+        let category = match meta
+            .as_deref()
+            .map(ron::from_str::<ctl_core::score::ScoreMeta>)
+        {
+            Some(Ok(meta)) => meta.category,
+            _ => {
+                tracing::warn!(
+                    "Failed to parse the meta of score {rowid}, assuming default category"
+                );
+                ctl_core::score::ScoreCategory::default()
+            }
+        };
+        sqlx::query("UPDATE scores SET category = ? WHERE rowid = ?")
+            .bind(category.key())
+            .bind(rowid)
+            .execute(&mut *trans)
+            .await?;
+    }
+    trans.commit().await?;
+
     Ok(())
 }
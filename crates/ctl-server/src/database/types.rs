@@ -32,6 +32,8 @@ pub struct ScoreRow {
     pub score: Score,
     pub extra_info: Option<String>,
     pub submitted_at: OffsetDateTime,
+    pub category: Option<String>,
+    pub performance: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -89,6 +91,9 @@ pub struct LevelSetRow {
     pub featured: bool,
     pub hash: String,
     pub created_at: OffsetDateTime,
+    /// Whether the level set is shown in the list.
+    pub listed: bool,
+    pub deleted_at: Option<OffsetDateTime>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -100,6 +105,7 @@ pub struct LevelRow {
     pub ord: i32,
     pub hash: String,
     pub created_at: OffsetDateTime,
+    pub difficulty: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
@@ -2,15 +2,34 @@ use super::*;
 
 use crate::database::types::LevelRow;
 
+use axum::extract::DefaultBodyLimit;
 use ctl_core::{
-    score::{ServerScore, SubmitScore},
-    types::MapperInfo,
+    model::{Level, LevelConfig, Score},
+    prelude::{R32, r32},
+    rating,
+    replay::{MAX_USER_MUSIC_OFFSET, Replay},
+    score::{FetchScoresQuery, ScoreCategory, ScoreMeta, ServerScore, SubmitScore},
+    simulation::{FIXED_FPS, TICK_DELTA_TIME},
+    types::{LevelSet, MapperInfo, Time},
 };
 
+const SCORE_SIZE_LIMIT: usize = 16 * 1024 * 1024; // 16 MB
+/// Relative difference allowed between the submitted and the verified score,
+/// accounts for floating point differences between platforms.
+const SCORE_TOLERANCE: f32 = 0.005;
+/// Maximum number of scores returned for a single leaderboard.
+const SCORES_LIMIT: u32 = 1000;
+/// Maximum number of ticks in a replay (an hour of real time).
+const REPLAY_TICKS_LIMIT: usize = 60 * 60 * FIXED_FPS as usize;
+/// Maximum total duration (in seconds) of the pauses in a replay.
+const REPLAY_PAUSE_LIMIT: f32 = 10.0 * 60.0;
+
 pub fn route(router: Router) -> Router {
     router.route("/level/:level_id", get(level_get)).route(
         "/level/:level_id/scores",
-        get(fetch_scores).post(submit_score),
+        get(fetch_scores)
+            .post(submit_score)
+            .layer(DefaultBodyLimit::max(SCORE_SIZE_LIMIT)),
     )
 }
 
@@ -50,6 +69,7 @@ WHERE level_id = ?
 async fn fetch_scores(
     State(app): State<Arc<App>>,
     Path(level_id): Path<Id>,
+    Query(query): Query<FetchScoresQuery>,
 ) -> Result<Json<Vec<ServerScore>>> {
     // Check that the level exists
     let level: Option<LevelRow> = sqlx::query_as("SELECT * FROM levels WHERE level_id = ?")
@@ -68,16 +88,29 @@ async fn fetch_scores(
         score: ScoreRow,
     }
 
-    // Fetch scores
+    let limit = query.limit.unwrap_or(SCORES_LIMIT).clamp(1, SCORES_LIMIT);
+
+    // Fetch the best score of each user in each category
     let scores: Vec<Row> = sqlx::query_as(
         "
 SELECT *
-FROM scores
+FROM (
+    SELECT scores.*, ROW_NUMBER() OVER (
+        PARTITION BY scores.user_id, scores.category
+        ORDER BY scores.score DESC, scores.submitted_at ASC
+    ) AS user_rank
+    FROM scores
+    WHERE level_id = ?1 AND (?2 IS NULL OR category = ?2)
+) AS scores
 JOIN users ON scores.user_id = users.user_id
-WHERE level_id = ?
+WHERE user_rank = 1
+ORDER BY scores.score DESC, scores.submitted_at ASC
+LIMIT ?3
         ",
     )
     .bind(level_id)
+    .bind(query.category)
+    .bind(limit)
     .fetch_all(&app.database)
     .await?;
 
@@ -104,13 +137,19 @@ async fn submit_score(
     Json(score): Json<SubmitScore>,
 ) -> Result<()> {
     let user = check_user(&session).await?;
-    let mut trans = app.database.begin().await?;
 
-    // Check that the level exists
-    let level: Option<LevelRow> = sqlx::query_as("SELECT * FROM levels WHERE level_id = ?")
-        .bind(level_id)
-        .fetch_optional(&mut *trans)
-        .await?;
+    // Check that the level exists and can still be played
+    let level: Option<LevelRow> = sqlx::query_as(
+        "
+SELECT levels.*
+FROM levels
+JOIN level_sets ON levels.level_set_id = level_sets.level_set_id
+WHERE levels.level_id = ? AND levels.enabled = TRUE AND level_sets.deleted_at IS NULL
+        ",
+    )
+    .bind(level_id)
+    .fetch_optional(&app.database)
+    .await?;
     let Some(level) = level else {
         return Err(RequestError::NoSuchLevel(level_id));
     };
@@ -119,40 +158,256 @@ async fn submit_score(
         return Err(RequestError::LevelHashMismatch);
     }
 
-    // Insert new score
-    let current: Option<ScoreRow> =
-        sqlx::query_as("SELECT * FROM scores WHERE level_id = ? AND user_id = ?")
-            .bind(level_id)
-            .bind(user.user_id)
-            .fetch_optional(&mut *trans)
-            .await?;
+    let Some(replay) = score.replay else {
+        return Err(RequestError::MissingReplay);
+    };
+    if replay.level_hash != level.hash {
+        return Err(RequestError::InvalidReplay);
+    }
+    let meta: ScoreMeta = ron::from_str(&score.meta).map_err(|_| RequestError::InvalidRequest)?;
+
+    // Reading the level file and simulating the replay would block the executor
+    let (difficulty, meta) = {
+        let app = app.clone();
+        let level = level.clone();
+        let submitted = score.score;
+        tokio::task::spawn_blocking(move || {
+            let (level_data, music_offset) = load_level(&app.config, &level)?;
+            // Levels uploaded before the difficulty was stored on upload
+            let difficulty = level
+                .difficulty
+                .map_or_else(|| rating::level_difficulty(&level_data), r32);
+            let meta = verify_score(level_data, music_offset, replay, submitted, meta)?;
+            Ok::<_, RequestError>((difficulty, meta))
+        })
+        .await
+        .map_err(|_| RequestError::Internal)??
+    };
+    let extra_info = ron::to_string(&meta).map_err(|_| RequestError::Internal)?;
 
-    if let Some(current) = current {
-        if score.score > current.score {
-            sqlx::query(
-                "UPDATE scores SET score = ?, extra_info = ? WHERE level_id = ? AND user_id = ?",
-            )
-            .bind(score.score)
-            .bind(&score.meta)
+    let mut trans = app.database.begin().await?;
+    if level.difficulty.is_none() {
+        sqlx::query("UPDATE levels SET difficulty = ? WHERE level_id = ?")
+            .bind(difficulty.as_f32())
             .bind(level_id)
-            .bind(user.user_id)
             .execute(&mut *trans)
             .await?;
-        }
-    } else {
-        sqlx::query(
-            "INSERT INTO scores (level_id, level_hash, user_id, score, extra_info, submitted_at) VALUES (?, ?, ?, ?, ?, ?)",
-        )
-        .bind(level_id)
-        .bind(&level.hash)
-        .bind(user.user_id)
-        .bind(score.score)
-        .bind(&score.meta)
-        .bind(OffsetDateTime::now_utc())
-        .execute(&mut *trans)
-        .await?;
     }
 
+    // Insert new score, keeping the history of all submissions
+    let performance = rating::performance(difficulty, &meta);
+    sqlx::query(
+        "INSERT INTO scores (level_id, level_hash, user_id, score, extra_info, submitted_at, category, performance) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(level_id)
+    .bind(&level.hash)
+    .bind(user.user_id)
+    .bind(meta.score())
+    .bind(extra_info)
+    .bind(OffsetDateTime::now_utc())
+    .bind(meta.category.key())
+    .bind(performance.as_f32())
+    .execute(&mut *trans)
+    .await?;
+
+    ranking::update_player_rating(user.user_id, &mut trans).await?;
+
     trans.commit().await?;
     Ok(())
 }
+
+/// Load the level data from the file of its level set.
+/// Returns the level together with the music offset of the level set.
+pub(super) fn load_level(config: &AppConfig, level: &LevelRow) -> Result<(Level, Time)> {
+    let path = config
+        .level_sets_path
+        .join("levels")
+        .join(level.level_set_id.to_string());
+    let bytes = std::fs::read(&path).map_err(|_| {
+        error!(
+            "Level set {} file not found at {path:?}",
+            level.level_set_id
+        );
+        RequestError::FileNotFound(format!("{path:?}"))
+    })?;
+    let level_set: LevelSet<Level> = cbor4ii::serde::from_slice(&bytes).map_err(|err| {
+        error!("Failed to parse level set {}: {err:?}", level.level_set_id);
+        RequestError::Internal
+    })?;
+
+    let music_offset = level_set.music_offset;
+    let data = usize::try_from(level.ord)
+        .ok()
+        .and_then(|ord| level_set.levels.into_iter().nth(ord))
+        .filter(|data| data.calculate_hash() == level.hash);
+    let data = data.ok_or_else(|| {
+        error!(
+            "Level {} does not match the data in the level set file",
+            level.level_id
+        );
+        RequestError::Internal
+    })?;
+    Ok((data, music_offset))
+}
+
+/// Re-simulate the replay on the level and check that it produces the submitted score.
+/// Returns the meta with the score and completion replaced by the simulated ones.
+fn verify_score(
+    level: Level,
+    music_offset: Time,
+    replay: Replay,
+    score: i32,
+    mut meta: ScoreMeta,
+) -> Result<ScoreMeta> {
+    let (simulated, completion) = simulate_replay(level, music_offset, replay, &meta.category)?;
+
+    let combined = simulated.calculated.combined;
+    let tolerance = (combined.abs() as f32 * SCORE_TOLERANCE).ceil() as i32;
+    if (combined - score).abs() > tolerance {
+        warn!("Submitted score {score} does not match the replay: {combined}");
+        return Err(RequestError::ScoreMismatch);
+    }
+
+    meta.score = simulated;
+    meta.completion = completion;
+    Ok(meta)
+}
+
+/// Play back the replay with the config of the score category,
+/// the config recorded in the replay is only checked to match it.
+/// Returns the simulated score and completion of the level.
+fn simulate_replay(
+    level: Level,
+    music_offset: Time,
+    replay: Replay,
+    category: &ScoreCategory,
+) -> Result<(Score, R32)> {
+    // The judgement windows are not a part of the category,
+    // so only runs with the default ones are accepted
+    let config = LevelConfig {
+        health: category.health.clone(),
+        modifiers: category.mods.clone(),
+        ..LevelConfig::default()
+    };
+    if replay.start_time != Time::ZERO
+        || replay.config != config
+        || (replay.music_offset - music_offset).abs() > MAX_USER_MUSIC_OFFSET
+    {
+        return Err(RequestError::InvalidReplay);
+    }
+
+    // Ticks of any other duration would allow to play the level in slow motion
+    let tick_time = r32(TICK_DELTA_TIME);
+    let paused_ticks = replay.ticks.iter().filter(|tick| tick.paused).count();
+    if replay.ticks.len() > REPLAY_TICKS_LIMIT
+        || paused_ticks as f32 * TICK_DELTA_TIME > REPLAY_PAUSE_LIMIT
+        || replay.ticks.iter().any(|tick| tick.delta_time != tick_time)
+    {
+        return Err(RequestError::InvalidReplay);
+    }
+
+    let replay = Replay { config, ..replay };
+    let sim = replay.simulate(std::rc::Rc::new(level));
+    if !sim.state.ended() {
+        // Replay ended before the player finished or lost the level
+        return Err(RequestError::InvalidReplay);
+    }
+    let completion = sim.current_completion();
+    Ok((sim.score, completion))
+}
+
+/// Record a replay of the auto player on the level.
+#[cfg(test)]
+fn record_replay(level: &Level, config: LevelConfig, music_offset: Time) -> (Replay, i32) {
+    use ctl_core::simulation::{AutoInput, InputSource, Simulation};
+
+    let level = std::rc::Rc::new(level.clone());
+    let mut replay = Replay::new(
+        level.calculate_hash(),
+        config.clone(),
+        music_offset,
+        Time::ZERO,
+    );
+    let mut sim = Simulation::new(level, config, music_offset, Time::ZERO, None);
+    sim.init(Time::ZERO);
+    let mut auto = AutoInput::default();
+    sim.run(&mut |sim: &Simulation| {
+        let tick = auto.next_input(sim)?;
+        replay.record(tick);
+        Some(tick)
+    });
+
+    let replay: Replay = bincode::deserialize(&bincode::serialize(&replay).unwrap()).unwrap();
+    (replay, sim.score.calculated.combined)
+}
+
+#[test]
+fn test_replay_verified() {
+    let level = ctl_core::simulation::test_level();
+    let category = ScoreCategory::default();
+    let (replay, score) = record_replay(&level, LevelConfig::default(), 50);
+
+    let (simulated, completion) = simulate_replay(level, 0, replay, &category).unwrap();
+    assert_eq!(simulated.calculated.combined, score);
+    assert_eq!(completion, R32::ONE);
+}
+
+#[test]
+fn test_replay_tampered() {
+    let level = ctl_core::simulation::test_level();
+    let category = ScoreCategory::default();
+
+    let mut config = LevelConfig::default();
+    config.player.radius = r32(1.0);
+    let (replay, _) = record_replay(&level, config, 0);
+    assert!(matches!(
+        simulate_replay(level.clone(), 0, replay, &category),
+        Err(RequestError::InvalidReplay)
+    ));
+
+    let mut config = LevelConfig::default();
+    config.judgement.late *= 2;
+    let (replay, _) = record_replay(&level, config, 0);
+    assert!(matches!(
+        simulate_replay(level.clone(), 0, replay, &category),
+        Err(RequestError::InvalidReplay)
+    ));
+
+    let (replay, _) = record_replay(&level, LevelConfig::default(), 500);
+    assert!(matches!(
+        simulate_replay(level, 0, replay, &category),
+        Err(RequestError::InvalidReplay)
+    ));
+}
+
+#[test]
+fn test_replay_slow_motion() {
+    let level = ctl_core::simulation::test_level();
+    let category = ScoreCategory::default();
+
+    let (mut replay, _) = record_replay(&level, LevelConfig::default(), 0);
+    for tick in &mut replay.ticks {
+        tick.delta_time = r32(TICK_DELTA_TIME * 0.5);
+    }
+    assert!(matches!(
+        simulate_replay(level.clone(), 0, replay, &category),
+        Err(RequestError::InvalidReplay)
+    ));
+
+    let (mut replay, _) = record_replay(&level, LevelConfig::default(), 0);
+    let pause = replay.ticks[0];
+    replay.ticks.splice(
+        0..0,
+        std::iter::repeat_n(
+            ctl_core::replay::ReplayTick {
+                paused: true,
+                ..pause
+            },
+            REPLAY_PAUSE_LIMIT as usize * FIXED_FPS as usize + 1,
+        ),
+    );
+    assert!(matches!(
+        simulate_replay(level, 0, replay, &category),
+        Err(RequestError::InvalidReplay)
+    ));
+}
@@ -1,106 +1,300 @@
 use super::*;
 
-use crate::database::types::LevelRow;
+use crate::database::types::{LevelRow, MusicRow};
 
 use axum::{body::Bytes, extract::DefaultBodyLimit};
-use ctl_core::types::{LevelSetFull, LevelSetsQuery, MapperInfo};
+use ctl_core::{
+    rating,
+    types::{LevelSetFull, LevelSetUpdate, LevelSetsQuery, LevelSetsSort, MapperInfo},
+};
 
 const LEVEL_SET_SIZE_LIMIT: usize = 1024 * 1024; // 1 MB
 const LEVEL_SETS_PER_USER: usize = 5;
 const LEVEL_SETS_PER_USER_PER_SONG: usize = 1;
+const LEVEL_SETS_PAGE_LIMIT: u32 = 50;
 /// seconds
 const LEVEL_MIN_DURATION: f32 = 30.0;
 
 pub fn route(router: Router) -> Router {
     router
         .route("/level_sets", get(level_set_list))
-        .route("/level_set/:level_set_id", get(level_set_get))
+        .route(
+            "/level_sets/recommended",
+            post(recommended_add).delete(recommended_remove),
+        )
+        .route(
+            "/level_set/:level_set_id",
+            get(level_set_get)
+                .patch(level_set_update)
+                .delete(level_set_delete),
+        )
+        .route("/level_set/:level_set_id/owner", post(level_set_transfer))
+        .route(
+            "/level_set/:level_set_id/owner/accept",
+            post(level_set_transfer_accept),
+        )
         .route("/level_set/:level_set_id/download", get(download))
         .route("/level_set/create", post(level_set_create))
         .layer(DefaultBodyLimit::max(LEVEL_SET_SIZE_LIMIT))
 }
 
-// TODO: filter, sort, limit, pages
 async fn level_set_list(
+    session: AuthSession,
     State(app): State<Arc<App>>,
     Query(query): Query<LevelSetsQuery>,
 ) -> Result<Json<Vec<LevelSetInfo>>> {
-    // TODO: lazy?
-    let music = super::music::music_list(
-        State(app.clone()),
-        Query(super::music::GetMusicQuery { level_set_id: None }),
-    )
-    .await?
-    .0;
+    let limit = query
+        .limit
+        .unwrap_or(LEVEL_SETS_PAGE_LIMIT)
+        .clamp(1, LEVEL_SETS_PAGE_LIMIT);
+    let offset = i64::from(query.page) * i64::from(limit);
+    let search = query
+        .search
+        .as_deref()
+        .map(str::trim)
+        .filter(|search| !search.is_empty())
+        .map(|search| format!("%{}%", escape_like(search)));
+    let order = match query.sort {
+        _ if query.recommended => "recommended.ord ASC",
+        LevelSetsSort::Newest => "level_sets.created_at DESC",
+        LevelSetsSort::Popularity => {
+            "(SELECT COUNT(*) FROM scores
+              JOIN levels ON scores.level_id = levels.level_id
+              WHERE levels.level_set_id = level_sets.level_set_id) DESC,
+             level_sets.created_at DESC"
+        }
+        LevelSetsSort::Featured => "level_sets.featured DESC, level_sets.created_at DESC",
+    };
 
     #[derive(sqlx::FromRow)]
-    struct LevelGroupRow {
-        #[sqlx(flatten)]
-        group: LevelSetRow,
+    struct LevelSetOwnerRow {
         #[sqlx(flatten)]
-        level: LevelRow,
+        level_set: LevelSetRow,
+        username: String,
     }
 
-    let query = if query.recommended {
-        // TODO
-        // "SELECT * FROM levels JOIN (
-        //     SELECT * FROM groups_recommended JOIN groups ON groups_recommended.group_id = groups.group_id
-        // ) AS groups ON levels.group_id = groups.group_id"
-        return Err(RequestError::Internal);
-    } else {
-        "SELECT * FROM levels JOIN level_sets ON levels.level_set_id = level_sets.level_set_id"
-    };
-
-    let levels: Vec<LevelGroupRow> = sqlx::query_as(query).fetch_all(&app.database).await?;
-
-    let authors: Vec<LevelAuthorRow> = sqlx::query_as("SELECT * FROM level_authors")
+    let sql = format!(
+        r#"
+SELECT level_sets.*, users.username
+FROM level_sets
+JOIN musics ON level_sets.music_id = musics.music_id
+JOIN users ON level_sets.owner_id = users.user_id
+LEFT JOIN level_sets_recommended AS recommended
+    ON level_sets.level_set_id = recommended.level_set_id
+WHERE level_sets.deleted_at IS NULL
+AND (level_sets.listed = TRUE OR level_sets.owner_id = ?6)
+AND (?1 IS NULL OR level_sets.owner_id = ?1)
+AND (?5 = FALSE OR recommended.level_set_id IS NOT NULL)
+AND EXISTS (
+    SELECT 1 FROM levels
+    WHERE levels.level_set_id = level_sets.level_set_id AND levels.enabled = TRUE
+)
+AND (?2 IS NULL
+    OR musics.name LIKE ?2 ESCAPE '\'
+    OR musics.romanized_name LIKE ?2 ESCAPE '\'
+    OR users.username LIKE ?2 ESCAPE '\'
+    OR EXISTS (
+        SELECT 1 FROM music_authors
+        WHERE music_authors.music_id = musics.music_id
+        AND (music_authors.name LIKE ?2 ESCAPE '\' OR music_authors.romanized_name LIKE ?2 ESCAPE '\')
+    )
+    OR EXISTS (
+        SELECT 1 FROM level_authors
+        JOIN levels ON level_authors.level_id = levels.level_id
+        WHERE levels.level_set_id = level_sets.level_set_id
+        AND (level_authors.name LIKE ?2 ESCAPE '\' OR level_authors.romanized_name LIKE ?2 ESCAPE '\')
+    )
+)
+ORDER BY {order}
+LIMIT ?3 OFFSET ?4
+        "#
+    );
+    let level_sets: Vec<LevelSetOwnerRow> = sqlx::query_as(&sql)
+        .bind(query.owner)
+        .bind(search)
+        .bind(limit)
+        .bind(offset)
+        .bind(query.recommended)
+        .bind(session.user.as_ref().map(|user| user.user_id))
         .fetch_all(&app.database)
         .await?;
+    if level_sets.is_empty() {
+        return Ok(Json(Vec::new()));
+    }
 
-    let mut groups = Vec::<LevelSetInfo>::new();
-    for level_row in levels {
-        let authors: Vec<MapperInfo> = authors
-            .iter()
-            .filter(|author| author.level_id == level_row.level.level_id)
-            .cloned()
-            .map(MapperInfo::from)
-            .collect();
+    // Fetch the details of the level sets on the page
+    let ids = vec!["?"; level_sets.len()].join(", ");
+    let music_ids = level_sets.iter().map(|row| row.level_set.music_id);
+    let level_set_ids = level_sets.iter().map(|row| row.level_set.level_set_id);
 
-        let owner: UserRow = sqlx::query_as("SELECT * FROM users WHERE user_id = ?")
-            .bind(level_row.group.owner_id)
-            .fetch_one(&app.database)
-            .await?;
-        let owner = UserInfo::from(owner);
+    let music_sql = format!("SELECT * FROM musics WHERE music_id IN ({ids})");
+    let mut music_query = sqlx::query_as::<_, MusicRow>(&music_sql);
+    for id in music_ids.clone() {
+        music_query = music_query.bind(id);
+    }
+    let musics: Vec<MusicRow> = music_query.fetch_all(&app.database).await?;
 
-        let level_info = LevelInfo {
-            id: level_row.level.level_id,
-            name: level_row.level.name.into(),
-            hash: level_row.level.hash,
-            authors,
-        };
+    let music_authors_sql = format!("SELECT * FROM music_authors WHERE music_id IN ({ids})");
+    let mut music_authors_query = sqlx::query_as::<_, MusicAuthorRow>(&music_authors_sql);
+    for id in music_ids {
+        music_authors_query = music_authors_query.bind(id);
+    }
+    let music_authors: Vec<MusicAuthorRow> = music_authors_query.fetch_all(&app.database).await?;
+
+    let levels_sql = format!(
+        "SELECT * FROM levels WHERE enabled = TRUE AND level_set_id IN ({ids}) ORDER BY ord"
+    );
+    let mut levels_query = sqlx::query_as::<_, LevelRow>(&levels_sql);
+    for id in level_set_ids.clone() {
+        levels_query = levels_query.bind(id);
+    }
+    let levels: Vec<LevelRow> = levels_query.fetch_all(&app.database).await?;
+
+    let level_authors_sql = format!(
+        "SELECT level_authors.* FROM level_authors
+         JOIN levels ON level_authors.level_id = levels.level_id
+         WHERE levels.level_set_id IN ({ids})"
+    );
+    let mut level_authors_query = sqlx::query_as::<_, LevelAuthorRow>(&level_authors_sql);
+    for id in level_set_ids {
+        level_authors_query = level_authors_query.bind(id);
+    }
+    let level_authors: Vec<LevelAuthorRow> = level_authors_query.fetch_all(&app.database).await?;
 
-        let group_i = groups
-            .iter()
-            .position(|g| g.id == level_row.level.level_set_id)
-            .unwrap_or_else(|| {
-                groups.push(LevelSetInfo {
-                    id: level_row.level.level_set_id,
-                    music: music
+    let level_sets = level_sets
+        .into_iter()
+        .map(|row| {
+            let level_set = row.level_set;
+            let music = musics
+                .iter()
+                .find(|music| music.music_id == level_set.music_id)
+                .map(|music| MusicInfo {
+                    id: music.music_id,
+                    original: music.original,
+                    featured: music.featured,
+                    name: music.name.clone().into(),
+                    romanized: music.romanized_name.clone().into(),
+                    authors: music_authors
+                        .iter()
+                        .filter(|author| author.music_id == music.music_id)
+                        .map(|author| author.clone().into())
+                        .collect(),
+                })
+                .unwrap_or_default();
+            let levels = levels
+                .iter()
+                .filter(|level| level.level_set_id == level_set.level_set_id)
+                .map(|level| LevelInfo {
+                    id: level.level_id,
+                    name: level.name.clone().into(),
+                    hash: level.hash.clone(),
+                    authors: level_authors
                         .iter()
-                        .find(|music| music.id == level_row.group.music_id)
+                        .filter(|author| author.level_id == level.level_id)
                         .cloned()
-                        .unwrap_or_default(), // Default should never be reached TODO: warning or smth
-                    owner,
-                    levels: Vec::new(), // Populated after
-                    featured: level_row.group.featured,
-                    hash: level_row.group.hash,
-                });
-                groups.len() - 1
-            });
-        groups[group_i].levels.push(level_info);
+                        .map(MapperInfo::from)
+                        .collect(),
+                })
+                .collect();
+            LevelSetInfo {
+                id: level_set.level_set_id,
+                music,
+                owner: UserInfo {
+                    id: level_set.owner_id,
+                    name: row.username.into(),
+                },
+                levels,
+                featured: level_set.featured,
+                hash: level_set.hash,
+            }
+        })
+        .collect();
+
+    Ok(Json(level_sets))
+}
+
+#[derive(Deserialize)]
+struct RecommendedQuery {
+    id: Id,
+    /// Position in the list, appended to the end by default.
+    ord: Option<i64>,
+}
+
+/// Add the level set to the recommended list, or move it if it is already there.
+async fn recommended_add(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Query(query): Query<RecommendedQuery>,
+) -> Result<()> {
+    check_auth(&session, &app, AuthorityLevel::Admin).await?;
+    let mut trans = app.database.begin().await?;
+
+    let level_set_id = query.id;
+    let check =
+        sqlx::query("SELECT null FROM level_sets WHERE level_set_id = ? AND deleted_at IS NULL")
+            .bind(level_set_id)
+            .fetch_optional(&mut *trans)
+            .await?;
+    if check.is_none() {
+        return Err(RequestError::NoSuchLevelSet(level_set_id));
     }
 
-    Ok(Json(groups))
+    sqlx::query("DELETE FROM level_sets_recommended WHERE level_set_id = ?")
+        .bind(level_set_id)
+        .execute(&mut *trans)
+        .await?;
+
+    let ord = match query.ord {
+        Some(ord) => {
+            // Make space
+            sqlx::query("UPDATE level_sets_recommended SET ord = ord + 1 WHERE ord >= ?")
+                .bind(ord)
+                .execute(&mut *trans)
+                .await?;
+            ord
+        }
+        None => {
+            let last: Option<i64> =
+                sqlx::query_scalar("SELECT MAX(ord) FROM level_sets_recommended")
+                    .fetch_one(&mut *trans)
+                    .await?;
+            last.map_or(0, |last| last + 1)
+        }
+    };
+
+    sqlx::query(
+        "INSERT INTO level_sets_recommended (level_set_id, ord, added_at) VALUES (?, ?, ?)",
+    )
+    .bind(level_set_id)
+    .bind(ord)
+    .bind(OffsetDateTime::now_utc())
+    .execute(&mut *trans)
+    .await?;
+
+    trans.commit().await?;
+    Ok(())
+}
+
+async fn recommended_remove(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Query(query): Query<IdQuery>,
+) -> Result<()> {
+    check_auth(&session, &app, AuthorityLevel::Admin).await?;
+
+    sqlx::query("DELETE FROM level_sets_recommended WHERE level_set_id = ?")
+        .bind(query.id)
+        .execute(&app.database)
+        .await?;
+
+    Ok(())
+}
+
+/// Escape the wildcard characters of the `LIKE` pattern.
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
 }
 
 async fn level_set_get(
@@ -108,7 +302,7 @@ async fn level_set_get(
     Path(level_set_id): Path<Id>,
 ) -> Result<Json<LevelSetInfo>> {
     let group_row: Option<LevelSetRow> =
-        sqlx::query_as("SELECT * FROM level_sets WHERE level_set_id = ?")
+        sqlx::query_as("SELECT * FROM level_sets WHERE level_set_id = ? AND deleted_at IS NULL")
             .bind(level_set_id)
             .fetch_optional(&app.database)
             .await?;
@@ -205,6 +399,181 @@ async fn level_set_create(
     Ok(Json(level_set_id))
 }
 
+/// Fetch the level set and check that the user is allowed to manage it.
+async fn get_managed_level_set(
+    trans: &mut Transaction,
+    user: &User,
+    level_set_id: Id,
+) -> Result<LevelSetRow> {
+    let level_set: Option<LevelSetRow> =
+        sqlx::query_as("SELECT * FROM level_sets WHERE level_set_id = ? AND deleted_at IS NULL")
+            .bind(level_set_id)
+            .fetch_optional(&mut **trans)
+            .await?;
+    let level_set = level_set.ok_or(RequestError::NoSuchLevelSet(level_set_id))?;
+
+    let auth = get_user_auth(user, trans).await?;
+    if user.user_id != level_set.owner_id && auth != AuthorityLevel::Admin {
+        return Err(RequestError::Forbidden);
+    }
+    Ok(level_set)
+}
+
+async fn level_set_update(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(level_set_id): Path<Id>,
+    Json(update): Json<LevelSetUpdate>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let mut trans = app.database.begin().await?;
+    get_managed_level_set(&mut trans, user, level_set_id).await?;
+
+    if let Some(listed) = update.listed {
+        sqlx::query("UPDATE level_sets SET listed = ? WHERE level_set_id = ?")
+            .bind(listed)
+            .bind(level_set_id)
+            .execute(&mut *trans)
+            .await?;
+    }
+
+    trans.commit().await?;
+    Ok(())
+}
+
+/// Delete the level set, keeping the scores archived.
+/// Deleted level sets do not count towards the upload limit.
+async fn level_set_delete(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(level_set_id): Path<Id>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let mut trans = app.database.begin().await?;
+    get_managed_level_set(&mut trans, user, level_set_id).await?;
+
+    sqlx::query("UPDATE level_sets SET deleted_at = ? WHERE level_set_id = ?")
+        .bind(OffsetDateTime::now_utc())
+        .bind(level_set_id)
+        .execute(&mut *trans)
+        .await?;
+    sqlx::query("UPDATE levels SET enabled = 0 WHERE level_set_id = ?")
+        .bind(level_set_id)
+        .execute(&mut *trans)
+        .await?;
+    sqlx::query("DELETE FROM level_sets_recommended WHERE level_set_id = ?")
+        .bind(level_set_id)
+        .execute(&mut *trans)
+        .await?;
+    sqlx::query("DELETE FROM level_set_transfers WHERE level_set_id = ?")
+        .bind(level_set_id)
+        .execute(&mut *trans)
+        .await?;
+
+    trans.commit().await?;
+    Ok(())
+}
+
+/// Offer the ownership of the level set to another user.
+/// The ownership is only transferred once the new owner accepts it.
+async fn level_set_transfer(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(level_set_id): Path<Id>,
+    Query(new_owner): Query<IdQuery>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let mut trans = app.database.begin().await?;
+    let level_set = get_managed_level_set(&mut trans, user, level_set_id).await?;
+
+    let new_owner_id = new_owner.id;
+    if new_owner_id == level_set.owner_id {
+        return Err(RequestError::InvalidRequest);
+    }
+    let check = sqlx::query("SELECT null FROM users WHERE user_id = ?")
+        .bind(new_owner_id)
+        .fetch_optional(&mut *trans)
+        .await?;
+    if check.is_none() {
+        return Err(RequestError::NoSuchUser(new_owner_id));
+    }
+
+    // Replaces the previous offer, if any
+    sqlx::query(
+        "INSERT INTO level_set_transfers (level_set_id, new_owner_id, offered_at) VALUES (?, ?, ?)
+         ON CONFLICT(level_set_id) DO UPDATE SET new_owner_id = excluded.new_owner_id, offered_at = excluded.offered_at",
+    )
+    .bind(level_set_id)
+    .bind(new_owner_id)
+    .bind(OffsetDateTime::now_utc())
+    .execute(&mut *trans)
+    .await?;
+
+    trans.commit().await?;
+    Ok(())
+}
+
+/// Accept the ownership of the level set offered to the user.
+async fn level_set_transfer_accept(
+    session: AuthSession,
+    State(app): State<Arc<App>>,
+    Path(level_set_id): Path<Id>,
+) -> Result<()> {
+    let user = check_user(&session).await?;
+    let mut trans = app.database.begin().await?;
+
+    let offer = sqlx::query(
+        "SELECT null FROM level_set_transfers WHERE level_set_id = ? AND new_owner_id = ?",
+    )
+    .bind(level_set_id)
+    .bind(user.user_id)
+    .fetch_optional(&mut *trans)
+    .await?;
+    if offer.is_none() {
+        return Err(RequestError::Forbidden);
+    }
+
+    let level_set: Option<LevelSetRow> =
+        sqlx::query_as("SELECT * FROM level_sets WHERE level_set_id = ? AND deleted_at IS NULL")
+            .bind(level_set_id)
+            .fetch_optional(&mut *trans)
+            .await?;
+    let level_set = level_set.ok_or(RequestError::NoSuchLevelSet(level_set_id))?;
+
+    // Check the limits of the new owner
+    let is_admin = get_user_auth(user, &mut trans).await? == AuthorityLevel::Admin;
+    let owner_groups: Vec<LevelSetRow> =
+        sqlx::query_as("SELECT * FROM level_sets WHERE owner_id = ? AND deleted_at IS NULL")
+            .bind(user.user_id)
+            .fetch_all(&mut *trans)
+            .await?;
+    if !is_admin && owner_groups.len() >= LEVEL_SETS_PER_USER {
+        return Err(RequestError::TooManyGroups);
+    }
+    if !is_admin
+        && owner_groups
+            .iter()
+            .filter(|group| group.music_id == level_set.music_id)
+            .count()
+            >= LEVEL_SETS_PER_USER_PER_SONG
+    {
+        return Err(RequestError::TooManyGroupsForSong);
+    }
+
+    sqlx::query("UPDATE level_sets SET owner_id = ? WHERE level_set_id = ?")
+        .bind(user.user_id)
+        .bind(level_set_id)
+        .execute(&mut *trans)
+        .await?;
+    sqlx::query("DELETE FROM level_set_transfers WHERE level_set_id = ?")
+        .bind(level_set_id)
+        .execute(&mut *trans)
+        .await?;
+
+    trans.commit().await?;
+    Ok(())
+}
+
 async fn update_level_set(
     trans: &mut Transaction,
     config: &AppConfig,
@@ -216,7 +585,7 @@ async fn update_level_set(
 
     let level_set_id = parsed_level_set.meta.id;
     let level_set: Option<LevelSetRow> =
-        sqlx::query_as("SELECT * FROM level_sets WHERE level_set_id = ?")
+        sqlx::query_as("SELECT * FROM level_sets WHERE level_set_id = ? AND deleted_at IS NULL")
             .bind(level_set_id)
             .fetch_optional(&mut **trans)
             .await?;
@@ -264,11 +633,12 @@ async fn update_level_set(
     {
         let order = order as i64;
         level_meta.hash = level.calculate_hash(); // Make sure the hash is valid
+        let difficulty = rating::level_difficulty(level).as_f32();
         if level_meta.id == 0 {
             // Create a new level
             level_meta.id = sqlx::query_scalar(
-                "INSERT INTO levels (hash, level_set_id, enabled, name, ord, created_at)
-                 VALUES (?, ?, ?, ?, ?, ?) RETURNING level_id",
+                "INSERT INTO levels (hash, level_set_id, enabled, name, ord, created_at, difficulty)
+                 VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING level_id",
             )
             .bind(&level_meta.hash)
             .bind(level_set_id)
@@ -276,6 +646,7 @@ async fn update_level_set(
             .bind(level_meta.name.as_ref())
             .bind(order)
             .bind(OffsetDateTime::now_utc())
+            .bind(difficulty)
             .fetch_one(&mut **trans)
             .await?;
         } else {
@@ -295,11 +666,12 @@ async fn update_level_set(
 
                 // Update
                 sqlx::query(
-                "UPDATE levels SET hash = ?, name = ?, ord = ? WHERE level_id = ? AND level_set_id = ?",
+                "UPDATE levels SET hash = ?, name = ?, ord = ?, difficulty = ? WHERE level_id = ? AND level_set_id = ?",
                 )
                 .bind(&level_meta.hash)
                 .bind(level_meta.name.as_ref())
                 .bind(order)
+                .bind(difficulty)
                 .bind(level_meta.id)
                 .bind(level_set_id)
                 .execute(&mut **trans)
@@ -370,7 +742,7 @@ async fn new_level_set(
 
     // Check if the user already has level_sets
     let user_groups: Vec<LevelSetRow> =
-        sqlx::query_as("SELECT * FROM level_sets WHERE owner_id = ?")
+        sqlx::query_as("SELECT * FROM level_sets WHERE owner_id = ? AND deleted_at IS NULL")
             .bind(user.user_id)
             .fetch_all(&mut **trans)
             .await?;
@@ -432,8 +804,15 @@ async fn new_level_set(
     parsed_level_set.meta.id = level_set_id;
 
     // Create levels
-    for (order, level_meta) in parsed_level_set.meta.levels.iter_mut().enumerate() {
+    for ((order, level), level_meta) in parsed_level_set
+        .data
+        .levels
+        .iter()
+        .enumerate()
+        .zip(&mut parsed_level_set.meta.levels)
+    {
         let order = order as i64;
+        let difficulty = rating::level_difficulty(level).as_f32();
 
         // Check if such a level already exists
         let conflict = sqlx::query("SELECT null FROM levels WHERE hash = ?")
@@ -445,8 +824,8 @@ async fn new_level_set(
         }
 
         level_meta.id = sqlx::query_scalar(
-            "INSERT INTO levels (hash, level_set_id, enabled, name, ord, created_at)
-             VALUES (?, ?, ?, ?, ?, ?) RETURNING level_id",
+            "INSERT INTO levels (hash, level_set_id, enabled, name, ord, created_at, difficulty)
+             VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING level_id",
         )
         .bind(&level_meta.hash)
         .bind(level_set_id)
@@ -454,6 +833,7 @@ async fn new_level_set(
         .bind(level_meta.name.as_ref())
         .bind(order)
         .bind(current_time)
+        .bind(difficulty)
         .fetch_one(&mut **trans)
         .await?;
 
@@ -517,10 +897,11 @@ async fn download(
     State(app): State<Arc<App>>,
     Path(level_set_id): Path<Id>,
 ) -> Result<impl IntoResponse> {
-    let level_row = sqlx::query("SELECT null FROM level_sets WHERE level_set_id = ?")
-        .bind(level_set_id)
-        .fetch_optional(&app.database)
-        .await?;
+    let level_row =
+        sqlx::query("SELECT null FROM level_sets WHERE level_set_id = ? AND deleted_at IS NULL")
+            .bind(level_set_id)
+            .fetch_optional(&app.database)
+            .await?;
 
     if level_row.is_none() {
         return Err(RequestError::NoSuchLevelSet(level_set_id));
@@ -3,6 +3,7 @@ mod level;
 mod level_set;
 mod music;
 mod musicians;
+mod ranking;
 mod users;
 
 #[cfg(test)]
@@ -99,7 +100,8 @@ pub async fn run(
         .route("/", get(get_root))
         .merge(auth::router())
         .merge(users::router())
-        .merge(musicians::router());
+        .merge(musicians::router())
+        .merge(ranking::router());
 
     let router = music::route(router);
     let router = level_set::route(router);
@@ -0,0 +1,100 @@
+use super::*;
+
+use ctl_core::{
+    prelude::r32,
+    rating,
+    score::{PlayerRanking, RankingsQuery},
+};
+
+/// Maximum number of players returned in a single page of the ranking.
+const RANKINGS_PAGE_LIMIT: u32 = 100;
+
+pub fn router() -> Router {
+    Router::new().route("/rankings", get(rankings))
+}
+
+#[derive(sqlx::FromRow)]
+struct RankingRow {
+    rank: i64,
+    user_id: Id,
+    username: String,
+    rating: f32,
+    ranked_levels: i64,
+}
+
+async fn rankings(
+    State(app): State<Arc<App>>,
+    Query(query): Query<RankingsQuery>,
+) -> Result<Json<Vec<PlayerRanking>>> {
+    let limit = query
+        .limit
+        .unwrap_or(RANKINGS_PAGE_LIMIT)
+        .clamp(1, RANKINGS_PAGE_LIMIT);
+    let offset = i64::from(query.page) * i64::from(limit);
+
+    let rows: Vec<RankingRow> = sqlx::query_as(
+        "
+SELECT *
+FROM (
+    SELECT ROW_NUMBER() OVER (ORDER BY rating DESC, updated_at ASC) AS rank,
+        users.user_id, users.username, rating, ranked_levels
+    FROM player_ratings
+    JOIN users ON player_ratings.user_id = users.user_id
+    WHERE ranked_levels > 0
+)
+ORDER BY rank
+LIMIT ? OFFSET ?
+        ",
+    )
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&app.database)
+    .await?;
+
+    let rankings = rows
+        .into_iter()
+        .map(|row| PlayerRanking {
+            rank: row.rank as u32,
+            user: UserInfo {
+                id: row.user_id,
+                name: row.username.into(),
+            },
+            rating: r32(row.rating),
+            ranked_levels: row.ranked_levels as u32,
+        })
+        .collect();
+
+    Ok(Json(rankings))
+}
+
+/// Recalculate the rating of the user from their best performance on each level.
+pub(super) async fn update_player_rating(user_id: Id, trans: &mut Transaction) -> Result<()> {
+    let performances: Vec<f32> = sqlx::query_scalar(
+        "
+SELECT MAX(scores.performance)
+FROM scores
+JOIN levels ON scores.level_id = levels.level_id
+WHERE scores.user_id = ? AND levels.enabled = TRUE AND scores.performance > 0
+GROUP BY scores.level_id
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(&mut **trans)
+    .await?;
+
+    let ranked_levels = performances.len() as i64;
+    let rating = rating::player_rating(performances.into_iter().map(r32));
+
+    sqlx::query(
+        "INSERT INTO player_ratings (user_id, rating, ranked_levels, updated_at) VALUES (?, ?, ?, ?)
+         ON CONFLICT(user_id) DO UPDATE SET rating = excluded.rating, ranked_levels = excluded.ranked_levels, updated_at = excluded.updated_at",
+    )
+    .bind(user_id)
+    .bind(rating.as_f32())
+    .bind(ranked_levels)
+    .bind(OffsetDateTime::now_utc())
+    .execute(&mut **trans)
+    .await?;
+
+    Ok(())
+}
@@ -1,10 +1,126 @@
 use super::*;
 
+use ctl_core::{
+    prelude::ScoreGrade,
+    score::{ProfileScore, ScoreMeta, UserProfile},
+};
+
+/// Number of recent plays shown in the profile.
+const PROFILE_RECENT_PLAYS: i64 = 10;
+
 pub fn router() -> Router {
-    Router::new().route("/user/me", get(user_me))
+    Router::new()
+        .route("/user/me", get(user_me))
+        .route("/user/:user_id/profile", get(user_profile))
 }
 
 pub async fn user_me(session: AuthSession) -> Result<String> {
     let user = session.user.as_ref().ok_or(RequestError::Unathorized)?;
     Ok(user.username.clone())
 }
+
+#[derive(sqlx::FromRow)]
+struct ProfileScoreRow {
+    level_id: Id,
+    level_set_id: Id,
+    level_name: String,
+    music_name: String,
+    score: i32,
+    submitted_at: OffsetDateTime,
+    extra_info: Option<String>,
+}
+
+impl From<ProfileScoreRow> for ProfileScore {
+    fn from(row: ProfileScoreRow) -> Self {
+        Self {
+            level_id: row.level_id,
+            level_set_id: row.level_set_id,
+            level_name: row.level_name,
+            music_name: row.music_name,
+            score: row.score,
+            submitted_at: row.submitted_at,
+            meta: row.extra_info,
+        }
+    }
+}
+
+async fn user_profile(
+    State(app): State<Arc<App>>,
+    Path(user_id): Path<Id>,
+) -> Result<Json<UserProfile>> {
+    let user: Option<UserRow> =
+        sqlx::query_as("SELECT user_id, username FROM users WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&app.database)
+            .await?;
+    let Some(user) = user else {
+        return Err(RequestError::NoSuchUser(user_id));
+    };
+
+    let play_count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scores WHERE user_id = ?")
+        .bind(user_id)
+        .fetch_one(&app.database)
+        .await?;
+
+    let recent: Vec<ProfileScoreRow> = sqlx::query_as(
+        "
+SELECT scores.level_id, levels.level_set_id, levels.name AS level_name, musics.name AS music_name,
+    scores.score, scores.submitted_at, scores.extra_info
+FROM scores
+JOIN levels ON scores.level_id = levels.level_id
+JOIN level_sets ON levels.level_set_id = level_sets.level_set_id
+JOIN musics ON level_sets.music_id = musics.music_id
+WHERE scores.user_id = ?
+ORDER BY scores.submitted_at DESC
+LIMIT ?
+        ",
+    )
+    .bind(user_id)
+    .bind(PROFILE_RECENT_PLAYS)
+    .fetch_all(&app.database)
+    .await?;
+
+    let best: Vec<ProfileScoreRow> = sqlx::query_as(
+        "
+SELECT scores.level_id, levels.level_set_id, levels.name AS level_name, musics.name AS music_name,
+    scores.score, scores.submitted_at, scores.extra_info
+FROM (
+    SELECT scores.*, ROW_NUMBER() OVER (
+        PARTITION BY scores.level_id
+        ORDER BY scores.score DESC, scores.submitted_at ASC
+    ) AS level_rank
+    FROM scores
+    WHERE user_id = ?
+) AS scores
+JOIN levels ON scores.level_id = levels.level_id
+JOIN level_sets ON levels.level_set_id = level_sets.level_set_id
+JOIN musics ON level_sets.music_id = musics.music_id
+WHERE level_rank = 1
+ORDER BY scores.score DESC
+        ",
+    )
+    .bind(user_id)
+    .fetch_all(&app.database)
+    .await?;
+
+    // Grade distribution of the best scores
+    let mut grades = BTreeMap::<ScoreGrade, u64>::new();
+    for score in &best {
+        let Some(meta) = score
+            .extra_info
+            .as_deref()
+            .and_then(|meta| ron::from_str::<ScoreMeta>(meta).ok())
+        else {
+            continue;
+        };
+        *grades.entry(meta.calculate_grade()).or_default() += 1;
+    }
+
+    Ok(Json(UserProfile {
+        user: user.into(),
+        play_count: play_count as u64,
+        grades: grades.into_iter().collect(),
+        recent: recent.into_iter().map(Into::into).collect(),
+        best: best.into_iter().map(Into::into).collect(),
+    }))
+}
@@ -3,7 +3,7 @@ use super::*;
 use crate::{layout::AreaOps, util::ScrollState};
 
 use ctl_assets::Assets;
-use ctl_core::types::{Id, LevelSetInfo};
+use ctl_core::types::{Id, LevelSetInfo, LevelSetsQuery, LevelSetsSort};
 use ctl_local::{CacheState, LevelCache};
 
 #[derive(Debug, Clone, Copy)]
@@ -24,8 +24,12 @@ pub struct ExploreWidget {
 
     pub reload: IconButtonWidget,
     pub close: IconButtonWidget,
+    pub search: InputWidget,
+    pub sort: TextWidget,
 
     pub levels: ExploreLevelsWidget,
+    /// Search and sorting of the fetched level sets.
+    query: LevelSetsQuery,
     refetch: bool,
 }
 
@@ -36,6 +40,8 @@ pub struct ExploreLevelsWidget {
     pub scroll: ScrollState,
     pub items_state: WidgetState,
     pub items: Vec<LevelItemWidget>,
+    /// Fetches the next page of the list.
+    pub load_more: TextWidget,
 }
 
 pub struct LevelItemWidget {
@@ -58,9 +64,12 @@ impl ExploreWidget {
 
             reload: IconButtonWidget::new_normal(assets.atlas.reset()),
             close: IconButtonWidget::new_close_button(assets.atlas.button_close()),
+            search: InputWidget::new("Search"),
+            sort: TextWidget::new(sort_name(LevelSetsSort::default())),
 
             levels: ExploreLevelsWidget::new(assets),
 
+            query: LevelSetsQuery::default(),
             refetch: true,
         }
     }
@@ -94,7 +103,7 @@ impl StatefulWidget for ExploreWidget {
 
         #[cfg(feature = "online")]
         if std::mem::take(&mut self.refetch) {
-            state.fetch_groups();
+            state.fetch_groups(self.query.clone());
         }
 
         self.state.update(position, context);
@@ -103,7 +112,11 @@ impl StatefulWidget for ExploreWidget {
             self.hide();
         }
 
-        self.levels.load(&state.inner.borrow().group_list);
+        {
+            let inner = state.inner.borrow();
+            self.levels
+                .load(&inner.group_list, inner.group_list_complete);
+        }
 
         let mut main = position;
         main.cut_top(context.layout_size * 1.0);
@@ -125,6 +138,30 @@ impl StatefulWidget for ExploreWidget {
             self.window.request = Some(WidgetRequest::Close);
         }
 
+        main.cut_top(context.layout_size * 0.5);
+        let mut bar = main
+            .cut_top(context.font_size * 1.2)
+            .extend_symmetric(-vec2(1.0, 0.0) * context.layout_size);
+
+        let sort = bar.cut_right(context.layout_size * 8.0);
+        self.sort.update(sort, context);
+        if self.sort.state.mouse_left.clicked {
+            self.query.sort = next_sort(self.query.sort);
+            self.sort.text = sort_name(self.query.sort).into();
+            self.refetch = true;
+        }
+
+        bar.cut_right(context.layout_size * 2.0);
+        self.search.update(bar, context);
+        // Search once the user has finished typing
+        let search = Some(self.search.raw.trim())
+            .filter(|search| !search.is_empty())
+            .map(str::to_owned);
+        if !self.search.editing && search != self.query.search {
+            self.query.search = search;
+            self.refetch = true;
+        }
+
         let main = main.extend_uniform(-context.font_size * 0.5);
         let mut state = (state.clone(), None);
         self.levels.update(main, context, &mut state);
@@ -141,21 +178,30 @@ impl ExploreLevelsWidget {
             scroll: ScrollState::new(),
             items_state: WidgetState::new(),
             items: Vec::new(),
+            load_more: TextWidget::new("Load more"),
         }
     }
 
-    fn load(&mut self, groups: &CacheState<Vec<LevelSetInfo>>) {
+    fn load(&mut self, groups: &CacheState<Vec<LevelSetInfo>>, complete: bool) {
         self.status.show();
+        self.load_more.hide();
         match groups {
-            CacheState::Offline => self.status.text = "Offline :(".into(),
-            CacheState::Loading => self.status.text = "Loading...".into(),
+            CacheState::Offline => {
+                self.status.text = "Offline :(".into();
+                self.items.clear();
+            }
+            CacheState::Loading => {
+                self.status.text = "Loading...".into();
+                self.items.clear();
+            }
             CacheState::Loaded(groups) => {
+                self.items.truncate(groups.len());
                 if groups.is_empty() {
                     self.status.text = "Empty :(".into();
                 } else {
                     self.status.hide();
-                    if self.items.len() > groups.len() {
-                        self.items.drain(groups.len() + 1..self.items.len());
+                    if !complete {
+                        self.load_more.show();
                     }
                     self.items.extend((self.items.len()..groups.len()).map(|_| {
                         let mut widget = LevelItemWidget {
@@ -220,7 +266,7 @@ impl StatefulWidget for ExploreLevelsWidget {
             vec2(0.0, -row.height() - context.layout_size * 1.0),
             self.items.len(),
         );
-        let height = rows.last().map_or(0.0, |row| main.max.y - row.min.y);
+        let mut height = rows.last().map_or(0.0, |row| main.max.y - row.min.y);
 
         for (row, position) in self.items.iter_mut().zip(rows) {
             let mut state = (state.clone(), None);
@@ -230,6 +276,23 @@ impl StatefulWidget for ExploreLevelsWidget {
             }
         }
 
+        if self.load_more.state.visible {
+            let load_more = Aabb2::point(main.top_left() - vec2(0.0, height))
+                .extend_right(main.width())
+                .extend_down(context.font_size * 1.5)
+                .translate(vec2(0.0, -context.layout_size * 1.0));
+            let load_more = load_more.align_aabb(
+                vec2(main.width() * 0.5, load_more.height()),
+                vec2::splat(0.5),
+            );
+            self.load_more.update(load_more, context);
+            height = main.max.y - load_more.min.y;
+            #[cfg(feature = "online")]
+            if self.load_more.state.mouse_left.clicked {
+                state.fetch_more_groups();
+            }
+        }
+
         self.scroll
             .overflow(context.delta_time, height, main.height());
     }
@@ -325,3 +388,19 @@ impl StatefulWidget for LevelItemWidget {
         self.author.align(vec2(0.0, 1.0));
     }
 }
+
+fn sort_name(sort: LevelSetsSort) -> &'static str {
+    match sort {
+        LevelSetsSort::Newest => "Newest",
+        LevelSetsSort::Popularity => "Most played",
+        LevelSetsSort::Featured => "Featured",
+    }
+}
+
+fn next_sort(sort: LevelSetsSort) -> LevelSetsSort {
+    match sort {
+        LevelSetsSort::Newest => LevelSetsSort::Popularity,
+        LevelSetsSort::Popularity => LevelSetsSort::Featured,
+        LevelSetsSort::Featured => LevelSetsSort::Newest,
+    }
+}
@@ -24,6 +24,17 @@ pub enum Command {
         diff: String,
         start_time: Option<String>,
     },
+    /// Watch the replay of the best local score on the level.
+    Replay {
+        level: String,
+        diff: String,
+        /// Play back the replay from the file instead.
+        #[clap(long)]
+        file: Option<PathBuf>,
+        /// Save the replay into the file to share it.
+        #[clap(long)]
+        export: Option<PathBuf>,
+    },
     #[cfg(feature = "editor")]
     Edit {
         level: String,
@@ -49,6 +60,7 @@ pub enum Command {
     },
     Music(MusicArgs),
     Artist(ArtistArgs),
+    Recommended(RecommendedArgs),
 }
 
 #[derive(clap::Args)]
@@ -63,6 +75,12 @@ pub struct ArtistArgs {
     pub command: ArtistCommand,
 }
 
+#[derive(clap::Args)]
+pub struct RecommendedArgs {
+    #[command(subcommand)]
+    pub command: RecommendedCommand,
+}
+
 #[derive(clap::Subcommand)]
 pub enum MusicCommand {
     #[cfg(feature = "online")]
@@ -123,6 +141,21 @@ pub enum ArtistCommand {
     },
 }
 
+#[derive(clap::Subcommand)]
+pub enum RecommendedCommand {
+    /// Add the level set to the recommended list, or move it to a new position.
+    #[cfg(feature = "online")]
+    Add {
+        level_set: Id,
+        /// Position in the list, appended to the end by default.
+        #[clap(long)]
+        position: Option<i64>,
+    },
+    /// Remove the level set from the recommended list.
+    #[cfg(feature = "online")]
+    Remove { level_set: Id },
+}
+
 impl Command {
     pub async fn execute(
         self,
@@ -247,6 +280,71 @@ impl Command {
                 );
                 context.geng.run_state(state).await;
             }
+            Command::Replay {
+                level,
+                diff,
+                file,
+                export,
+            } => {
+                let ((group_index, group), (diff_index, diff)) = {
+                    let local = context.local.inner.borrow();
+                    let group = find_group(&local, &level)?;
+                    let diff = find_diff(&group.1, &diff)?;
+                    (group, diff)
+                };
+
+                let replay: ctl_core::replay::Replay = match file {
+                    Some(path) => cbor4ii::serde::from_reader(std::io::BufReader::new(
+                        std::fs::File::open(path)?,
+                    ))?,
+                    None => {
+                        let level_id = ctl_local::fs::LocalLevelId::from_info(&diff.meta);
+                        let scores = context.local.fs.load_local_scores(&level_id).await?;
+                        scores
+                            .into_iter()
+                            .filter(|score| score.replay.is_some())
+                            .max_by_key(|score| score.score)
+                            .and_then(|score| score.replay)
+                            .ok_or_else(|| anyhow!("No replays found for the level"))?
+                    }
+                };
+
+                if let Some(path) = export {
+                    let writer = std::io::BufWriter::new(std::fs::File::create(&path)?);
+                    cbor4ii::serde::to_writer(writer, &replay)?;
+                    println!("Saved the replay to {path:?}");
+                    return Ok(());
+                }
+
+                let level = ctl_logic::PlayLevel {
+                    music_offset: group.local.data.music_offset,
+                    start_time: replay.start_time,
+                    end_time: None,
+                    level: diff,
+                    group: ctl_logic::PlayGroup {
+                        group_index,
+                        music: group.local.music.clone(),
+                        cached: group,
+                    },
+                    level_index: diff_index,
+                    config: replay.config.clone(),
+                    transition_button: None,
+                };
+
+                let state = crate::game::Game::replay(
+                    context.clone(),
+                    level,
+                    ctl_local::Leaderboard::new(
+                        &context.geng,
+                        None,
+                        &context.local.fs,
+                        &context.achievements,
+                        true,
+                    ),
+                    replay,
+                );
+                context.geng.run_state(state).await;
+            }
             #[cfg(feature = "editor")]
             Command::Edit { level, diff } => {
                 let (group_index, group, level) = {
@@ -524,6 +622,33 @@ impl Command {
                     }
                 }
             }
+            Command::Recommended(recommended) => {
+                #[cfg(feature = "online")]
+                let client = init_client(secrets.as_ref())
+                    .await?
+                    .expect("Cannot update recommended levels without secrets");
+                match recommended.command {
+                    #[cfg(feature = "online")]
+                    RecommendedCommand::Add {
+                        level_set,
+                        position,
+                    } => {
+                        log::info!("Adding level set {level_set} to recommended at {position:?}");
+                        client
+                            .recommended_add(level_set, position)
+                            .await
+                            .context("when adding a recommended level set")?;
+                    }
+                    #[cfg(feature = "online")]
+                    RecommendedCommand::Remove { level_set } => {
+                        log::info!("Removing level set {level_set} from recommended");
+                        client
+                            .recommended_remove(level_set)
+                            .await
+                            .context("when removing a recommended level set")?;
+                    }
+                }
+            }
         }
 
         Ok(())
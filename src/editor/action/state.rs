@@ -43,6 +43,8 @@ impl EditorState {
                     Ok(group) => {
                         self.editor.group.music = group.local.music.clone();
                         self.editor.group.cached = group;
+                        self.editor.analyze_music();
+                        self.editor.detect_timing();
                     }
                 }
             }
@@ -358,7 +358,7 @@ impl EditorState {
                 } else {
                     // Look for lights to select
                     for (i, event) in level_editor.level.events.iter().enumerate() {
-                        if let Event::Light(light) = &event.event {
+                        if let Some(light) = event.event.as_light() {
                             let time = level_editor.current_time.target - event.time;
                             if time >= Time::ZERO && time <= light.movement.duration() {
                                 let baked = self.interpolation_cache.get_or_bake(&light.movement);
@@ -416,7 +416,7 @@ impl EditorState {
                     .events
                     .get(light_anchor.event)
                     .and_then(|event| {
-                        if let Event::Light(light) = &event.event {
+                        if let Some(light) = event.event.as_light() {
                             Some((event.time, light.movement.initial.transform.translation))
                         } else {
                             None
@@ -468,7 +468,7 @@ impl EditorState {
                     .events
                     .get(light.event)
                     .and_then(|event| {
-                        let Event::Light(light) = &event.event else {
+                        let Some(light) = event.event.as_light() else {
                             return None;
                         };
                         let waypoint = light.movement.get_frame(*anchor)?;
@@ -489,7 +489,7 @@ impl EditorState {
                             .iter()
                             .flat_map(|waypoint| {
                                 let event = level_editor.level.events.get(light.event)?;
-                                let Event::Light(light) = &event.event else {
+                                let Some(light) = event.event.as_light() else {
                                     return None;
                                 };
                                 let orig_time =
@@ -566,7 +566,7 @@ impl EditorState {
                     // Clicked on an light
                     let light_id = LightId { event };
                     if let Some(e) = level_editor.level.events.get(event)
-                        && let Event::Light(light) = &e.event
+                        && let Some(light) = e.event.as_light()
                     {
                         let light_anchor = light_id;
                         match button {
@@ -770,7 +770,7 @@ impl EditorState {
         let Some(level_editor) = &self.editor.level_edit else {
             return;
         };
-        let Event::Light(light_event) = &event.event else {
+        let Some(light_event) = event.event.as_light() else {
             return;
         };
         let Some(_frame) = light_event.movement.get_frame(waypoint_id) else {
@@ -860,7 +860,7 @@ impl EditorState {
                         .events
                         .get(waypoints.light.event)
                         .and_then(|event| {
-                            if let Event::Light(light) = &event.event {
+                            if let Some(light) = event.event.as_light() {
                                 Some(event.time + light.movement.get_time(waypoint_id)?)
                             } else {
                                 None
@@ -903,7 +903,7 @@ impl EditorState {
 
         if let Selection::Waypoints(light_id, waypoints) = &level_editor.selection
             && let Some(event) = level_editor.level.events.get(light_id.event)
-            && let Event::Light(light) = &event.event
+            && let Some(light) = event.event.as_light()
         {
             let scale = r32(waypoints.len() as f32).recip();
             let anchor = waypoints
@@ -935,7 +935,7 @@ impl EditorState {
                         .events
                         .get(selected.event)
                         .and_then(|event| {
-                            let Event::Light(light) = &event.event else {
+                            let Some(light) = event.event.as_light() else {
                                 return None;
                             };
                             let baked = self.interpolation_cache.get_or_bake(&light.movement);
@@ -135,12 +135,16 @@ impl EditorState {
                 .current_time
                 .snap_to(*start_target_time + seconds_to_time(*playing_time));
 
-            // Camera shake
-            level_editor.model.camera.center = level_editor.model.camera.center * 0.5
+            // Camera pan and shake
+            let pan = level_editor.model.vfx.camera_pan();
+            level_editor.model.camera.center = pan
+                + (level_editor.model.camera.center - pan) * 0.5
                 + Angle::from_degrees(thread_rng().gen_range(0.0..=360.0)).unit_vec()
                     * level_editor.model.vfx.camera_shake.as_f32();
+            level_editor.model.camera.rotation = level_editor.model.vfx.camera_rotation();
         } else {
             level_editor.model.camera.center = vec2::ZERO;
+            level_editor.model.camera.rotation = Angle::ZERO;
         }
 
         let include_cursor = !self.ui_focused
@@ -306,8 +310,13 @@ impl geng::State for EditorState {
         }
 
         if let Some(level_editor) = &mut self.editor.level_edit {
+            let effect_zoom = if let EditingState::Playing { .. } = level_editor.state {
+                level_editor.model.vfx.camera_zoom()
+            } else {
+                1.0
+            };
             level_editor.model.camera.fov =
-                geng::Camera2dFov::Vertical(10.0 / self.editor.view_zoom.current);
+                geng::Camera2dFov::Vertical(10.0 / self.editor.view_zoom.current / effect_zoom);
         }
 
         let buffer = &mut self
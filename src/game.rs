@@ -64,6 +64,19 @@ impl Game {
         )
     }
 
+    /// Watch the recorded replay of the level.
+    pub fn replay(
+        context: Context,
+        level: PlayLevel,
+        leaderboard: Leaderboard,
+        replay: ctl_core::replay::Replay,
+    ) -> Self {
+        Self::preloaded(
+            context.clone(),
+            Model::new_replay(context, level, leaderboard, replay),
+        )
+    }
+
     fn preloaded(context: Context, model: Model) -> Self {
         Self {
             framebuffer_size: vec2(1, 1),
@@ -75,7 +88,7 @@ impl Game {
             ui_context: UiContext::new(context.clone()),
 
             pause_player: {
-                let mut player = model.player.clone();
+                let mut player = model.sim.player.clone();
                 player.collider = Collider::new(player.collider.position, Shape::circle(r32(0.1)));
                 player
             },
@@ -154,7 +167,7 @@ impl geng::State for Game {
         let buffer = &mut self.post.begin(framebuffer.size(), theme.dark);
 
         let fading = self.model.restart_button.is_fading() || self.model.exit_button.is_fading();
-        let flashlight_mode = !self.model.state.ended();
+        let flashlight_mode = !self.model.sim.state.ended();
 
         self.render.draw_world(&self.model, self.debug_mode, buffer);
 
@@ -191,7 +204,7 @@ impl geng::State for Game {
                 self.model.vfx.spotlight.value.current.as_f32()
             };
             // Transition at start/end of level
-            let transition = match self.model.state {
+            let transition = match self.model.sim.state {
                 State::Starting { .. } => (self.model.switch_time.as_f32() / 1.5).clamp(0.0, 1.0),
                 State::Lost { .. } | State::Finished => {
                     (1.0 - self.model.switch_time.as_f32() / 1.5).clamp(0.0, 1.0)
@@ -224,7 +237,7 @@ impl geng::State for Game {
         if !self.model.level.config.modifiers.clean_auto && !flashlight_mode {
             let mut dither_buffer = self.render.dither.start();
             self.render.util.draw_player(
-                &self.model.player,
+                &self.model.sim.player,
                 &self.model.camera,
                 &mut dither_buffer,
             );
@@ -355,14 +368,14 @@ impl geng::State for Game {
 
         self.model.leaderboard.get_mut().poll();
         if let Some(player) = self.model.leaderboard.get_loaded().player {
-            self.model.player.info.id = player;
+            self.model.sim.player.info.id = player;
         }
 
         self.ui_context.update(delta_time.as_f32());
 
         if self.is_paused() {
             if let Some(PauseState::Normal { cursor_aligned }) = &mut self.pause_state {
-                *cursor_aligned = (self.model.player.collider.position
+                *cursor_aligned = (self.model.sim.player.collider.position
                     - self.pause_player.collider.position)
                     .len()
                     .as_f32()
@@ -382,7 +395,7 @@ impl geng::State for Game {
         if let Some(transition) = self.model.transition.take() {
             match transition {
                 Transition::LoadLeaderboard { submit_score } => {
-                    let score = &self.model.score;
+                    let score = &self.model.sim.score;
                     let raw_score = score.calculated.combined;
 
                     let mut meta = ctl_core::score::ScoreMeta::new(
@@ -391,7 +404,7 @@ impl geng::State for Game {
                         score.clone(),
                         self.model.current_completion(),
                     );
-                    meta.pauses = self.model.pauses.clone();
+                    meta.pauses = self.model.sim.pauses.clone();
 
                     self.model.leaderboard.get_mut().reload_submit(
                         Some(raw_score),
@@ -405,6 +418,7 @@ impl geng::State for Game {
                             .unwrap_or_default(),
                         self.model.level.level.meta.clone(),
                         meta,
+                        Some(self.model.recording.clone()),
                     );
                 }
                 Transition::Exit => self.transition = Some(geng::state::Transition::Pop),
@@ -418,13 +432,13 @@ impl geng::State for Game {
 
         if self.was_paused
             && !is_paused
-            && let State::Playing = self.model.state
+            && let State::Playing = self.model.sim.state
             && let Some(music) = &self.model.level.group.music
         {
             // Resume from pause
             self.context
                 .music
-                .play_from_time(music, self.model.play_time_ms, false);
+                .play_from_time(music, self.model.sim.play_time_ms, false);
         }
 
         let pos = self.ui_context.cursor.position;
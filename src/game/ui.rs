@@ -56,7 +56,7 @@ impl GameUI {
         // update!(self.ctl_logo, ctl_logo);
         let main = main.extend_up(-layout_size * 3.0);
 
-        if let State::Lost { .. } | State::Finished = model.state {
+        if let State::Lost { .. } | State::Finished = model.sim.state {
             self.leaderboard.show();
             self.score.show();
 
@@ -113,7 +113,7 @@ impl GameUI {
                     &ctl_core::score::ScoreMeta::new(
                         model.level.config.modifiers.clone(),
                         model.level.config.health.clone(),
-                        model.score.clone(),
+                        model.sim.score.clone(),
                         model.current_completion(),
                     ),
                     &model
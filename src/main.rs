@@ -15,10 +15,9 @@ mod util;
 
 use ctl_client::Nertboard;
 use ctl_context::Context;
+use ctl_core::simulation::FIXED_FPS;
 use geng::prelude::*;
 
-const FIXED_FPS: f64 = 60.0; // TODO: upgrade to 120 i think
-
 const DISCORD_SERVER_URL: &str = "https://discord.gg/Aq9bTvSbFN";
 
 #[derive(clap::Parser)]
@@ -164,12 +164,12 @@ impl geng::State for TrailerState {
                 .level
                 .data
                 .timing
-                .get_timing(model.play_time_ms)
+                .get_timing(model.sim.play_time_ms)
                 .beat_time;
 
             if !model.level.config.modifiers.sudden {
                 // Telegraphs
-                for tele in &model.level_state.telegraphs {
+                for tele in &model.sim.level_state.telegraphs {
                     let color = if tele.light.danger {
                         THEME.danger
                     } else {
@@ -187,7 +187,7 @@ impl geng::State for TrailerState {
 
             if !model.level.config.modifiers.hidden {
                 // Lights
-                for light in &model.level_state.lights {
+                for light in &model.sim.level_state.lights {
                     let color = if light.danger {
                         THEME.danger
                     } else {
@@ -206,7 +206,7 @@ impl geng::State for TrailerState {
 
             // Rhythm feedback
             for rhythm in &model.rhythms {
-                let color = if rhythm.perfect {
+                let color = if rhythm.judgement.is_hit() {
                     THEME.highlight
                 } else {
                     THEME.danger
@@ -235,9 +235,9 @@ impl geng::State for TrailerState {
                 let mut options = self.context.get_options();
                 options.cursor.inner_radius = 0.15 * t;
                 self.context.set_options(options);
-                model.player.collider.shape = Shape::circle(r32(0.5 * t));
+                model.sim.player.collider.shape = Shape::circle(r32(0.5 * t));
                 self.util_render
-                    .draw_player(&model.player, &model.camera, &mut dither_buffer);
+                    .draw_player(&model.sim.player, &model.camera, &mut dither_buffer);
             }
         }
 
@@ -290,6 +290,7 @@ impl geng::State for TrailerState {
                         lifetime: 0,
                         danger: false,
                         hollow: r32(-1.0),
+                        sustain: false,
                         event_id: None,
                         closest_waypoint: (100, WaypointId::Initial),
                     },
@@ -431,6 +432,7 @@ impl geng::State for TrailerState {
                     lifetime: 0,
                     danger: false,
                     hollow: r32(-1.0),
+                    sustain: false,
                     event_id: None,
                     closest_waypoint: (100, WaypointId::Initial),
                 },
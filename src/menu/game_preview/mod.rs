@@ -80,6 +80,8 @@ fn infinity_level() -> Level {
             time: 0,
             event: Event::Light(LightEvent {
                 danger: false,
+                group: None,
+                sustain: false,
                 shape: Shape::circle(r32(1.3)),
                 movement: Movement {
                     initial: WaypointInitial {
@@ -93,6 +95,8 @@ fn infinity_level() -> Level {
                             lerp_time,
                             interpolation,
                             change_curve: None,
+                            danger: None,
+                            shape: None,
                             transform: TransformLight {
                                 translation: vec2(-X_MID, Y).as_r32(),
                                 ..default()
@@ -102,6 +106,8 @@ fn infinity_level() -> Level {
                             lerp_time,
                             interpolation,
                             change_curve: Some(TrajectoryInterpolation::Bezier),
+                            danger: None,
+                            shape: None,
                             transform: TransformLight {
                                 translation: vec2(-X_MAX, 0.0).as_r32(),
                                 ..default()
@@ -111,6 +117,8 @@ fn infinity_level() -> Level {
                             lerp_time,
                             interpolation,
                             change_curve: None,
+                            danger: None,
+                            shape: None,
                             transform: TransformLight {
                                 translation: vec2(-X_MID, -Y).as_r32(),
                                 ..default()
@@ -120,12 +128,16 @@ fn infinity_level() -> Level {
                             lerp_time,
                             interpolation,
                             change_curve: Some(TrajectoryInterpolation::Bezier),
+                            danger: None,
+                            shape: None,
                             transform: TransformLight::default(),
                         },
                         Waypoint {
                             lerp_time,
                             interpolation,
                             change_curve: None,
+                            danger: None,
+                            shape: None,
                             transform: TransformLight {
                                 translation: vec2(X_MID, Y).as_r32(),
                                 ..default()
@@ -135,6 +147,8 @@ fn infinity_level() -> Level {
                             lerp_time,
                             interpolation,
                             change_curve: Some(TrajectoryInterpolation::Bezier),
+                            danger: None,
+                            shape: None,
                             transform: TransformLight {
                                 translation: vec2(X_MAX, 0.0).as_r32(),
                                 ..default()
@@ -144,6 +158,8 @@ fn infinity_level() -> Level {
                             lerp_time,
                             interpolation,
                             change_curve: None,
+                            danger: None,
+                            shape: None,
                             transform: TransformLight {
                                 translation: vec2(X_MID, -Y).as_r32(),
                                 ..default()
@@ -153,12 +169,16 @@ fn infinity_level() -> Level {
                             lerp_time,
                             interpolation,
                             change_curve: Some(TrajectoryInterpolation::Bezier),
+                            danger: None,
+                            shape: None,
                             transform: TransformLight::default(),
                         },
                         Waypoint {
                             lerp_time,
                             interpolation,
                             change_curve: None,
+                            danger: None,
+                            shape: None,
                             transform: TransformLight {
                                 translation: vec2(-X_MID, Y).as_r32(),
                                 ..default()
@@ -172,7 +192,9 @@ fn infinity_level() -> Level {
                     },
                 },
             }),
+            envelope: None,
         }],
         timing: Timing::new(bpm),
+        groups: Vec::new(),
     }
 }
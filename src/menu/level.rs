@@ -29,6 +29,8 @@ pub enum ConfirmAction {
     DownloadRecommended,
     #[cfg(feature = "online")]
     SyncUpload,
+    #[cfg(feature = "online")]
+    SyncDelete(Index),
     #[cfg(feature = "editor")]
     CreateLevel,
 }
@@ -209,6 +211,13 @@ impl MenuState {
                 }
             }
             #[cfg(feature = "online")]
+            ConfirmAction::SyncDelete(index) => {
+                self.context.local.delete_group_online(index);
+                if let Some(sync) = &mut ui.sync {
+                    sync.window.request = Some(WidgetRequest::Close);
+                }
+            }
+            #[cfg(feature = "online")]
             ConfirmAction::DownloadRecommended => {
                 self.context.local.download_recommended();
                 self.notifications
@@ -487,6 +496,7 @@ impl LevelMenu {
                     .unwrap_or_default(),
                 level.meta.clone(),
                 meta,
+                None,
             );
         }
     }
@@ -71,6 +71,16 @@ impl ModButtonWidget {
             if self.state.mouse_left.clicked {
                 *value = if self.selected { None } else { Some(mode) }
             }
+        } else if let Modifier::Health(preset) = self.modifier {
+            self.selected = state.config.health.preset() == Some(preset);
+            if self.state.mouse_left.clicked {
+                let preset = if self.selected {
+                    HealthPreset::Normal
+                } else {
+                    preset
+                };
+                state.config.health = preset.config();
+            }
         }
     }
 }
@@ -95,6 +105,11 @@ impl ModifiersWidget {
                     Modifier::LightMode(LightMode::Flashlight),
                     Modifier::LightMode(LightMode::Spotlight),
                 ],
+                vec![
+                    Modifier::Health(HealthPreset::Lenient),
+                    Modifier::Health(HealthPreset::Strict),
+                    Modifier::Health(HealthPreset::SuddenDeath),
+                ],
             ]
             .into_iter()
             .map(|row| {
@@ -115,9 +130,8 @@ impl ModifiersWidget {
         // Active mods
         if state.selected_diff.is_some() {
             self.active_mods = state
-                .config
-                .modifiers
-                .iter()
+                .get_category()
+                .modifiers()
                 .map(|modifier| IconWidget::new(context.context.assets.get_modifier(modifier)))
                 .collect();
             let mods = head.translate(vec2(0.0, head.height()));
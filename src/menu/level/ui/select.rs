@@ -2,6 +2,7 @@ use super::*;
 
 use crate::ui::UiWindow;
 
+use ctl_core::difficulty::LevelDifficulty;
 use ctl_local::{CachedGroup, SavedScore, fs::LocalLevelId};
 use ctl_ui::util::ScrollState;
 
@@ -33,6 +34,8 @@ pub struct LevelSelectUI {
     pub diffs_area: WidgetState,
     pub diffs_scroll: ScrollState,
     pub diffs: Vec<ItemDiffWidget>,
+    /// Star ratings of the analyzed levels by their hash.
+    difficulties: HashMap<String, R32>,
 
     pub no_diffs: TextWidget,
     pub no_level_selected: TextWidget,
@@ -108,6 +111,7 @@ impl LevelSelectUI {
             diffs_area: WidgetState::new(),
             diffs_scroll: ScrollState::new(),
             diffs: Vec::new(),
+            difficulties: HashMap::new(),
 
             no_diffs: TextWidget::new("Create a Difficulty in the editor"),
             no_level_selected: TextWidget::new("Select a level\n<-"),
@@ -392,7 +396,7 @@ impl LevelSelectUI {
             self.no_level_selected.hide();
         }
 
-        let levels: Vec<_> = group_idx
+        let mut levels: Vec<_> = group_idx
             .and_then(|group| local.groups.get(group))
             .map(|group| {
                 group
@@ -408,11 +412,21 @@ impl LevelSelectUI {
             })
             .into_iter()
             .flatten()
+            .enumerate()
+            .map(|(level_id, level)| {
+                let stars = *self
+                    .difficulties
+                    .entry(level.meta.hash.clone())
+                    .or_insert_with(|| LevelDifficulty::analyze(&level.data).stars);
+                (level_id, level, stars)
+            })
             .collect();
+        // Easiest difficulties first
+        levels.sort_by_key(|&(_, _, stars)| stars);
 
         // Synchronize vec length
         if self.diffs.len() != levels.len() {
-            if let Some(cached) = levels.first() {
+            if let Some((_, cached, _)) = levels.first() {
                 self.diffs = vec![
                     ItemDiffWidget::new(
                         &self.assets,
@@ -433,7 +447,7 @@ impl LevelSelectUI {
 
         // Synchronize data
         let loaded = state.leaderboard.get_loaded();
-        for (widget, (level_id, cached)) in self.diffs.iter_mut().zip(levels.iter().enumerate()) {
+        for (widget, &(level_id, ref cached, stars)) in self.diffs.iter_mut().zip(&levels) {
             let origin_hash = group.origin.as_ref().and_then(|info| {
                 info.levels
                     .iter()
@@ -457,6 +471,7 @@ impl LevelSelectUI {
                 level_id,
                 &group,
                 cached,
+                stars,
                 local_score,
                 edited,
                 context,
@@ -670,6 +685,7 @@ pub struct ItemDiffWidget {
     pub edited: IconWidget,
     pub local: IconWidget,
     pub text: TextWidget,
+    pub stars: TextWidget,
     pub group: Index,
     pub index: usize,
     pub level: LevelFull,
@@ -694,6 +710,7 @@ impl ItemDiffWidget {
             edited: IconWidget::new(assets.atlas.star()),
             local: IconWidget::new(assets.atlas.local()),
             text: TextWidget::new(text).aligned(vec2(0.5, 0.5)),
+            stars: TextWidget::new("").aligned(vec2(1.0, 0.5)),
             group,
             index,
             level,
@@ -709,6 +726,7 @@ impl ItemDiffWidget {
         level_index: usize,
         group: &CachedGroup,
         cached: &LevelFull,
+        stars: R32,
         local_highscore: Option<&SavedScore>,
         edited: bool,
         context: &UiContext,
@@ -725,6 +743,7 @@ impl ItemDiffWidget {
         self.group = group_idx;
         self.level = cached.clone();
         self.text.text = cached.meta.name.clone();
+        self.stars.text = format!("{:.1}*", stars.as_f32()).into();
         if cached.meta.id == 0 {
             self.local.show();
             self.edited.hide();
@@ -784,6 +803,14 @@ impl ItemDiffWidget {
             .translate(vec2(position.height(), 0.0));
         self.grade.update(grade, context);
 
+        let stars = position
+            .align_aabb(
+                vec2(position.height() * 1.5, position.height()),
+                vec2(0.0, 0.5),
+            )
+            .translate(vec2(-position.height() * 1.75, 0.0));
+        self.stars.update(stars, &context.scale_font(0.7));
+
         let widgets = [&mut self.edited, &mut self.local];
         if widgets.iter().any(|widget| widget.state.visible) {
             let icons = position
@@ -82,20 +82,17 @@ impl EditorRender {
 
             if let Some(event_id) = event_id {
                 let check = |a: Option<usize>| -> bool { a == Some(event_id) };
-                let base_color =
-                    if level_editor
-                        .level
-                        .events
-                        .get(event_id)
-                        .is_some_and(|e| match &e.event {
-                            Event::Light(event) => event.danger,
-                            _ => false,
-                        })
-                    {
-                        danger_color
-                    } else {
-                        light_color
-                    };
+                let base_color = if level_editor
+                    .level
+                    .events
+                    .get(event_id)
+                    .and_then(|e| e.event.as_light())
+                    .is_some_and(|event| event.danger)
+                {
+                    danger_color
+                } else {
+                    light_color
+                };
                 let mod_color = if !editor.show_only_selected
                     && level_editor
                         .selection
@@ -279,8 +276,9 @@ impl EditorRender {
         for light_id in lights_movement_preview {
             if let Some(timed_event) = level_editor.level.events.get(light_id.event) {
                 let visibility = |beat| visibility(timed_event, beat);
+                let pivot = level_editor.group_pivot(light_id);
 
-                if let Event::Light(event) = &timed_event.event {
+                if let Some(event) = timed_event.event.as_light() {
                     let color = if event.danger {
                         THEME.danger
                     } else {
@@ -315,7 +313,12 @@ impl EditorRender {
                                 let t = seconds_to_time(t) + event.movement.get_fade_in();
                                 let alpha = visibility(t);
                                 draw2d::ColoredVertex {
-                                    a_pos: event.movement.get_baked(t, baked).translation.as_f32(),
+                                    a_pos: event
+                                        .movement
+                                        .get_baked(t, baked)
+                                        .relative_to(&pivot)
+                                        .translation
+                                        .as_f32(),
                                     a_color: crate::util::with_alpha(color, alpha),
                                 }
                             })
@@ -337,7 +340,7 @@ impl EditorRender {
             if let Some(timed_event) = level_editor.level.events.get(light_id.event) {
                 let visibility = |beat| visibility(timed_event, beat);
 
-                if let Event::Light(event) = &timed_event.event {
+                if let Some(event) = timed_event.event.as_light() {
                     let color = if event.danger {
                         THEME.danger
                     } else {
@@ -375,7 +378,7 @@ impl EditorRender {
                                     .events
                                     .get(waypoints.light.event)
                                     .and_then(|event| {
-                                        if let Event::Light(light) = &event.event {
+                                        if let Some(light) = event.event.as_light() {
                                             let beat = light.movement.get_time(i)?;
                                             alpha = visibility(beat);
                                             return Some((event.time, beat));
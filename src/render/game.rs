@@ -65,7 +65,7 @@ impl GameRender {
             );
             ugli::clear(framebuffer, Some(Color::TRANSPARENT_BLACK), None, None);
             self.util
-                .draw_level_sdf(&model.level_state, &model.camera, framebuffer);
+                .draw_level_sdf(&model.sim.level_state, &model.camera, framebuffer);
         }
         {
             // Cursor SDF
@@ -75,7 +75,7 @@ impl GameRender {
             );
             ugli::clear(framebuffer, Some(Color::TRANSPARENT_BLACK), None, None);
             self.util.draw_light_sdf(
-                &Collider::circle(model.player.collider.position, r32(1.0)),
+                &Collider::circle(model.sim.player.collider.position, r32(1.0)),
                 r32(-1.0),
                 THEME.light,
                 &model.camera,
@@ -90,12 +90,12 @@ impl GameRender {
             .level
             .data
             .timing
-            .get_timing(model.play_time_ms)
+            .get_timing(model.sim.play_time_ms)
             .beat_time;
 
         if !model.level.config.modifiers.hidden {
             // Lights
-            for light in &model.level_state.lights {
+            for light in &model.sim.level_state.lights {
                 let color = if light.danger {
                     THEME.danger
                 } else {
@@ -118,7 +118,7 @@ impl GameRender {
         }
 
         let fading = model.restart_button.is_fading() || model.exit_button.is_fading();
-        let end_screen = matches!(model.state, State::Lost { .. } | State::Finished);
+        let end_screen = matches!(model.sim.state, State::Lost { .. } | State::Finished);
         if end_screen {
             for (button, text) in [
                 (&model.restart_button, "RESTART"),
@@ -146,7 +146,7 @@ impl GameRender {
         if options.cursor.show_rhythm_circles {
             // Rhythm feedback
             for rhythm in &model.rhythms {
-                let color = if rhythm.perfect {
+                let color = if rhythm.judgement.is_hit() {
                     if options.cursor.show_rhythm_only_miss {
                         continue;
                     }
@@ -164,19 +164,32 @@ impl GameRender {
                 visual.position = rhythm.position;
                 self.util
                     .draw_outline(&visual, 0.05, color, camera, &mut framebuffer);
+
+                if let Some(offset) = rhythm.offset
+                    && rhythm.judgement != Judgement::Perfect
+                {
+                    let text = if offset < 0 { "EARLY" } else { "LATE" };
+                    self.util.draw_text(
+                        text,
+                        rhythm.position + vec2(0.0, 0.8).as_r32(),
+                        TextRenderOptions::new(0.4).color(color),
+                        camera,
+                        &mut framebuffer,
+                    );
+                }
             }
         }
 
-        if !model.level.config.modifiers.clean_auto && !model.state.ended() {
+        if !model.level.config.modifiers.clean_auto && !model.sim.state.ended() {
             self.util
-                .draw_player(&model.player, camera, &mut framebuffer);
+                .draw_player(&model.sim.player, camera, &mut framebuffer);
         }
 
         if !fading {
             let t = (model.switch_time.as_f32() / 2.0).min(1.0);
             // let t = crate::util::smoothstep(t);
             let color = THEME.light.map_rgb(|x| x * t);
-            match model.state {
+            match model.sim.state {
                 State::Starting { .. } | State::Playing => {}
                 State::Lost { .. } => {
                     self.util.draw_text(
@@ -229,7 +242,7 @@ impl GameRender {
         if !model.level.config.modifiers.sudden {
             // Telegraphs
             // NOTE: non-dithered to apply different coloring options
-            for tele in &model.level_state.telegraphs {
+            for tele in &model.sim.level_state.telegraphs {
                 let mut color = if tele.light.danger {
                     theme.danger
                 } else {
@@ -241,7 +254,7 @@ impl GameRender {
             }
             // Waypoints
             // TODO: config
-            // for waypoint in &model.level_state.waypoints {
+            // for waypoint in &model.sim.level_state.waypoints {
             //     let color = if waypoint.light.danger {
             //         THEME.danger
             //     } else {
@@ -283,21 +296,21 @@ impl GameRender {
         let theme = options.theme.swap(model.vfx.palette_swap.current.as_f32());
 
         // Draw player health bar
-        if let State::Playing = model.state
+        if let State::Playing = model.sim.state
             && !model.level.config.modifiers.clean_auto
         {
             self.util.draw_health(
-                &model.player.health,
-                model.player.get_lit_state(),
+                &model.sim.player.health,
+                model.sim.player.get_lit_state(),
                 theme,
                 framebuffer,
             );
         }
 
-        let accuracy = model.score.calculated.accuracy.as_f32() * 100.0;
-        // let precision = model.score.calculated.precision.as_f32() * 100.0;
+        let accuracy = model.sim.score.calculated.accuracy.as_f32() * 100.0;
+        // let precision = model.sim.score.calculated.precision.as_f32() * 100.0;
 
-        if let State::Lost { .. } | State::Finished = model.state {
+        if let State::Lost { .. } | State::Finished = model.sim.state {
         } else if !model.level.config.modifiers.clean_auto {
             // Score
             self.util.draw_text(
@@ -310,7 +323,7 @@ impl GameRender {
                 framebuffer,
             );
             self.util.draw_text(
-                format!("{}", model.score.calculated.combined),
+                format!("{}", model.sim.score.calculated.combined),
                 vec2(-8.5, 4.3).as_r32(),
                 TextRenderOptions::new(0.8)
                     .color(theme.light)
@@ -343,7 +356,7 @@ impl GameRender {
             // {
             // // Rank letter
             //     let completion = model.current_completion();
-            //     let grade = model.score.calculate_grade(completion);
+            //     let grade = model.sim.score.calculate_grade(completion);
             //     let grade_texture = self.context.assets.get_grade(grade);
 
             //     let rank_world_pos = vec2(-6.0, 4.0);
@@ -382,7 +395,7 @@ impl GameRender {
 
             if debug_mode {
                 self.util.draw_text(
-                    format!("{:#?}", model.score),
+                    format!("{:#?}", model.sim.score),
                     vec2(-7.0, 0.0).as_r32(),
                     TextRenderOptions::new(0.7)
                         .color(theme.light)
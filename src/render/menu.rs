@@ -294,6 +294,8 @@ impl MenuRender {
                     .draw_toggle(&sync.upload, self.font_size * 0.2, theme, framebuffer);
                 self.ui
                     .draw_toggle(&sync.discard, self.font_size * 0.2, theme, framebuffer);
+                self.ui
+                    .draw_toggle(&sync.delete, self.font_size * 0.2, theme, framebuffer);
 
                 self.ui.draw_text(&sync.response, framebuffer);
             },
@@ -415,6 +417,7 @@ impl MenuRender {
                     );
 
                     self.ui.draw_icon(&diff.grade, theme, &mut masking.color);
+                    self.ui.draw_text(&diff.stars, &mut masking.color);
                 }
 
                 self.masked2
@@ -609,6 +612,9 @@ impl MenuRender {
             |framebuffer| {
                 self.ui.draw_icon(&ui.reload.icon, theme, framebuffer);
                 self.ui.draw_icon(&ui.close.icon, theme, framebuffer);
+                self.ui.draw_input_widget(&ui.search, theme, framebuffer);
+                self.ui
+                    .draw_toggle(&ui.sort, self.font_size * 0.2, theme, framebuffer);
 
                 let mut mask = self.masked2.start();
 
@@ -633,6 +639,14 @@ impl MenuRender {
                             &mut mask.color,
                         );
                     }
+                    if ui.levels.load_more.state.visible {
+                        self.ui.draw_toggle(
+                            &ui.levels.load_more,
+                            self.font_size * 0.2,
+                            theme,
+                            &mut mask.color,
+                        );
+                    }
                 }
 
                 self.masked2.draw(draw_parameters(), framebuffer);
@@ -25,6 +25,9 @@ pub struct PostVfx {
     pub curvature: f32,
     pub rgb_split: f32,
     pub noise_offset: f32,
+    pub flash: f32,
+    pub tint: f32,
+    pub tint_color: TintColor,
     pub colors: GraphicsColorsOptions,
 }
 
@@ -37,6 +40,9 @@ impl PostVfx {
             curvature: vfx.curvature.value.current.as_f32(),
             rgb_split: vfx.rgb_split.value.current.as_f32(),
             noise_offset: vfx.noise_offset.value.current.as_f32(),
+            flash: vfx.flash.value.current.as_f32(),
+            tint: vfx.tint.value.current.as_f32(),
+            tint_color: vfx.tint_color,
             colors,
         }
     }
@@ -49,6 +55,9 @@ impl PostVfx {
             curvature: 0.0,
             rgb_split: 0.0,
             noise_offset: 0.0,
+            flash: 0.0,
+            tint: 0.0,
+            tint_color: TintColor::Highlight,
             colors,
         }
     }
@@ -179,6 +188,10 @@ impl PostRender {
                     u_texture: texture,
                     u_saturation: vfx.colors.saturation,
                     u_blue: vfx.colors.blue,
+                    u_flash: vfx.flash.clamp(0.0, 1.0),
+                    u_flash_color: options.theme.light,
+                    u_tint: vfx.tint.clamp(0.0, 1.0),
+                    u_tint_color: options.theme.get_color(vfx.tint_color.into()),
                 },
                 ugli::DrawParameters::default(),
             );
@@ -583,6 +583,35 @@ impl UiRender {
         self.draw_text(&score.accuracy_text, framebuffer);
         self.draw_text(&score.precision_value, framebuffer);
         self.draw_text(&score.precision_text, framebuffer);
+        self.draw_text(&score.sustain, framebuffer);
+
+        self.draw_text(&score.judgements, framebuffer);
+        self.draw_text(&score.hit_error_text, framebuffer);
+        if score.hit_error.visible {
+            // Hit error histogram, early on the left and late on the right
+            let histogram = &metrics.timing.histogram;
+            let max = histogram.iter().copied().max().unwrap_or(0).max(1) as f32;
+            let position = score.hit_error.position;
+            let bin_width = position.width() / histogram.len().max(1) as f32;
+            let center = histogram.len() / 2;
+            for (i, &count) in histogram.iter().enumerate() {
+                let height = count as f32 / max * position.height();
+                let color = if i == center {
+                    theme.highlight
+                } else {
+                    theme.light
+                };
+                let bin = Aabb2::point(position.bottom_left() + vec2(i as f32 * bin_width, 0.0))
+                    .extend_positive(vec2(bin_width * 0.8, height));
+                self.draw_quad(bin, color, framebuffer);
+            }
+            self.draw_quad(
+                Aabb2::point(position.bottom_left())
+                    .extend_positive(vec2(position.width(), outline_width)),
+                theme.light,
+                framebuffer,
+            );
+        }
 
         self.draw_outline(
             score.state.position.extend_uniform(outline_width),
@@ -842,6 +871,8 @@ impl UiRender {
         let logged = &ui.logged;
         if logged.state.visible {
             self.draw_text(&logged.username, framebuffer);
+            self.draw_text(&logged.stats, framebuffer);
+            self.draw_text(&logged.recent, framebuffer);
             self.draw_button(&logged.logout, theme, framebuffer);
         }
 
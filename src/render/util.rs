@@ -249,11 +249,7 @@ impl UtilRender {
             return;
         }
 
-        let shape = match light.collider.shape {
-            Shape::Circle { .. } => Shape::circle(radius),
-            Shape::Line { .. } => Shape::line(radius / r32(2.0)),
-            Shape::Rectangle { .. } => Shape::rectangle(vec2::splat(radius)),
-        };
+        let shape = light.collider.shape.waypoint_marker(radius);
         let waypoint = Collider {
             shape,
             ..light.collider
@@ -286,6 +282,21 @@ impl UtilRender {
                 &self.context.assets.sprites.square_gradient,
                 mat3::scale(vec2(width.as_f32(), height.as_f32()) / 2.0),
             ),
+            Shape::Ellipse { width, height } => (
+                &self.context.assets.sprites.radial_gradient,
+                mat3::scale(vec2(width.as_f32(), height.as_f32()) / 2.0),
+            ),
+            Shape::Polygon { .. } | Shape::Arc { .. } | Shape::Convex { .. } => {
+                self.draw_light_mesh(
+                    collider,
+                    hollow_cut,
+                    color,
+                    blend_additive(),
+                    camera,
+                    framebuffer,
+                );
+                return;
+            }
         };
         // let texture = &*texture.texture;
         let transform = mat3::translate(collider.position.as_f32())
@@ -357,6 +368,22 @@ impl UtilRender {
                 &self.context.assets.sprites.square_gradient,
                 mat3::scale(vec2(width.as_f32(), height.as_f32()) / 2.0),
             ),
+            Shape::Ellipse { width, height } => (
+                &self.context.assets.sprites.radial_gradient,
+                mat3::scale(vec2(width.as_f32(), height.as_f32()) / 2.0),
+            ),
+            Shape::Polygon { .. } | Shape::Arc { .. } | Shape::Convex { .. } => {
+                // NOTE: no padding for the distance field outside the shape
+                self.draw_light_mesh(
+                    collider,
+                    hollow_cut,
+                    color,
+                    blend_max(),
+                    camera,
+                    framebuffer,
+                );
+                return;
+            }
         };
         // let texture = &*texture.texture;
         let transform = mat3::translate(collider.position.as_f32())
@@ -386,6 +413,78 @@ impl UtilRender {
         );
     }
 
+    /// Draw the light gradient of a polygon or an arc using the linear gradient texture.
+    /// The texture coordinates go from the middle of the light to its edges.
+    fn draw_light_mesh(
+        &self,
+        collider: &Collider,
+        hollow_cut: f32,
+        color: Color,
+        blend_mode: ugli::BlendMode,
+        camera: &impl geng::AbstractCamera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        let vertex = |a_pos: vec2<Coord>, edge: f32| draw2d::TexturedVertex {
+            a_pos: a_pos.as_f32(),
+            a_color: Color::WHITE,
+            a_vt: vec2(0.5, 0.5 + edge * 0.5),
+        };
+
+        let mut vertices = Vec::new();
+        if let Some(points) = collider.shape.polygon_points() {
+            // Triangle fan from the center
+            for (&a, &b) in points.iter().circular_tuple_windows() {
+                vertices.extend([vertex(vec2::ZERO, 0.0), vertex(a, 1.0), vertex(b, 1.0)]);
+            }
+        } else if let Shape::Arc { width, .. } = collider.shape
+            && let Some(points) = collider.shape.arc_points()
+        {
+            // Strip along the middle line
+            let half_width = width / r32(2.0);
+            let offset = |p: vec2<Coord>| p.as_f32().normalize_or_zero().as_r32() * half_width;
+            for (&a, &b) in points.iter().tuple_windows() {
+                let (da, db) = (offset(a), offset(b));
+                for (a_side, b_side, edge) in [(a + da, b + db, 1.0), (a - da, b - db, -1.0)] {
+                    vertices.extend([
+                        vertex(a, 0.0),
+                        vertex(a_side, edge),
+                        vertex(b_side, edge),
+                        vertex(a, 0.0),
+                        vertex(b_side, edge),
+                        vertex(b, 0.0),
+                    ]);
+                }
+            }
+        }
+        if vertices.is_empty() {
+            return;
+        }
+
+        let transform = mat3::translate(collider.position.as_f32())
+            * mat3::rotate(collider.rotation.map(Coord::as_f32));
+        let framebuffer_size = framebuffer.size();
+        ugli::draw(
+            framebuffer,
+            &self.context.assets.shaders.light,
+            ugli::DrawMode::Triangles,
+            &ugli::VertexBuffer::new_dynamic(self.context.geng.ugli(), vertices),
+            (
+                ugli::uniforms! {
+                    u_model_matrix: transform,
+                    u_color: color,
+                    u_texture: &self.context.assets.sprites.linear_gradient,
+                    u_hollow_cut: hollow_cut,
+                    u_sdf_pad: 0.0,
+                },
+                camera.uniforms(framebuffer_size.as_f32()),
+            ),
+            ugli::DrawParameters {
+                blend_mode: Some(blend_mode),
+                ..default()
+            },
+        );
+    }
+
     fn circle_with_cut(
         &self,
         framebuffer: &mut ugli::Framebuffer,
@@ -539,7 +638,85 @@ impl UtilRender {
                     .translate(collider.position.as_f32()),
                 );
             }
+            Shape::Polygon { .. } | Shape::Convex { .. } | Shape::Ellipse { .. } => {
+                let points: Vec<vec2<f32>> = collider
+                    .shape
+                    .polygon_points()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|p| {
+                        let p = p.as_f32();
+                        p - p.normalize_or_zero() * outline_width / 2.0
+                    })
+                    .collect();
+                self.draw_closed_chain(collider, points, outline_width, color, camera, framebuffer);
+            }
+            Shape::Arc { width, angle, .. } => {
+                let mut points = collider.shape.arc_points().unwrap_or_default();
+                let full = angle >= r32(360.0);
+                if full {
+                    // The last point is the same as the first one
+                    points.pop();
+                }
+                let half_width = width.as_f32() / 2.0 - outline_width / 2.0;
+                let side = |sign: f32| {
+                    points.iter().map(move |p| {
+                        let p = p.as_f32();
+                        p + p.normalize_or_zero() * half_width * sign
+                    })
+                };
+                if full {
+                    // Two separate circles
+                    for sign in [-1.0, 1.0] {
+                        self.draw_closed_chain(
+                            collider,
+                            side(sign).collect(),
+                            outline_width,
+                            color,
+                            camera,
+                            framebuffer,
+                        );
+                    }
+                } else {
+                    let points = side(1.0).chain(side(-1.0).rev()).collect();
+                    self.draw_closed_chain(
+                        collider,
+                        points,
+                        outline_width,
+                        color,
+                        camera,
+                        framebuffer,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Draw a closed outline through the points in the local coordinates of the collider.
+    fn draw_closed_chain(
+        &self,
+        collider: &Collider,
+        mut points: Vec<vec2<f32>>,
+        outline_width: f32,
+        color: Rgba<f32>,
+        camera: &impl geng::AbstractCamera2d,
+        framebuffer: &mut ugli::Framebuffer,
+    ) {
+        if points.len() < 2 {
+            return;
         }
+        // Start in the middle of an edge to avoid a sharp joint
+        let m = (points[0] + points[1]) / 2.0;
+        points.rotate_left(1);
+        points.insert(0, m);
+        points.push(m);
+        self.draw_chain(
+            framebuffer,
+            camera,
+            &draw2d::Chain::new(Chain::new(points), outline_width, color, 1)
+                .rotate(collider.rotation.map(Coord::as_f32))
+                .translate(collider.position.as_f32()),
+        );
     }
 
     pub fn draw_button(
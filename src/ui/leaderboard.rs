@@ -65,6 +65,7 @@ impl LeaderboardWidget {
                     },
                     score: 0,
                     meta: ctl_core::score::ScoreMeta::default(),
+                    replay: None,
                 },
                 false,
             ),
@@ -116,6 +117,7 @@ impl LeaderboardWidget {
                     user: entry.user.clone(),
                     score: entry.score.score(),
                     meta: entry.score.clone(),
+                    replay: None,
                 };
                 LeaderboardEntryWidget::new(
                     &self.assets,
@@ -247,8 +249,7 @@ impl LeaderboardEntryWidget {
         let modifiers = score
             .meta
             .category
-            .mods
-            .iter()
+            .modifiers()
             .map(|modifier| IconWidget::new(assets.get_modifier(modifier)))
             .collect();
 
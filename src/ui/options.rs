@@ -7,11 +7,13 @@ use crate::{
 };
 
 use ctl_assets::{CursorOptions, GameplayOptions, GraphicsOptions};
+use ctl_core::replay::MAX_USER_MUSIC_OFFSET;
 use ctl_ui::util::ScrollState;
 use geng_utils::bounded::Bounded;
 
 const RANGE_VOLUME: RangeInclusive<f32> = 0.0..=100.0;
-const RANGE_MUSIC_OFFSET: RangeInclusive<f32> = -100.0..=100.0;
+const RANGE_MUSIC_OFFSET: RangeInclusive<f32> =
+    -(MAX_USER_MUSIC_OFFSET as f32)..=MAX_USER_MUSIC_OFFSET as f32;
 const RANGE_BLUE: RangeInclusive<f32> = 0.5..=1.0;
 const RANGE_SATURATION: RangeInclusive<f32> = 0.0..=1.0;
 const RANGE_TELEGRAPH_BRIGHTNESS: RangeInclusive<f32> = 0.25..=1.0;
@@ -27,6 +27,8 @@ pub struct RegisterWidget {
 pub struct LoggedWidget {
     pub state: WidgetState,
     pub username: TextWidget,
+    pub stats: TextWidget,
+    pub recent: TextWidget,
     pub logout: ButtonWidget,
 }
 
@@ -51,6 +53,8 @@ impl ProfileWidget {
             logged: LoggedWidget {
                 state: WidgetState::new(),
                 username: TextWidget::new("<username>"),
+                stats: TextWidget::new(""),
+                recent: TextWidget::new(""),
                 logout: ButtonWidget::new("Logout"),
             },
         }
@@ -214,13 +218,39 @@ impl StatefulWidget for LoggedWidget {
         if let Some(user) = &*state.get_user() {
             self.username.text = user.name.clone();
         }
+        if let Some(profile) = &state.get().profile {
+            let grades = profile
+                .grades
+                .iter()
+                .rev()
+                .map(|(grade, count)| format!("{grade:?}: {count}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            self.stats.text = format!(
+                "Plays: {}  Levels: {}\n{}",
+                profile.play_count,
+                profile.best.len(),
+                grades
+            )
+            .into();
+            self.recent.text = profile
+                .recent
+                .iter()
+                .take(3)
+                .map(|play| format!("{} - {}: {}", play.music_name, play.level_name, play.score))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .into();
+        }
 
         let main = position;
 
-        let rows = main.split_rows(2);
+        let rows = main.split_rows(4);
         self.username.update(rows[0], context);
+        self.stats.update(rows[1], &context.scale_font(0.6));
+        self.recent.update(rows[2], &context.scale_font(0.6));
 
-        self.logout.update(rows[1], context);
+        self.logout.update(rows[3], context);
         #[cfg(feature = "online")]
         if self.logout.text.state.mouse_left.clicked {
             state.get_mut().logout();
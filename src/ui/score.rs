@@ -25,6 +25,10 @@ pub struct ScoreWidget {
     pub precision_bar: WidgetState,
     pub precision_value: TextWidget,
     pub precision_text: TextWidget,
+    pub sustain: TextWidget,
+    pub judgements: TextWidget,
+    pub hit_error: WidgetState,
+    pub hit_error_text: TextWidget,
 }
 
 impl ScoreWidget {
@@ -50,6 +54,10 @@ impl ScoreWidget {
             precision_bar: WidgetState::new(),
             precision_value: TextWidget::new("99.99%"),
             precision_text: TextWidget::new("Precision"),
+            sustain: TextWidget::new("Held: 100.00%  Breaks: 0"),
+            judgements: TextWidget::new("Perfect 0  Great 0  Ok 0  Miss 0"),
+            hit_error: WidgetState::new(),
+            hit_error_text: TextWidget::new("On time"),
         }
     }
 
@@ -65,8 +73,7 @@ impl ScoreWidget {
         self.difficulty_name.text = level.name.clone();
         self.modifiers = score
             .category
-            .mods
-            .iter()
+            .modifiers()
             .map(|modifier| {
                 let mut icon = IconWidget::new(self.assets.get_modifier(modifier));
                 icon.color = ThemeColor::Danger;
@@ -94,6 +101,41 @@ impl ScoreWidget {
             format!("{:.2}%", score.score.calculated.accuracy.as_f32() * 100.0).into();
         self.precision_value.text =
             format!("{:.2}%", score.score.calculated.precision.as_f32() * 100.0).into();
+
+        let timing = &score.score.metrics.timing;
+        if timing.hits() + timing.miss == 0 {
+            // Scores without timing information
+            self.judgements.hide();
+            self.hit_error.hide();
+            self.hit_error_text.hide();
+        } else {
+            self.judgements.show();
+            self.hit_error.show();
+            self.hit_error_text.show();
+            self.judgements.text = format!(
+                "Perfect {}  Great {}  Ok {}  Miss {}",
+                timing.perfect, timing.great, timing.ok, timing.miss
+            )
+            .into();
+            self.hit_error_text.text = match timing.mean_offset() {
+                Some(offset) if offset < 0 => format!("Avg: {}ms early", -offset).into(),
+                Some(offset) if offset > 0 => format!("Avg: {}ms late", offset).into(),
+                _ => "Avg: on time".into(),
+            };
+        }
+
+        let sustain = &score.score.metrics.sustain;
+        if sustain.is_empty() {
+            self.sustain.hide();
+        } else {
+            self.sustain.show();
+            self.sustain.text = format!(
+                "Held: {:.2}%  Breaks: {}",
+                sustain.held().as_f32() * 100.0,
+                sustain.breaks
+            )
+            .into();
+        }
     }
 }
 
@@ -154,6 +196,21 @@ impl WidgetOld for ScoreWidget {
         let completion = main.cut_top(context.font_size * 1.1);
         self.completion.update(completion, &context.scale_font(0.8));
 
+        if self.sustain.state.visible {
+            let sustain = main.cut_bottom(context.font_size * 1.0);
+            self.sustain.update(sustain, &context.scale_font(0.8));
+        }
+        if self.judgements.state.visible {
+            let text = main.cut_bottom(context.font_size * 1.0);
+            self.hit_error_text.update(text, &context.scale_font(0.8));
+            let histogram = main.cut_bottom(context.font_size * 1.5);
+            self.hit_error
+                .update(histogram.with_width(context.font_size * 8.0, 0.5), context);
+            let text = main.cut_bottom(context.font_size * 1.0);
+            self.judgements.update(text, &context.scale_font(0.8));
+            main.cut_bottom(context.font_size * 0.2);
+        }
+
         let columns = main.split_columns(2);
         let mut acc_col = columns[0];
         let mut prec_col = columns[1];
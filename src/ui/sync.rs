@@ -33,6 +33,7 @@ pub struct SyncWidget {
     pub status: TextWidget,
     pub upload: TextWidget,
     pub discard: TextWidget,
+    pub delete: TextWidget,
     pub response: TextWidget,
 
     task_group_info: TaskRes<LevelSetInfo>,
@@ -64,6 +65,7 @@ impl SyncWidget {
             status: TextWidget::new("Offline"),
             upload: TextWidget::new("Upload to the server"),
             discard: TextWidget::new("Download new version"),
+            delete: TextWidget::new("Delete from the server"),
             response: TextWidget::new(""),
 
             task_group_info: None,
@@ -72,6 +74,7 @@ impl SyncWidget {
         };
         sync.upload.hide();
         sync.discard.hide();
+        sync.delete.hide();
         sync
     }
 
@@ -167,6 +170,7 @@ impl StatefulWidget for SyncWidget {
                 self.response.hide();
                 self.upload.show();
                 self.discard.hide();
+                self.delete.hide();
             } else {
                 let future = async move { client.get_group_info(group_id).await };
                 self.task_group_info = Some(Task::new(&self.geng, future));
@@ -183,15 +187,25 @@ impl StatefulWidget for SyncWidget {
                         self.response.hide();
                         self.upload.show();
                         self.discard.show();
+                        self.delete.hide();
                     } else {
                         self.status.text = "Failed".into();
                         self.response.show();
                         self.response.text = format!("{err}").into();
                         self.upload.hide();
                         self.discard.hide();
+                        self.delete.hide();
                     }
                 }
                 Ok(Ok(group)) => {
+                    // Only the owner can delete the level from the server
+                    let owner = state.leaderboard.get().user.as_ref().map(|user| user.id);
+                    if owner == Some(group.owner.id) {
+                        self.delete.show();
+                    } else {
+                        self.delete.hide();
+                    }
+
                     if group != self.cached_group.local.meta {
                         // Local level version is probably outdated (or invalid)
                         self.status.text = "Outdated".into();
@@ -365,6 +379,20 @@ impl StatefulWidget for SyncWidget {
             }
         }
 
+        let delete = main
+            .cut_top(context.font_size * 1.5)
+            .align_aabb(button_size, vec2::splat(0.5));
+        self.delete.update(delete, context);
+        if self.delete.state.mouse_left.clicked {
+            state.popup_confirm(
+                ConfirmAction::SyncDelete(self.cached_group_index),
+                "delete the level from the server, the local copy and the scores are kept",
+                "delete",
+                ThemeColor::Danger,
+                "cancel",
+            );
+        }
+
         main.cut_top(context.layout_size * 1.0);
 
         let response = main.cut_top(context.font_size);